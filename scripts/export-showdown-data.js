@@ -0,0 +1,201 @@
+#!/usr/bin/env node
+// Regenerates data/*.json from Pokémon Showdown's own dex, in the shapes src/data/mod.rs reads.
+//
+//   just data
+//
+// or by hand:
+//
+//   npm install --no-save --prefix target/showdown pokemon-showdown
+//   NODE_PATH=target/showdown/node_modules node scripts/export-showdown-data.js data
+//
+// Writes pokedex.json, moves.json, items.json, abilities.json, learnsets.json, formats-data.json
+// and manifest.json. formats.json (format rules) is maintained by hand and left alone.
+
+'use strict';
+
+const fs = require('fs');
+const path = require('path');
+const {Dex} = require('pokemon-showdown');
+
+const LATEST_GEN = 9;
+// Fan-made and side-game entries that no cartridge format allows
+const EXCLUDED = new Set(['CAP', 'Custom', 'LGPE', 'Future']);
+
+const outDir = process.argv[2] || path.join(__dirname, '..', 'data');
+const gens = Array.from({length: LATEST_GEN}, (_, i) => i + 1);
+const dexes = Object.fromEntries(gens.map(g => [g, Dex.forGen(g)]));
+
+const included = entry => entry.exists && !EXCLUDED.has(entry.isNonstandard);
+const same = (a, b) => JSON.stringify(a) === JSON.stringify(b);
+
+function write(name, data) {
+	const file = path.join(outDir, name);
+	fs.writeFileSync(file, JSON.stringify(data, null, 1) + '\n');
+	console.log(`${file}: ${Object.keys(data).length} entries`);
+}
+
+// Drops undefined, null, empty strings and empty arrays so the JSON only holds real values
+function compact(object) {
+	const out = {};
+	for (const [key, value] of Object.entries(object)) {
+		if (value === undefined || value === null || value === '') continue;
+		if (Array.isArray(value) && !value.length) continue;
+		out[key] = value;
+	}
+	return out;
+}
+
+// Types, stats and abilities each generation changed, keyed by the last generation the older
+// values apply to (the `past` field of src/data/mod.rs `Species`)
+function pastSpecies(id, introduced) {
+	const past = {};
+	for (let g = LATEST_GEN - 1; g >= Math.max(introduced, 1); g--) {
+		const then = dexes[g].species.get(id);
+		const next = dexes[g + 1].species.get(id);
+		if (!then.exists || !next.exists) continue;
+		const changed = {};
+		if (!same(then.types, next.types)) changed.types = then.types;
+		if (!same(then.baseStats, next.baseStats)) changed.baseStats = then.baseStats;
+		if (g >= 3 && !same(then.abilities, next.abilities)) changed.abilities = then.abilities;
+		if (Object.keys(changed).length) past[g] = changed;
+	}
+	return past;
+}
+
+function exportPokedex() {
+	const pokedex = {};
+	for (const species of Dex.species.all()) {
+		if (!included(species)) continue;
+		const past = pastSpecies(species.id, species.gen);
+		pokedex[species.id] = compact({
+			num: species.num,
+			name: species.name,
+			types: species.types,
+			baseStats: species.baseStats,
+			abilities: species.abilities,
+			weightkg: species.weightkg,
+			gen: species.gen,
+			baseSpecies: species.baseSpecies !== species.name ? species.baseSpecies : undefined,
+			forme: species.forme,
+			changesFrom: species.changesFrom,
+			// Necrozma-Ultra can come from either fusion, the first is enough to name one
+			battleOnly: [].concat(species.battleOnly || [])[0],
+			requiredItem: species.requiredItems && species.requiredItems.length > 1 ?
+				undefined : species.requiredItem,
+			requiredMove: species.requiredMove,
+			requiredAbility: species.requiredAbility,
+			prevo: species.prevo,
+			evos: species.evos,
+			otherFormes: species.otherFormes,
+			cosmeticFormes: species.cosmeticFormes,
+			past: Object.keys(past).length ? past : undefined,
+		});
+	}
+	return pokedex;
+}
+
+// The last generation a move that has since been cut was usable in
+function lastGen(table, id, introduced) {
+	if (!dexes[LATEST_GEN][table].get(id).isNonstandard) return undefined;
+	for (let g = LATEST_GEN - 1; g >= introduced; g--) {
+		const entry = dexes[g][table].get(id);
+		if (entry.exists && !entry.isNonstandard) return g;
+	}
+	return undefined;
+}
+
+function exportMoves() {
+	const moves = {};
+	for (const move of Dex.moves.all()) {
+		if (!included(move)) continue;
+		// Functions (the battle callbacks) are dropped by the JSON round trip
+		const data = JSON.parse(JSON.stringify(Dex.data.Moves[move.id]));
+		delete data.isNonstandard;
+		moves[move.id] = compact({
+			...data,
+			gen: move.gen,
+			lastGen: lastGen('moves', move.id, move.gen),
+			shortDesc: move.shortDesc,
+		});
+	}
+	return moves;
+}
+
+function exportItems() {
+	const items = {};
+	for (const item of Dex.items.all()) {
+		if (!included(item)) continue;
+		const data = JSON.parse(JSON.stringify(Dex.data.Items[item.id]));
+		delete data.isNonstandard;
+		// Newer Showdown versions key stones with several Megas by the species they evolve
+		let {megaStone, megaEvolves} = data;
+		if (megaStone && typeof megaStone === 'object') {
+			[megaEvolves, megaStone] = Object.entries(megaStone)[0];
+		}
+		items[item.id] = compact({
+			...data,
+			megaStone,
+			megaEvolves,
+			gen: item.gen,
+			shortDesc: item.shortDesc,
+		});
+	}
+	return items;
+}
+
+function exportAbilities() {
+	const abilities = {};
+	for (const ability of Dex.abilities.all()) {
+		if (!included(ability) || ability.id === 'noability') continue;
+		abilities[ability.id] = {
+			num: ability.num,
+			name: ability.name,
+			gen: ability.gen,
+			shortDesc: ability.shortDesc,
+		};
+	}
+	return abilities;
+}
+
+function exportLearnsets(pokedex) {
+	const learnsets = {};
+	for (const [id, entry] of Object.entries(Dex.data.Learnsets)) {
+		if (pokedex[id] && entry.learnset) learnsets[id] = {learnset: entry.learnset};
+	}
+	return learnsets;
+}
+
+// Tier and availability of every species in every generation, as `tier` and `isNonstandard`
+function exportFormatsData() {
+	const formatsData = {};
+	for (const g of gens) {
+		const table = {};
+		for (const species of dexes[g].species.all()) {
+			if (!included(species) || species.gen > g) continue;
+			table[species.id] = compact({
+				tier: species.tier,
+				isNonstandard: species.isNonstandard,
+			});
+		}
+		formatsData[`gen${g}`] = table;
+	}
+	return formatsData;
+}
+
+const pokedex = exportPokedex();
+write('pokedex.json', pokedex);
+write('moves.json', exportMoves());
+write('items.json', exportItems());
+write('abilities.json', exportAbilities());
+write('learnsets.json', exportLearnsets(pokedex));
+write('formats-data.json', exportFormatsData());
+
+// The package's `exports` hide package.json, so find it next to the entry point
+let packageDir = path.dirname(require.resolve('pokemon-showdown'));
+while (!fs.existsSync(path.join(packageDir, 'package.json'))) packageDir = path.dirname(packageDir);
+const {version} = JSON.parse(fs.readFileSync(path.join(packageDir, 'package.json'), 'utf8'));
+write('manifest.json', {
+	complete: true,
+	source: `pokemon-showdown ${version}`,
+	generated: new Date().toISOString().slice(0, 10),
+});
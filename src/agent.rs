@@ -1,4 +1,5 @@
-use crate::BattleEvents;
+use crate::analysis::classify_team;
+use crate::{BattleEvents, Team};
 #[allow(unused)]
 use anyhow::Result;
 use colored::Colorize;
@@ -24,6 +25,28 @@ RESPONSE FORMAT:\n\
 Action: [specific move/switch]\n\
 Reason: [why in 1-2 sentences]";
 
+/// Likely opposing sets, inferred from usage data and what the battle has revealed so far
+fn opponent_predictions(events: &BattleEvents) -> String {
+    if events.opponents.beliefs().next().is_none() {
+        return String::new();
+    }
+    let speed = events
+        .opponents
+        .active_speed()
+        .map(|s| format!("Speed: {}\n", s))
+        .unwrap_or_default();
+    let damage: String = events
+        .opponents
+        .active_damage()
+        .iter()
+        .map(|d| format!("Damage: {}\n", d))
+        .collect();
+    format!(
+        "Opponent set predictions:\n{}\n{}{}\n",
+        events.opponents, speed, damage
+    )
+}
+
 pub enum ModelType {
     Local,
     Cloud,
@@ -72,6 +95,23 @@ impl BattleAgent {
         })
     }
 
+    /// Gives the agent the player's own team, its archetype and each member's role
+    pub fn add_team_context(&mut self, team: &Team) {
+        let context = format!(
+            "My team:\n{}\n\n{}",
+            team.serialize().trim(),
+            classify_team(team)
+        );
+        self.history.push(Message {
+            role: USER,
+            content: Some(context),
+            multi_content: None,
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        });
+    }
+
     pub async fn get_initial_suggestions(&mut self, events: BattleEvents) -> String {
         println!("\n{}\n", "Generating initial suggestions...".yellow());
 
@@ -94,6 +134,7 @@ impl BattleAgent {
         );
         prompt.push_str(&team_match_up);
         prompt.push('\n');
+        prompt.push_str(&opponent_predictions(&events));
 
         let question = "Which Pokemon should lead with and why?";
         prompt.push_str(question);
@@ -124,6 +165,7 @@ impl BattleAgent {
         );
         prompt.push_str(&team_match_up);
         prompt.push('\n');
+        prompt.push_str(&opponent_predictions(&events));
 
         let question = "Which Pokemon should lead with and why?";
         prompt.push_str(question);
@@ -150,6 +192,7 @@ impl BattleAgent {
             prompt.push_str(&turn_text);
             prompt.push('\n');
         }
+        prompt.push_str(&opponent_predictions(&events));
 
         let question = "Based on the current battle state, what is the optimal move or switch?";
         prompt.push_str(question);
@@ -174,6 +217,7 @@ impl BattleAgent {
             prompt.push_str(&turn_text);
             prompt.push('\n');
         }
+        prompt.push_str(&opponent_predictions(&events));
 
         let question = "Based on the current battle state, what is the optimal move or switch?";
         prompt.push_str(question);
@@ -0,0 +1,329 @@
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::{Pokemon, Team};
+use crate::types::{Matchup, Type, type_effectiveness_in};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// How one team member takes hits from each attacking type
+#[derive(Debug, Clone)]
+pub struct MemberMatchup {
+    pub name: String,
+    pub types: Vec<Type>,
+    pub ability: Option<String>,
+    /// Damage multiplier taken from each attacking type that exists in the generation
+    pub multipliers: Vec<(Type, f32)>,
+    /// Types of the member's damaging moves, after abilities like Pixilate
+    pub attacking_types: Vec<Type>,
+}
+
+impl MemberMatchup {
+    pub fn multiplier(&self, attack: Type) -> f32 {
+        self.multipliers
+            .iter()
+            .find(|(t, _)| *t == attack)
+            .map(|(_, m)| *m)
+            .unwrap_or(1.0)
+    }
+
+    pub fn weaknesses(&self) -> Vec<(Type, f32)> {
+        self.filter(|m| m > 1.0)
+    }
+
+    pub fn resistances(&self) -> Vec<(Type, f32)> {
+        self.filter(|m| m > 0.0 && m < 1.0)
+    }
+
+    pub fn immunities(&self) -> Vec<Type> {
+        self.filter(|m| m == 0.0)
+            .into_iter()
+            .map(|(t, _)| t)
+            .collect()
+    }
+
+    fn filter(&self, keep: impl Fn(f32) -> bool) -> Vec<(Type, f32)> {
+        self.multipliers
+            .iter()
+            .copied()
+            .filter(|(_, m)| keep(*m))
+            .collect()
+    }
+}
+
+/// An attacking type several team members are weak to
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedWeakness {
+    pub r#type: Type,
+    pub weak: usize,
+    pub resist: usize,
+}
+
+/// Defensive and offensive type analysis of a whole team
+#[derive(Debug, Clone)]
+pub struct TeamAnalysis {
+    /// Generation whose typings and type chart were used
+    pub r#gen: u8,
+    pub members: Vec<MemberMatchup>,
+    /// Team members whose species isn't in the dex, left out of the analysis
+    pub unknown: Vec<String>,
+    /// Types at least two members are weak to, worst first
+    pub shared_weaknesses: Vec<SharedWeakness>,
+    /// Best multiplier the team's moves get against each single type
+    pub coverage: Vec<(Type, f32)>,
+    /// Single types no move on the team hits super effectively
+    pub uncovered: Vec<Type>,
+    /// Pairs of attacking types no team member resists both of
+    pub unresisted_combos: Vec<(Type, Type)>,
+    /// Type combinations that resist every attacking type on the team
+    pub walled_by: Vec<Vec<Type>>,
+}
+
+/// Damage multiplier a Pokémon with `types` and `ability` takes from an `attack` type move in
+/// `gen`, counting abilities that grant immunities (see [`Matchup`]) or cut damage
+pub fn defensive_multiplier(r#gen: u8, types: &[Type], ability: Option<&str>, attack: Type) -> f32 {
+    let matchup = Matchup {
+        defender_ability: ability.map(str::to_string),
+        ..Matchup::new(r#gen)
+    };
+    let base = matchup.multiplier(attack, types);
+    let Some(ability) = ability.map(to_id) else {
+        return base;
+    };
+
+    match (ability.as_str(), attack) {
+        ("thickfat", Type::Fire | Type::Ice)
+        | ("heatproof" | "waterbubble", Type::Fire)
+        | ("purifyingsalt", Type::Ghost) => base * 0.5,
+        ("dryskin", Type::Fire) => base * 1.25,
+        _ => base,
+    }
+}
+
+/// Type of a damaging move as used by this Pokémon, `None` for status moves
+fn attacking_type(dex: &Dex, pkmn: &Pokemon, move_name: &str) -> Option<Type> {
+    let mv = dex.get_move(move_name)?;
+    if mv.category == "Status" {
+        return None;
+    }
+    // "Hidden Power [Ice]" carries its type in the name
+    if let Some(start) = move_name.find('[')
+        && let Some(end) = move_name.find(']')
+    {
+        return move_name[start + 1..end].parse().ok();
+    }
+    let r#type: Type = mv.r#type.parse().ok()?;
+    let converted = match pkmn.ability.as_deref().map(to_id).as_deref() {
+        Some("pixilate") => Some(Type::Fairy),
+        Some("aerilate") => Some(Type::Flying),
+        Some("refrigerate") => Some(Type::Ice),
+        Some("galvanize") => Some(Type::Electric),
+        _ => None,
+    };
+    match converted {
+        Some(converted) if r#type == Type::Normal => Some(converted),
+        _ => Some(r#type),
+    }
+}
+
+/// Every single and dual type combination of `types`
+fn type_combinations(types: &[Type]) -> Vec<Vec<Type>> {
+    let mut combos = Vec::new();
+    for (i, &a) in types.iter().enumerate() {
+        combos.push(vec![a]);
+        for &b in &types[i + 1..] {
+            combos.push(vec![a, b]);
+        }
+    }
+    combos
+}
+
+/// Analyzes the team's weaknesses, resistances and immunities, the weaknesses members share,
+/// and the offensive coverage of its moves, with the typings and type chart of `gen`
+pub fn analyze_team(team: &Team, r#gen: u8) -> TeamAnalysis {
+    let dex = Dex::get();
+    let all_types: Vec<Type> = Type::ALL
+        .into_iter()
+        .filter(|t| t.exists_in(r#gen))
+        .collect();
+    let mut members = Vec::new();
+    let mut unknown = Vec::new();
+
+    for pkmn in &team.pokemon {
+        let name = pkmn.species.as_deref().unwrap_or(&pkmn.name);
+        let Some(species) = dex.get_species(name) else {
+            unknown.push(name.to_string());
+            continue;
+        };
+        let species = species.in_gen(r#gen);
+        let types: Vec<Type> = species
+            .types
+            .iter()
+            .filter_map(|t| t.parse().ok())
+            .collect();
+        let multipliers = all_types
+            .iter()
+            .map(|&attack| {
+                let multiplier =
+                    defensive_multiplier(r#gen, &types, pkmn.ability.as_deref(), attack);
+                (attack, multiplier)
+            })
+            .collect();
+        let mut attacking_types: Vec<Type> = pkmn
+            .moves
+            .iter()
+            .filter_map(|m| attacking_type(dex, pkmn, m))
+            .collect();
+        attacking_types.sort();
+        attacking_types.dedup();
+
+        members.push(MemberMatchup {
+            name: species.name.clone(),
+            types,
+            ability: pkmn.ability.clone(),
+            multipliers,
+            attacking_types,
+        });
+    }
+
+    let mut shared_weaknesses: Vec<SharedWeakness> = all_types
+        .iter()
+        .map(|&t| SharedWeakness {
+            r#type: t,
+            weak: members.iter().filter(|m| m.multiplier(t) > 1.0).count(),
+            resist: members.iter().filter(|m| m.multiplier(t) < 1.0).count(),
+        })
+        .filter(|w| w.weak >= 2)
+        .collect();
+    shared_weaknesses.sort_by(|a, b| (b.weak, a.resist).cmp(&(a.weak, b.resist)));
+
+    // Offense
+    let attacking: Vec<Type> = members
+        .iter()
+        .flat_map(|m| m.attacking_types.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let best = |defenders: &[Type], attacks: &[Type]| {
+        attacks
+            .iter()
+            .map(|&a| type_effectiveness_in(r#gen, a, defenders))
+            .fold(0.0, f32::max)
+    };
+
+    let coverage: Vec<(Type, f32)> = all_types
+        .iter()
+        .map(|&t| (t, best(&[t], &attacking)))
+        .collect();
+    let uncovered = coverage
+        .iter()
+        .filter(|(_, m)| *m < 2.0)
+        .map(|(t, _)| *t)
+        .collect();
+
+    // Attackers carrying both types of a pair have no safe switch-in on the team
+    let mut unresisted_combos = Vec::new();
+    for (i, &a) in all_types.iter().enumerate() {
+        for &b in &all_types[i + 1..] {
+            let resisted = members
+                .iter()
+                .any(|m| m.multiplier(a) < 1.0 && m.multiplier(b) < 1.0);
+            if !members.is_empty() && !resisted {
+                unresisted_combos.push((a, b));
+            }
+        }
+    }
+    let walled_by = if attacking.is_empty() {
+        Vec::new()
+    } else {
+        type_combinations(&all_types)
+            .into_iter()
+            .filter(|defenders| best(defenders, &attacking) < 1.0)
+            .collect()
+    };
+
+    TeamAnalysis {
+        r#gen,
+        members,
+        unknown,
+        shared_weaknesses,
+        coverage,
+        uncovered,
+        unresisted_combos,
+        walled_by,
+    }
+}
+
+fn join_types(types: &[Type]) -> String {
+    types
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn join_multipliers(list: &[(Type, f32)]) -> String {
+    list.iter()
+        .map(|(t, m)| format!("{} (x{})", t, m))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for TeamAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Defensive matchups (Gen {}):", self.r#gen)?;
+        for m in &self.members {
+            let ability = m
+                .ability
+                .as_ref()
+                .map(|a| format!(", {}", a))
+                .unwrap_or_default();
+            writeln!(f, "  {} ({}{})", m.name, join_types(&m.types), ability)?;
+            writeln!(f, "    Weak to: {}", join_multipliers(&m.weaknesses()))?;
+            writeln!(f, "    Resists: {}", join_multipliers(&m.resistances()))?;
+            let immune: Vec<String> = m.immunities().iter().map(|t| t.to_string()).collect();
+            writeln!(f, "    Immune to: {}", immune.join(", "))?;
+        }
+        if !self.unknown.is_empty() {
+            writeln!(
+                f,
+                "  Not analyzed (unknown species): {}",
+                self.unknown.join(", ")
+            )?;
+        }
+
+        writeln!(f, "\nShared weaknesses:")?;
+        if self.shared_weaknesses.is_empty() {
+            writeln!(f, "  None")?;
+        }
+        for w in &self.shared_weaknesses {
+            writeln!(f, "  {}: {} weak, {} resist", w.r#type, w.weak, w.resist)?;
+        }
+        let combos: Vec<String> = self
+            .unresisted_combos
+            .iter()
+            .map(|(a, b)| format!("{} + {}", a, b))
+            .collect();
+        writeln!(f, "Attack combos no member resists: {}", combos.join(", "))?;
+
+        writeln!(f, "\nOffensive coverage:")?;
+        let covered: Vec<String> = self
+            .coverage
+            .iter()
+            .filter(|(_, m)| *m >= 2.0)
+            .map(|(t, _)| t.to_string())
+            .collect();
+        writeln!(f, "  Super effective against: {}", covered.join(", "))?;
+        let uncovered: Vec<String> = self.uncovered.iter().map(|t| t.to_string()).collect();
+        writeln!(
+            f,
+            "  No super effective move against: {}",
+            uncovered.join(", ")
+        )?;
+        let walls: Vec<String> = self.walled_by.iter().map(|t| join_types(t)).collect();
+        write!(
+            f,
+            "  Resists every attack on the team: {}",
+            walls.join(", ")
+        )
+    }
+}
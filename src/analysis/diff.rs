@@ -0,0 +1,273 @@
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::{EVs, Pokemon, Team};
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
+
+/// A stat whose EV or IV value changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatChange {
+    pub stat: &'static str,
+    pub from: u16,
+    pub to: u16,
+}
+
+impl StatChange {
+    pub fn delta(&self) -> i32 {
+        i32::from(self.to) - i32::from(self.from)
+    }
+}
+
+/// One difference between two sets of the same Pokémon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum Change {
+    /// Another forme of the same species, e.g. Charizard to Charizard-Mega-X
+    Species {
+        from: String,
+        to: String,
+    },
+    Item {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Ability {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Nature {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Level {
+        from: u8,
+        to: u8,
+    },
+    Evs {
+        stats: Vec<StatChange>,
+    },
+    Ivs {
+        stats: Vec<StatChange>,
+    },
+    Moves {
+        removed: Vec<String>,
+        added: Vec<String>,
+    },
+}
+
+/// Everything that changed on a Pokémon both teams have
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PokemonDiff {
+    pub species: String,
+    pub changes: Vec<Change>,
+}
+
+/// Structural difference between two teams, with members matched by species
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TeamDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<PokemonDiff>,
+}
+
+impl TeamDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+fn species_of(pkmn: &Pokemon) -> &str {
+    pkmn.species.as_deref().unwrap_or(&pkmn.name)
+}
+
+fn stat_changes(from: &EVs, to: &EVs) -> Vec<StatChange> {
+    let values = |s: &EVs| [s.hp, s.atk, s.def, s.spa, s.spd, s.spe];
+    ["HP", "Atk", "Def", "SpA", "SpD", "Spe"]
+        .into_iter()
+        .zip(values(from).into_iter().zip(values(to)))
+        .filter(|(_, (from, to))| from != to)
+        .map(|(stat, (from, to))| StatChange { stat, from, to })
+        .collect()
+}
+
+fn diff_pokemon(old: &Pokemon, new: &Pokemon) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let differs =
+        |a: &Option<String>, b: &Option<String>| a.as_deref().map(to_id) != b.as_deref().map(to_id);
+
+    if to_id(species_of(old)) != to_id(species_of(new)) {
+        changes.push(Change::Species {
+            from: species_of(old).to_string(),
+            to: species_of(new).to_string(),
+        });
+    }
+    if differs(&old.item, &new.item) {
+        changes.push(Change::Item {
+            from: old.item.clone(),
+            to: new.item.clone(),
+        });
+    }
+    if differs(&old.ability, &new.ability) {
+        changes.push(Change::Ability {
+            from: old.ability.clone(),
+            to: new.ability.clone(),
+        });
+    }
+    if differs(&old.nature, &new.nature) {
+        changes.push(Change::Nature {
+            from: old.nature.clone(),
+            to: new.nature.clone(),
+        });
+    }
+    let (old_level, new_level) = (old.level.unwrap_or(100), new.level.unwrap_or(100));
+    if old_level != new_level {
+        changes.push(Change::Level {
+            from: old_level,
+            to: new_level,
+        });
+    }
+
+    let evs = stat_changes(&old.evs, &new.evs);
+    if !evs.is_empty() {
+        changes.push(Change::Evs { stats: evs });
+    }
+    let all_31 = EVs::max_ivs();
+    let ivs = stat_changes(
+        old.ivs.as_ref().unwrap_or(&all_31),
+        new.ivs.as_ref().unwrap_or(&all_31),
+    );
+    if !ivs.is_empty() {
+        changes.push(Change::Ivs { stats: ivs });
+    }
+
+    // Move order doesn't matter
+    let missing_from = |moves: &[String], other: &[String]| -> Vec<String> {
+        moves
+            .iter()
+            .filter(|m| !other.iter().any(|o| to_id(o) == to_id(m)))
+            .cloned()
+            .collect()
+    };
+    let removed = missing_from(&old.moves, &new.moves);
+    let added = missing_from(&new.moves, &old.moves);
+    if !removed.is_empty() || !added.is_empty() {
+        changes.push(Change::Moves { removed, added });
+    }
+
+    changes
+}
+
+/// Compares two teams. Members are matched by species, then by Pokédex number so a forme
+/// change (Charizard to Charizard-Mega-X) shows as a change rather than a swap.
+pub fn diff_teams(old: &Team, new: &Team) -> TeamDiff {
+    let dex = Dex::get();
+    let mut unmatched: Vec<&Pokemon> = new.pokemon.iter().collect();
+    let mut pairs: Vec<(&Pokemon, Option<&Pokemon>)> = Vec::new();
+
+    for pkmn in &old.pokemon {
+        let id = to_id(species_of(pkmn));
+        let found = unmatched.iter().position(|p| to_id(species_of(p)) == id);
+        pairs.push((pkmn, found.map(|i| unmatched.remove(i))));
+    }
+
+    let num = |p: &Pokemon| dex.get_species(species_of(p)).map(|s| s.num);
+    for (pkmn, partner) in pairs.iter_mut().filter(|(_, partner)| partner.is_none()) {
+        let Some(n) = num(pkmn) else {
+            continue;
+        };
+        if let Some(i) = unmatched.iter().position(|p| num(p) == Some(n)) {
+            *partner = Some(unmatched.remove(i));
+        }
+    }
+
+    let mut diff = TeamDiff::default();
+    for (pkmn, partner) in pairs {
+        match partner {
+            None => diff.removed.push(species_of(pkmn).to_string()),
+            Some(partner) => {
+                let changes = diff_pokemon(pkmn, partner);
+                if !changes.is_empty() {
+                    diff.changed.push(PokemonDiff {
+                        species: species_of(partner).to_string(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    diff.added = unmatched
+        .into_iter()
+        .map(|p| species_of(p).to_string())
+        .collect();
+    diff
+}
+
+fn or_none(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("(none)")
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Species { from, to } => write!(f, "Forme: {} -> {}", from, to),
+            Change::Item { from, to } => write!(f, "Item: {} -> {}", or_none(from), or_none(to)),
+            Change::Ability { from, to } => {
+                write!(f, "Ability: {} -> {}", or_none(from), or_none(to))
+            }
+            Change::Nature { from, to } => {
+                write!(f, "Nature: {} -> {}", or_none(from), or_none(to))
+            }
+            Change::Level { from, to } => write!(f, "Level: {} -> {}", from, to),
+            Change::Evs { stats } | Change::Ivs { stats } => {
+                let label = if matches!(self, Change::Evs { .. }) {
+                    "EVs"
+                } else {
+                    "IVs"
+                };
+                let stats: Vec<String> = stats
+                    .iter()
+                    .map(|s| format!("{} {} -> {} ({:+})", s.stat, s.from, s.to, s.delta()))
+                    .collect();
+                write!(f, "{}: {}", label, stats.join(", "))
+            }
+            Change::Moves { removed, added } => {
+                // Pair removals with additions so a replaced move reads as a swap
+                let mut parts: Vec<String> = removed
+                    .iter()
+                    .zip(added)
+                    .map(|(old, new)| format!("{} -> {}", old, new))
+                    .collect();
+                parts.extend(removed.iter().skip(added.len()).map(|m| format!("-{}", m)));
+                parts.extend(added.iter().skip(removed.len()).map(|m| format!("+{}", m)));
+                write!(f, "Moves: {}", parts.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for TeamDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No changes");
+        }
+        let mut lines = Vec::new();
+        for name in &self.added {
+            lines.push(format!("+ {}", name));
+        }
+        for name in &self.removed {
+            lines.push(format!("- {}", name));
+        }
+        for pkmn in &self.changed {
+            lines.push(format!("~ {}", pkmn.species));
+            for change in &pkmn.changes {
+                lines.push(format!("    {}", change));
+            }
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
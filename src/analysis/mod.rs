@@ -0,0 +1,7 @@
+pub mod coverage;
+pub mod diff;
+pub mod roles;
+
+pub use coverage::{TeamAnalysis, analyze_team, defensive_multiplier};
+pub use diff::{Change, PokemonDiff, StatChange, TeamDiff, diff_teams};
+pub use roles::{Archetype, MemberRoles, Role, TeamProfile, Weather, classify_team};
@@ -0,0 +1,389 @@
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::{Pokemon, Team};
+use std::fmt;
+
+const HAZARD_MOVES: [&str; 5] = [
+    "stealthrock",
+    "spikes",
+    "toxicspikes",
+    "stickyweb",
+    "stoneaxe",
+];
+const HAZARD_REMOVAL_MOVES: [&str; 5] =
+    ["rapidspin", "defog", "courtchange", "tidyup", "mortalspin"];
+const PIVOT_MOVES: [&str; 7] = [
+    "uturn",
+    "voltswitch",
+    "flipturn",
+    "partingshot",
+    "teleport",
+    "chillyreception",
+    "shedtail",
+];
+const CLERIC_MOVES: [&str; 7] = [
+    "healbell",
+    "aromatherapy",
+    "wish",
+    "healingwish",
+    "lunardance",
+    "junglehealing",
+    "lifedew",
+];
+const WALLBREAKER_ITEMS: [&str; 3] = ["choiceband", "choicespecs", "lifeorb"];
+const WALLBREAKER_ABILITIES: [&str; 8] = [
+    "hugepower",
+    "purepower",
+    "adaptability",
+    "sheerforce",
+    "toughclaws",
+    "gorillatactics",
+    "protean",
+    "sandforce",
+];
+
+/// What a team member does for its team
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Role {
+    WeatherSetter,
+    HazardSetter,
+    HazardRemover,
+    Pivot,
+    Cleric,
+    SetupSweeper,
+    Wallbreaker,
+    RevengeKiller,
+    Wall,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Role::WeatherSetter => "Weather setter",
+            Role::HazardSetter => "Hazard setter",
+            Role::HazardRemover => "Spinner/Defogger",
+            Role::Pivot => "Pivot",
+            Role::Cleric => "Cleric",
+            Role::SetupSweeper => "Setup sweeper",
+            Role::Wallbreaker => "Wallbreaker",
+            Role::RevengeKiller => "Revenge killer",
+            Role::Wall => "Wall",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weather {
+    Rain,
+    Sun,
+    Sand,
+    Snow,
+}
+
+impl Weather {
+    const ALL: [Weather; 4] = [Weather::Rain, Weather::Sun, Weather::Sand, Weather::Snow];
+
+    fn set_by_ability(ability: &str) -> Option<Weather> {
+        match ability {
+            "drizzle" | "primordialsea" => Some(Weather::Rain),
+            "drought" | "desolateland" | "orichalcumpulse" => Some(Weather::Sun),
+            "sandstream" => Some(Weather::Sand),
+            "snowwarning" => Some(Weather::Snow),
+            _ => None,
+        }
+    }
+
+    fn set_by_move(move_id: &str) -> Option<Weather> {
+        match move_id {
+            "raindance" => Some(Weather::Rain),
+            "sunnyday" => Some(Weather::Sun),
+            "sandstorm" => Some(Weather::Sand),
+            "snowscape" | "hail" => Some(Weather::Snow),
+            _ => None,
+        }
+    }
+
+    /// Whether the ability or move is better, or only works, under this weather
+    fn abused_by(&self, id: &str) -> bool {
+        let ids: &[&str] = match self {
+            Weather::Rain => &[
+                "swiftswim",
+                "raindish",
+                "hydration",
+                "dryskin",
+                "thunder",
+                "hurricane",
+            ],
+            Weather::Sun => &[
+                "chlorophyll",
+                "solarpower",
+                "flowergift",
+                "leafguard",
+                "protosynthesis",
+                "solarbeam",
+                "solarblade",
+            ],
+            Weather::Sand => &["sandrush", "sandforce", "sandveil"],
+            Weather::Snow => &[
+                "slushrush",
+                "icebody",
+                "snowcloak",
+                "blizzard",
+                "auroraveil",
+            ],
+        };
+        ids.contains(&id)
+    }
+}
+
+impl fmt::Display for Weather {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Weather::Rain => "Rain",
+            Weather::Sun => "Sun",
+            Weather::Sand => "Sand",
+            Weather::Snow => "Snow",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The overall playstyle of a team
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Archetype {
+    Weather(Weather),
+    HyperOffense,
+    Balance,
+    Stall,
+}
+
+impl fmt::Display for Archetype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Archetype::Weather(weather) => write!(f, "{}", weather),
+            Archetype::HyperOffense => write!(f, "Hyper Offense"),
+            Archetype::Balance => write!(f, "Balance"),
+            Archetype::Stall => write!(f, "Stall"),
+        }
+    }
+}
+
+/// The roles one team member fills
+#[derive(Debug, Clone)]
+pub struct MemberRoles {
+    pub name: String,
+    pub roles: Vec<Role>,
+    /// Invests mostly in Attack or Special Attack
+    pub offensive: bool,
+}
+
+impl MemberRoles {
+    pub fn has(&self, role: Role) -> bool {
+        self.roles.contains(&role)
+    }
+}
+
+/// Archetype of a team and the role of each member
+#[derive(Debug, Clone)]
+pub struct TeamProfile {
+    pub archetype: Archetype,
+    pub members: Vec<MemberRoles>,
+    /// Key roles nobody on the team fills
+    pub missing: Vec<Role>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spread {
+    Offensive,
+    Defensive,
+    Mixed,
+}
+
+/// Reads the EV spread, or the base stats when the set has no EVs
+fn spread_of(dex: &Dex, pkmn: &Pokemon) -> Spread {
+    let evs = &pkmn.evs;
+    let total = evs.hp + evs.atk + evs.def + evs.spa + evs.spd + evs.spe;
+    if total > 0 {
+        let offense = evs.atk.max(evs.spa);
+        let bulk = evs.hp + evs.def + evs.spd;
+        return if offense >= 200 {
+            Spread::Offensive
+        } else if bulk >= 320 {
+            Spread::Defensive
+        } else {
+            Spread::Mixed
+        };
+    }
+
+    let name = pkmn.species.as_deref().unwrap_or(&pkmn.name);
+    let Some(species) = dex.get_species(name) else {
+        return Spread::Mixed;
+    };
+    let base = &species.base_stats;
+    let offense = base.atk.max(base.spa);
+    let bulk = base.def.max(base.spd);
+    if offense >= 100 && offense > bulk {
+        Spread::Offensive
+    } else if bulk >= 100 && bulk > offense {
+        Spread::Defensive
+    } else {
+        Spread::Mixed
+    }
+}
+
+fn classify_member(dex: &Dex, pkmn: &Pokemon) -> MemberRoles {
+    let moves: Vec<String> = pkmn.moves.iter().map(|m| to_id(m)).collect();
+    let knows = |ids: &[&str]| moves.iter().any(|m| ids.contains(&m.as_str()));
+    let ability = pkmn.ability.as_deref().map(to_id).unwrap_or_default();
+    let item = pkmn.item.as_deref().map(to_id).unwrap_or_default();
+    let spread = spread_of(dex, pkmn);
+    let mut roles = Vec::new();
+
+    if Weather::set_by_ability(&ability).is_some()
+        || moves.iter().any(|m| Weather::set_by_move(m).is_some())
+    {
+        roles.push(Role::WeatherSetter);
+    }
+    if knows(&HAZARD_MOVES) {
+        roles.push(Role::HazardSetter);
+    }
+    if knows(&HAZARD_REMOVAL_MOVES) {
+        roles.push(Role::HazardRemover);
+    }
+    if knows(&PIVOT_MOVES) {
+        roles.push(Role::Pivot);
+    }
+    if knows(&CLERIC_MOVES) {
+        roles.push(Role::Cleric);
+    }
+
+    let boosts_offense = moves.iter().any(|m| {
+        m == "bellydrum"
+            || m == "shellsmash"
+            || dex.get_move(m).is_some_and(|mv| {
+                mv.target == "self"
+                    && ["atk", "spa", "spe"]
+                        .iter()
+                        .any(|s| mv.boosts.get(*s).is_some_and(|b| *b > 0))
+            })
+    });
+    let choiced = item.starts_with("choice");
+    if boosts_offense && !choiced {
+        roles.push(Role::SetupSweeper);
+    }
+
+    let offensive = spread == Spread::Offensive;
+    if offensive {
+        if item == "choicescarf" {
+            roles.push(Role::RevengeKiller);
+        } else if WALLBREAKER_ITEMS.contains(&item.as_str())
+            || WALLBREAKER_ABILITIES.contains(&ability.as_str())
+        {
+            roles.push(Role::Wallbreaker);
+        }
+    } else if spread == Spread::Defensive && !boosts_offense {
+        roles.push(Role::Wall);
+    }
+
+    let name = pkmn.species.as_deref().unwrap_or(&pkmn.name);
+    let name = dex
+        .get_species(name)
+        .map(|s| s.name.clone())
+        .unwrap_or_else(|| name.to_string());
+
+    MemberRoles {
+        name,
+        roles,
+        offensive,
+    }
+}
+
+/// Classifies the team's archetype and each member's roles from abilities, items, moves
+/// and EV spreads. Weather teams are recognized by their setter, the rest by how many
+/// members are walls and how many are attackers.
+pub fn classify_team(team: &Team) -> TeamProfile {
+    let dex = Dex::get();
+    let members: Vec<MemberRoles> = team
+        .pokemon
+        .iter()
+        .map(|p| classify_member(dex, p))
+        .collect();
+
+    // The weather with the most setters and abusers wins, but it needs a setter
+    let mut best: Option<(Weather, usize)> = None;
+    for weather in Weather::ALL {
+        let mut setters = 0;
+        let mut abusers = 0;
+        for pkmn in &team.pokemon {
+            let ability = pkmn.ability.as_deref().map(to_id).unwrap_or_default();
+            let moves: Vec<String> = pkmn.moves.iter().map(|m| to_id(m)).collect();
+            if Weather::set_by_ability(&ability) == Some(weather)
+                || moves
+                    .iter()
+                    .any(|m| Weather::set_by_move(m) == Some(weather))
+            {
+                setters += 1;
+            }
+            if weather.abused_by(&ability) || moves.iter().any(|m| weather.abused_by(m)) {
+                abusers += 1;
+            }
+        }
+        if setters > 0 && best.is_none_or(|(_, score)| setters + abusers > score) {
+            best = Some((weather, setters + abusers));
+        }
+    }
+
+    let walls = members.iter().filter(|m| m.has(Role::Wall)).count();
+    let attackers = members.iter().filter(|m| m.offensive).count();
+    let archetype = match best {
+        Some((weather, _)) => Archetype::Weather(weather),
+        None if walls >= 4 => Archetype::Stall,
+        None if walls == 0 && attackers >= 4 => Archetype::HyperOffense,
+        None => Archetype::Balance,
+    };
+
+    let missing = [
+        Role::HazardSetter,
+        Role::HazardRemover,
+        Role::Pivot,
+        Role::Wallbreaker,
+    ]
+    .into_iter()
+    .filter(|role| !members.iter().any(|m| m.has(*role)))
+    .collect();
+
+    TeamProfile {
+        archetype,
+        members,
+        missing,
+    }
+}
+
+fn join<T: fmt::Display>(list: &[T]) -> String {
+    list.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for TeamProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Archetype: {}", self.archetype)?;
+        writeln!(f, "Roles:")?;
+        for m in &self.members {
+            let roles = if m.roles.is_empty() {
+                "No clear role".to_string()
+            } else {
+                join(&m.roles)
+            };
+            writeln!(f, "  {}: {}", m.name, roles)?;
+        }
+        write!(f, "Missing roles: ")?;
+        if self.missing.is_empty() {
+            write!(f, "None")
+        } else {
+            write!(f, "{}", join(&self.missing))
+        }
+    }
+}
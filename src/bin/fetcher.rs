@@ -1,13 +1,135 @@
-use pokebrains::Result;
-use pokebrains::pretty_display;
+use anyhow::{anyhow, bail};
+use pokebrains::data::LATEST_GEN;
+use pokebrains::search::{SpeciesQuery, report, search};
+use pokebrains::{
+    Result, compare, fetch_ability_info, fetch_item_info, fetch_move_info, fetch_pokemon_info_in,
+    print_pokemon, type_matchups,
+};
+use serde::Serialize;
 use std::env;
 
+const USAGE: &str = "\
+Usage: pokefetcher [--json] [--gen N] <command> [args]
+
+  mon <name>                             Pokémon details
+  move <name>                            Type, power, accuracy and effects
+  item <name>                            What an item does
+  ability <name>                         What an ability does
+  type <type>[/<type>] | <pokemon>       Weaknesses, resistances and coverage
+  compare <a> <b>                        Side-by-side base stats and matchups
+  search <query>                         e.g. search steel gen6ou spe>=90 U-turn
+
+  --json                                 Print JSON instead of text
+  --gen N                                Data as of generation N (latest by default)
+
+Names with spaces need quotes, except with compare's \"vs\": compare Tapu Koko vs Zapdos.
+A name on its own is looked up as a Pokémon.";
+
+/// Prints `value` as pretty JSON, or its text form
+fn output<T: Serialize>(json: bool, value: &T, text: impl FnOnce(&T) -> String) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        print!("{}", text(value));
+    }
+    Ok(())
+}
+
+/// Pokémon details in color, or as JSON
+async fn show_pokemon(name: &str, r#gen: u8, json: bool) -> Result<()> {
+    let info = fetch_pokemon_info_in(name, r#gen).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+    } else {
+        print_pokemon(&info);
+    }
+    Ok(())
+}
+
+fn joined(args: &[String], what: &str) -> Result<String> {
+    let name = args.join(" ");
+    if name.is_empty() {
+        bail!("Missing {}\n\n{}", what, USAGE);
+    }
+    Ok(name)
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-    let pokemon_name = if args.len() > 1 { &args[1] } else { "Rayquaza" };
-
-    pretty_display(pokemon_name).await?;
+    let mut json = false;
+    let mut r#gen = LATEST_GEN;
+    let mut args = Vec::new();
+    let mut rest = env::args().skip(1);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--gen" => {
+                let value = rest
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing value for --gen"))?;
+                r#gen = value
+                    .trim_start_matches("gen")
+                    .parse::<u8>()
+                    .map_err(|_| anyhow!("Not a generation: {}", value))?
+                    .clamp(1, LATEST_GEN);
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => args.push(arg),
+        }
+    }
 
+    let Some((command, args)) = args.split_first() else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+    match command.as_str() {
+        "mon" => show_pokemon(&joined(args, "Pokémon name")?, r#gen, json).await?,
+        "move" => output(
+            json,
+            &fetch_move_info(&joined(args, "move name")?, r#gen).await?,
+            |info| info.to_readable_form(),
+        )?,
+        "item" => output(
+            json,
+            &fetch_item_info(&joined(args, "item name")?, r#gen).await?,
+            |info| info.to_readable_form(),
+        )?,
+        "ability" => output(
+            json,
+            &fetch_ability_info(&joined(args, "ability name")?, r#gen).await?,
+            |info| info.to_readable_form(),
+        )?,
+        "type" => output(
+            json,
+            &type_matchups(&joined(args, "type or Pokémon")?, r#gen).await?,
+            |matchups| matchups.to_readable_form(),
+        )?,
+        "compare" => {
+            let (first, second) = match args.iter().position(|a| a.eq_ignore_ascii_case("vs")) {
+                Some(i) => (args[..i].join(" "), args[i + 1..].join(" ")),
+                None if args.len() == 2 => (args[0].clone(), args[1].clone()),
+                None => bail!("compare takes two Pokémon\n\n{}", USAGE),
+            };
+            output(
+                json,
+                &compare(&first, &second, r#gen).await?,
+                |comparison| comparison.to_readable_form(),
+            )?
+        }
+        "search" => {
+            let mut query = SpeciesQuery::parse(&joined(args, "search query")?)?;
+            query.r#gen = query.r#gen.or(Some(r#gen));
+            let matches = search(&query)?;
+            output(json, &matches, |matches| report(&query, matches))?
+        }
+        // `pokefetcher Garchomp` from before there were subcommands
+        _ => {
+            let name = format!("{} {}", command, args.join(" "));
+            show_pokemon(name.trim(), r#gen, json).await?
+        }
+    }
     Ok(())
 }
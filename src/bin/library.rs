@@ -0,0 +1,101 @@
+use anyhow::anyhow;
+use pokebrains::Result;
+use pokebrains::library::{Outcome, TeamLibrary, TeamQuery};
+use std::env;
+use std::path::Path;
+
+const USAGE: &str = "\
+Usage: teamlib <command> [args]
+
+  list                                   List every team
+  search <query>                         e.g. search Ferrothorn gen5ou #rain
+  show <id>                              Print a team as Showdown text
+  import <file> [--name N] [--format F] [--tag T]...
+  export <id> <file>                     .txt .packed .json .toml .md .html
+  tag <id> <tag>...
+  untag <id> <tag>...
+  format <id> <format>
+  record <id> win|loss|tie
+  remove <id>
+
+The library lives in ./teams, or POKEBRAINS_LIBRARY_DIR if set.";
+
+fn arg<'a>(args: &'a [String], i: usize, what: &str) -> Result<&'a str> {
+    args.get(i)
+        .map(String::as_str)
+        .ok_or_else(|| anyhow!("Missing {}\n\n{}", what, USAGE))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let library = TeamLibrary::open(TeamLibrary::default_dir())?;
+
+    match args.first().map(String::as_str) {
+        Some("list") => {
+            for entry in library.list()? {
+                println!("{}", entry);
+            }
+        }
+        Some("search") => {
+            let query = TeamQuery::parse(&args[1..].join(" "));
+            for entry in library.search(&query).await? {
+                println!("{}", entry);
+            }
+        }
+        Some("show") => {
+            let id = arg(&args, 1, "team id")?;
+            println!("{}", library.entry(id)?);
+            print!("{}", library.load(id).await?.serialize());
+        }
+        Some("import") => {
+            let path = arg(&args, 1, "file to import")?;
+            let (mut name, mut format, mut tags) = (None, None, Vec::new());
+            let mut rest = args[2..].iter();
+            while let Some(flag) = rest.next() {
+                let value = rest
+                    .next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", flag))?;
+                match flag.as_str() {
+                    "--name" => name = Some(value.as_str()),
+                    "--format" => format = Some(value.as_str()),
+                    "--tag" => tags.push(value.clone()),
+                    _ => return Err(anyhow!("Unknown option {}\n\n{}", flag, USAGE)),
+                }
+            }
+            let entry = library.import(Path::new(path), name, format, &tags).await?;
+            println!("Imported {}", entry);
+        }
+        Some("export") => {
+            let id = arg(&args, 1, "team id")?;
+            let path = arg(&args, 2, "output file")?;
+            library.export(id, Path::new(path)).await?;
+            println!("Exported {} to {}", id, path);
+        }
+        Some("tag") => {
+            let id = arg(&args, 1, "team id")?;
+            println!("{}", library.add_tags(id, &args[2..])?);
+        }
+        Some("untag") => {
+            let id = arg(&args, 1, "team id")?;
+            println!("{}", library.remove_tags(id, &args[2..])?);
+        }
+        Some("format") => {
+            let id = arg(&args, 1, "team id")?;
+            println!("{}", library.set_format(id, arg(&args, 2, "format")?)?);
+        }
+        Some("record") => {
+            let id = arg(&args, 1, "team id")?;
+            let outcome: Outcome = arg(&args, 2, "win, loss or tie")?.parse()?;
+            println!("{}", library.record_result(id, outcome)?);
+        }
+        Some("remove") => {
+            let id = arg(&args, 1, "team id")?;
+            library.remove(id)?;
+            println!("Removed {}", id);
+        }
+        _ => println!("{}", USAGE),
+    }
+
+    Ok(())
+}
@@ -1,5 +1,9 @@
 use forge::api::tools_registry::ToolRegistry;
-use pokebrains::tools::PokeAPITool;
+use pokebrains::library::TeamLibrary;
+use pokebrains::tools::{
+    AbilityDetailsTool, ItemDetailsTool, LearnsetTool, MoveDetailsTool, PokeAPITool,
+    PokemonSearchTool, TypeMatchupTool,
+};
 use pokebrains::{BattleAgent, Colorize, ModelType, Result, ShowdownClient};
 use std::io::Write;
 use std::io::{stdin, stdout};
@@ -39,6 +43,14 @@ async fn main() -> Result<()> {
         .read_line(&mut player)
         .expect("Failed to read input");
 
+    println!("{}", "Enter your team's library id (optional): ".yellow());
+    print!("{} ", "↪".yellow());
+    stdout().flush()?;
+    let mut team_id = String::new();
+    stdin()
+        .read_line(&mut team_id)
+        .expect("Failed to read input");
+
     if room_id.trim().is_empty() {
         println!();
         print!("Exiting...");
@@ -51,6 +63,12 @@ async fn main() -> Result<()> {
 
     let mut tool_registry: ToolRegistry = ToolRegistry::new();
     tool_registry.register(PokeAPITool);
+    tool_registry.register(MoveDetailsTool);
+    tool_registry.register(ItemDetailsTool);
+    tool_registry.register(AbilityDetailsTool);
+    tool_registry.register(LearnsetTool);
+    tool_registry.register(PokemonSearchTool);
+    tool_registry.register(TypeMatchupTool);
 
     match battle_agent.build_agent("local", tool_registry) {
         Ok(a) => {
@@ -62,6 +80,24 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Give the agent our own team and track its win record
+    let team_id = team_id.trim();
+    let mut library_team = None;
+    let mut own_team = None;
+    if !team_id.is_empty() {
+        let library = TeamLibrary::open(TeamLibrary::default_dir())?;
+        match library.load(team_id).await {
+            Ok(team) => {
+                if let Some(ref mut a) = agent {
+                    a.add_team_context(&team);
+                }
+                library_team = Some((library, team_id.to_string()));
+                own_team = Some(team);
+            }
+            Err(e) => eprintln!("{}", format!("Failed to load team: {}", e).red()),
+        }
+    }
+
     let room_id = room_id.trim();
     let room_id = if room_id.contains("play.pokemonshowdown.com/") {
         room_id.split('/').next_back().unwrap_or(room_id)
@@ -75,6 +111,10 @@ async fn main() -> Result<()> {
         );
         let mut battle_room = ShowdownClient::new("lobby", player, 30);
         battle_room.ai_agent = agent;
+        battle_room.library_team = library_team;
+        if let Some(ref team) = own_team {
+            battle_room.event_logs.opponents.set_own_team(team);
+        }
         if let Err(e) = battle_room.join_room().await {
             eprintln!("{}", format!("Connection error: {}", e).red());
         }
@@ -82,6 +122,10 @@ async fn main() -> Result<()> {
         println!("{}", format!("Connecting to room: {}", room_id).green());
         let mut battle_room = ShowdownClient::new(room_id, player, 30);
         battle_room.ai_agent = agent;
+        battle_room.library_team = library_team;
+        if let Some(ref team) = own_team {
+            battle_room.event_logs.opponents.set_own_team(team);
+        }
         if let Err(e) = battle_room.join_room().await {
             eprintln!("{}", format!("Connection error: {}", e).red());
         }
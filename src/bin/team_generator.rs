@@ -4,15 +4,29 @@ use forge::api::dtos::Message;
 use forge::api::dtos::Role::{ASSISTANT, USER};
 use forge::api::request::log_typewriter_effect;
 use forge::api::tools_registry::ToolRegistry;
-use pokebrains::tools::{PokeAPITool, PokemonShowdownTeamGeneratorTool, TeamValidatorTool};
+use pokebrains::tools::{
+    AbilityDetailsTool, ItemDetailsTool, LearnsetTool, MoveDetailsTool, PokeAPITool,
+    PokemonSearchTool, PokemonShowdownTeamGeneratorTool, TeamAnalysisTool, TeamDiffTool,
+    TeamLibrarySaveTool, TeamLibrarySearchTool, TeamValidatorTool, TypeMatchupTool,
+};
 use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let mut tool_registry = ToolRegistry::new();
     tool_registry.register(PokeAPITool);
+    tool_registry.register(MoveDetailsTool);
+    tool_registry.register(ItemDetailsTool);
+    tool_registry.register(AbilityDetailsTool);
+    tool_registry.register(LearnsetTool);
+    tool_registry.register(PokemonSearchTool);
     tool_registry.register(PokemonShowdownTeamGeneratorTool);
     tool_registry.register(TeamValidatorTool);
+    tool_registry.register(TeamAnalysisTool);
+    tool_registry.register(TeamDiffTool);
+    tool_registry.register(TeamLibrarySaveTool);
+    tool_registry.register(TeamLibrarySearchTool);
+    tool_registry.register(TypeMatchupTool);
 
     let agent = AgentBuilder::new()
         .model("qwen/qwen3-8b")
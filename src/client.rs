@@ -1,5 +1,7 @@
 use crate::agent::BattleAgent;
-use crate::parser::logs::BattleEvents;
+use crate::id::to_id;
+use crate::library::{Outcome, TeamLibrary};
+use crate::parser::logs::{BattleEvents, Token};
 use crate::{Colorize, Result, Team};
 use futures_util::{SinkExt, StreamExt};
 use std::time::Duration;
@@ -15,6 +17,8 @@ pub struct ShowdownClient {
     is_connected: bool,
     pub event_logs: BattleEvents,
     pub ai_agent: Option<BattleAgent>,
+    /// Library and id of the team being played, to record the result when the battle ends
+    pub library_team: Option<(TeamLibrary, String)>,
     last_turn: usize, // Track the last processed turn
 }
 
@@ -27,6 +31,7 @@ impl ShowdownClient {
             is_connected: false,
             event_logs: BattleEvents::new(user),
             ai_agent: None,
+            library_team: None,
             last_turn: 0, // Initialize last_turn
         }
     }
@@ -191,6 +196,9 @@ impl ShowdownClient {
 
                     // Check if battle just ended (wasn't ended before, but is now)
                     let is_battle_ended = self.event_logs.is_battle_ended();
+                    if !was_battle_ended && is_battle_ended {
+                        self.record_result();
+                    }
 
                     // AI Integration
                     if let Some(agent) = &mut self.ai_agent {
@@ -265,6 +273,41 @@ impl ShowdownClient {
         Ok(())
     }
 
+    /// Adds the finished battle to the library team's win record
+    fn record_result(&self) {
+        let Some((ref library, ref id)) = self.library_team else {
+            return;
+        };
+        let outcome = self
+            .event_logs
+            .events
+            .iter()
+            .flatten()
+            .chain(&self.event_logs.event_buffer)
+            .find_map(|token| match token {
+                Token::WIN(winner) if to_id(winner) == to_id(&self.event_logs.assist) => {
+                    Some(Outcome::Win)
+                }
+                Token::WIN(_) => Some(Outcome::Loss),
+                Token::TIE => Some(Outcome::Tie),
+                _ => None,
+            });
+        let Some(outcome) = outcome else {
+            return;
+        };
+        match library.record_result(id, outcome) {
+            Ok(entry) => println!(
+                "{}",
+                format!(
+                    "Recorded {:?} for {} ({})",
+                    outcome, entry.name, entry.record
+                )
+                .green()
+            ),
+            Err(e) => eprintln!("{}", format!("Failed to record result: {}", e).red()),
+        }
+    }
+
     #[allow(dead_code)]
     fn debug_turn_print(&self, line: &str) {
         if line.contains("|turn|") {
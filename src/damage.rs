@@ -0,0 +1,1391 @@
+//! Damage calculation for Generations 3 through 9.
+//!
+//! Follows the games' formulas in the order Showdown's damage calculator applies them: base
+//! damage from level, power and the attacking and defending stats, then weather, critical hits,
+//! the 16 random rolls, STAB, type effectiveness, burn and the final modifiers from screens,
+//! items and abilities. From Gen 5 modifiers are chained in 4096ths and rounded the way the
+//! games do; Gens 3 and 4 apply them one at a time, flooring after each.
+
+use crate::analysis::Weather;
+use crate::data::{Dex, Move, Species};
+use crate::id::to_id;
+use crate::parser::team::Pokemon;
+use crate::stats::{Stat, Stats, apply_stage};
+use crate::types::{Matchup, Type};
+use anyhow::{Result, anyhow, bail};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terrain {
+    Electric,
+    Grassy,
+    Psychic,
+    Misty,
+}
+
+impl Terrain {
+    /// "Electric Terrain" or "electricterrain"
+    pub fn from_name(name: &str) -> Option<Terrain> {
+        match to_id(name).trim_end_matches("terrain") {
+            "electric" => Some(Terrain::Electric),
+            "grassy" => Some(Terrain::Grassy),
+            "psychic" => Some(Terrain::Psychic),
+            "misty" => Some(Terrain::Misty),
+            _ => None,
+        }
+    }
+
+    fn boosted_type(&self) -> Type {
+        match self {
+            Terrain::Electric => Type::Electric,
+            Terrain::Grassy => Type::Grass,
+            Terrain::Psychic => Type::Psychic,
+            Terrain::Misty => Type::Fairy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    Burn,
+    Paralysis,
+    Poison,
+    Sleep,
+    Freeze,
+}
+
+impl Status {
+    /// Protocol ids: "brn", "par", "psn", "tox", "slp", "frz"
+    pub fn from_id(id: &str) -> Option<Status> {
+        match id.trim() {
+            "brn" => Some(Status::Burn),
+            "par" => Some(Status::Paralysis),
+            "psn" | "tox" => Some(Status::Poison),
+            "slp" => Some(Status::Sleep),
+            "frz" => Some(Status::Freeze),
+            _ => None,
+        }
+    }
+}
+
+/// Stat stages, from -6 to +6
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Boosts {
+    pub atk: i8,
+    pub def: i8,
+    pub spa: i8,
+    pub spd: i8,
+    pub spe: i8,
+}
+
+impl Boosts {
+    pub fn get(&self, stat: Stat) -> i8 {
+        match stat {
+            Stat::HP => 0,
+            Stat::Atk => self.atk,
+            Stat::Def => self.def,
+            Stat::SpA => self.spa,
+            Stat::SpD => self.spd,
+            Stat::Spe => self.spe,
+        }
+    }
+
+    /// Sets a stage, clamped to -6..=6. HP has no stage and is ignored.
+    pub fn set(&mut self, stat: Stat, stage: i8) {
+        let stage = stage.clamp(-6, 6);
+        match stat {
+            Stat::HP => {}
+            Stat::Atk => self.atk = stage,
+            Stat::Def => self.def = stage,
+            Stat::SpA => self.spa = stage,
+            Stat::SpD => self.spd = stage,
+            Stat::Spe => self.spe = stage,
+        }
+    }
+}
+
+/// A Pokémon in battle: its set and what has happened to it so far
+#[derive(Debug, Clone, Default)]
+pub struct Combatant {
+    pub pokemon: Pokemon,
+    pub boosts: Boosts,
+    pub status: Option<Status>,
+    /// Current HP as a fraction of the max, full when `None`
+    pub hp: Option<f64>,
+    /// The type it Terastallized into
+    pub tera_type: Option<Type>,
+}
+
+impl From<Pokemon> for Combatant {
+    fn from(pokemon: Pokemon) -> Self {
+        Combatant {
+            pokemon,
+            ..Default::default()
+        }
+    }
+}
+
+/// Screens on the defending side
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Screens {
+    pub reflect: bool,
+    pub light_screen: bool,
+    pub aurora_veil: bool,
+}
+
+/// Conditions the hit happens under
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Field {
+    pub weather: Option<Weather>,
+    pub terrain: Option<Terrain>,
+    /// Screens on the defender's side
+    pub screens: Screens,
+}
+
+/// Damage of one move against one defender, for every random roll
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageResult {
+    /// "252+ Atk Choice Band Dragonite Outrage vs. 252 HP / 0 Def Tyranitar"
+    pub description: String,
+    /// Damage of one hit for each of the 16 random rolls, lowest first
+    pub rolls: Vec<u16>,
+    /// The same for a critical hit
+    pub crit_rolls: Vec<u16>,
+    /// Hits per use, more than one for multi-hit moves
+    pub hits: u8,
+    pub max_hp: u16,
+    pub current_hp: u16,
+    /// The attacker's stat the hit used, `None` when it used the defender's (Foul Play)
+    pub attack_stat: Option<Stat>,
+    pub defense_stat: Stat,
+}
+
+impl DamageResult {
+    pub fn min(&self) -> u32 {
+        u32::from(self.rolls.first().copied().unwrap_or(0)) * u32::from(self.hits)
+    }
+
+    pub fn max(&self) -> u32 {
+        u32::from(self.rolls.last().copied().unwrap_or(0)) * u32::from(self.hits)
+    }
+
+    /// Lowest and highest damage of one use as fractions of the defender's max HP
+    pub fn range(&self) -> (f64, f64) {
+        let hp = f64::from(self.max_hp.max(1));
+        (f64::from(self.min()) / hp, f64::from(self.max()) / hp)
+    }
+
+    /// Chance that `uses` uses of the move KO from the defender's current HP, without crits
+    pub fn ko_chance(&self, uses: u32) -> f64 {
+        let hp = u32::from(self.current_hp);
+        if hp == 0 {
+            return 1.0;
+        }
+        if self.max() == 0 || uses == 0 {
+            return 0.0;
+        }
+        // Damage distribution with everything at or past the KO point merged into `hp`
+        let mut distribution: BTreeMap<u32, f64> = BTreeMap::from([(0, 1.0)]);
+        let roll_chance = 1.0 / self.rolls.len() as f64;
+        for _ in 0..uses * u32::from(self.hits) {
+            let mut next = BTreeMap::new();
+            for (damage, p) in distribution {
+                for roll in &self.rolls {
+                    *next
+                        .entry((damage + u32::from(*roll)).min(hp))
+                        .or_insert(0.0) += p * roll_chance;
+                }
+            }
+            distribution = next;
+        }
+        distribution.get(&hp).copied().unwrap_or(0.0)
+    }
+
+    /// Fewest uses, up to four, with a chance to KO and that chance
+    pub fn ko(&self) -> Option<(u32, f64)> {
+        (1..=4)
+            .map(|uses| (uses, self.ko_chance(uses)))
+            .find(|(_, chance)| *chance > 0.0)
+    }
+
+    /// "guaranteed OHKO", "43.8% chance to 2HKO", or "possible 5HKO or worse"
+    pub fn ko_text(&self) -> String {
+        let name = |uses: u32| match uses {
+            1 => "OHKO".to_string(),
+            n => format!("{}HKO", n),
+        };
+        match self.ko() {
+            _ if self.max() == 0 => "no damage".to_string(),
+            Some((uses, chance)) if chance >= 1.0 - 1e-9 => {
+                format!("guaranteed {}", name(uses))
+            }
+            Some((uses, chance)) => format!("{:.1}% chance to {}", chance * 100.0, name(uses)),
+            None => "possible 5HKO or worse".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for DamageResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (low, high) = self.range();
+        write!(
+            f,
+            "{}: {}-{} ({:.1} - {:.1}%) -- {}",
+            self.description,
+            self.min(),
+            self.max(),
+            low * 100.0,
+            high * 100.0,
+            self.ko_text()
+        )
+    }
+}
+
+/// Multipliers in 4096ths, the games' fixed-point format
+const HALF: u32 = 2048;
+const THREE_QUARTERS: u32 = 3072;
+const TENTH_UP: u32 = 4505;
+const FIFTH_UP: u32 = 4915;
+const LIFE_ORB: u32 = 5324;
+const THIRD_UP: u32 = 5325;
+const FOUR_THIRDS: u32 = 5461;
+const HALF_UP: u32 = 6144;
+const DOUBLE: u32 = 8192;
+
+/// Rounds half down, like the games
+fn poke_round(value: f64) -> u32 {
+    if value.fract() > 0.5 {
+        value.ceil() as u32
+    } else {
+        value.floor() as u32
+    }
+}
+
+fn chain(mods: &[u32]) -> u32 {
+    mods.iter()
+        .fold(4096u64, |m, x| (m * u64::from(*x) + 2048) >> 12) as u32
+}
+
+/// The 4096ths as the decimal Gens 3 and 4 use, e.g. 4915 -> 1.2
+fn decimal(m: u32) -> f64 {
+    (f64::from(m) / 4096.0 * 100.0).round() / 100.0
+}
+
+/// Applies modifiers to a stat or power: chained from Gen 5, one at a time before
+fn modify(r#gen: u8, value: u32, mods: &[u32]) -> u32 {
+    if r#gen >= 5 {
+        poke_round(f64::from(value) * f64::from(chain(mods)) / 4096.0)
+    } else {
+        mods.iter()
+            .fold(value, |v, m| (f64::from(v) * decimal(*m)).floor() as u32)
+    }
+}
+
+/// Gen 3 splits moves into physical and special by type
+fn physical_type(r#type: Type) -> bool {
+    matches!(
+        r#type,
+        Type::Normal
+            | Type::Fighting
+            | Type::Flying
+            | Type::Poison
+            | Type::Ground
+            | Type::Rock
+            | Type::Bug
+            | Type::Ghost
+            | Type::Steel
+    )
+}
+
+/// Items that boost one type's moves by 20% (10% in Gen 3)
+pub(crate) fn type_boosting_item(item: &str) -> Option<Type> {
+    let r#type = match item {
+        "silkscarf" => Type::Normal,
+        "blackbelt" | "fistplate" => Type::Fighting,
+        "sharpbeak" | "skyplate" => Type::Flying,
+        "poisonbarb" | "toxicplate" => Type::Poison,
+        "softsand" | "earthplate" => Type::Ground,
+        "hardstone" | "stoneplate" | "rockincense" => Type::Rock,
+        "silverpowder" | "insectplate" => Type::Bug,
+        "spelltag" | "spookyplate" => Type::Ghost,
+        "metalcoat" | "ironplate" => Type::Steel,
+        "charcoal" | "flameplate" => Type::Fire,
+        "mysticwater" | "splashplate" | "seaincense" | "waveincense" => Type::Water,
+        "miracleseed" | "meadowplate" | "roseincense" => Type::Grass,
+        "magnet" | "zapplate" => Type::Electric,
+        "twistedspoon" | "mindplate" | "oddincense" => Type::Psychic,
+        "nevermeltice" | "icicleplate" => Type::Ice,
+        "dragonfang" | "dracoplate" => Type::Dragon,
+        "blackglasses" | "dreadplate" => Type::Dark,
+        "pixieplate" | "fairyfeather" => Type::Fairy,
+        _ => return None,
+    };
+    Some(r#type)
+}
+
+/// Berries that halve a super effective hit of one type (Chilan: any Normal hit)
+fn resist_berry(item: &str) -> Option<Type> {
+    let r#type = match item {
+        "chilanberry" => Type::Normal,
+        "chopleberry" => Type::Fighting,
+        "cobaberry" => Type::Flying,
+        "kebiaberry" => Type::Poison,
+        "shucaberry" => Type::Ground,
+        "chartiberry" => Type::Rock,
+        "tangaberry" => Type::Bug,
+        "kasibberry" => Type::Ghost,
+        "babiriberry" => Type::Steel,
+        "occaberry" => Type::Fire,
+        "passhoberry" => Type::Water,
+        "rindoberry" => Type::Grass,
+        "wacanberry" => Type::Electric,
+        "payapaberry" => Type::Psychic,
+        "yacheberry" => Type::Ice,
+        "habanberry" => Type::Dragon,
+        "colburberry" => Type::Dark,
+        "roseliberry" => Type::Fairy,
+        _ => return None,
+    };
+    Some(r#type)
+}
+
+/// Everything about one side of the hit that doesn't depend on the roll
+struct Side<'a> {
+    combatant: &'a Combatant,
+    species: Species,
+    stats: Stats,
+    types: Vec<Type>,
+    ability: String,
+    item: String,
+}
+
+impl<'a> Side<'a> {
+    fn new(dex: &Dex, r#gen: u8, combatant: &'a Combatant) -> Result<Side<'a>> {
+        let pkmn = &combatant.pokemon;
+        let name = pkmn.species.as_deref().unwrap_or(&pkmn.name);
+        let species = dex
+            .get_species(name)
+            .ok_or_else(|| anyhow!("Unknown species: {}", name))?
+            .in_gen(r#gen);
+        let stats = pkmn.stats(r#gen, &species.base_stats);
+        let types = species
+            .types
+            .iter()
+            .filter_map(|t| t.parse().ok())
+            .collect();
+        Ok(Side {
+            combatant,
+            stats,
+            types,
+            species,
+            ability: pkmn.ability.as_deref().map(to_id).unwrap_or_default(),
+            item: pkmn.item.as_deref().map(to_id).unwrap_or_default(),
+        })
+    }
+
+    /// Types after Terastallization
+    fn defending_types(&self) -> Vec<Type> {
+        match self.combatant.tera_type {
+            Some(tera) => vec![tera],
+            None => self.types.clone(),
+        }
+    }
+
+    fn hp_fraction(&self) -> f64 {
+        self.combatant.hp.unwrap_or(1.0)
+    }
+
+    fn current_hp(&self) -> u16 {
+        (f64::from(self.stats.hp) * self.hp_fraction()).round() as u16
+    }
+
+    fn grounded(&self) -> bool {
+        !self.defending_types().contains(&Type::Flying)
+            && self.ability != "levitate"
+            && self.item != "airballoon"
+    }
+
+    fn statused(&self) -> bool {
+        self.combatant.status.is_some()
+    }
+
+    fn boosted(&self, r#gen: u8, stat: Stat, stage: i8) -> u32 {
+        u32::from(apply_stage(r#gen, self.stats.get(stat), stage))
+    }
+
+    /// Protosynthesis and Quark Drive boost the highest stat when active
+    fn paradox_boost(&self, field: &Field) -> Option<Stat> {
+        let active = match self.ability.as_str() {
+            "protosynthesis" => field.weather == Some(Weather::Sun),
+            "quarkdrive" => field.terrain == Some(Terrain::Electric),
+            _ => return None,
+        } || self.item == "boosterenergy";
+        active.then(|| {
+            [Stat::Atk, Stat::Def, Stat::SpA, Stat::SpD, Stat::Spe]
+                .into_iter()
+                .rev()
+                .max_by_key(|s| self.stats.get(*s))
+                .unwrap_or(Stat::Atk)
+        })
+    }
+}
+
+/// A resolved move: its type, power and category as this attacker uses it
+struct Attack<'a> {
+    mv: &'a Move,
+    id: String,
+    r#type: Type,
+    power: u32,
+    physical: bool,
+    hits: u8,
+}
+
+/// What ended up mattering, for the description
+#[derive(Default)]
+struct Notes {
+    attacker: Vec<String>,
+    defender: Vec<String>,
+}
+
+struct Calc<'a> {
+    r#gen: u8,
+    attacker: Side<'a>,
+    defender: Side<'a>,
+    attack: Attack<'a>,
+    field: Field,
+    /// Mold Breaker and friends ignore the defender's ability
+    defender_ability: String,
+}
+
+fn item_name(pkmn: &Pokemon) -> String {
+    pkmn.item.clone().unwrap_or_default()
+}
+
+fn ability_name(pkmn: &Pokemon) -> String {
+    pkmn.ability.clone().unwrap_or_default()
+}
+
+/// Damage rolls of `move_name` from `attacker` against `defender` in `gen`, from 3 to 9
+pub fn calculate(
+    r#gen: u8,
+    attacker: &Combatant,
+    defender: &Combatant,
+    move_name: &str,
+    field: &Field,
+) -> Result<DamageResult> {
+    if !(3..=9).contains(&r#gen) {
+        bail!("Damage calculation covers Gens 3 to 9, not Gen {}", r#gen);
+    }
+    let dex = Dex::get();
+    let mv = dex
+        .get_move(move_name)
+        .ok_or_else(|| anyhow!("Unknown move: {}", move_name))?;
+    if mv.category == "Status" {
+        bail!("{} is a status move", mv.name);
+    }
+    if mv.ohko.is_some() {
+        bail!("{} is a one-hit KO move", mv.name);
+    }
+    let attacker_side = Side::new(dex, r#gen, attacker)?;
+    let defender_side = Side::new(dex, r#gen, defender)?;
+
+    // Cloud Nine and Air Lock suppress the weather for everyone
+    let mut field = *field;
+    if [&attacker_side.ability, &defender_side.ability]
+        .iter()
+        .any(|a| matches!(a.as_str(), "cloudnine" | "airlock"))
+    {
+        field.weather = None;
+    }
+    let defender_ability = if matches!(
+        attacker_side.ability.as_str(),
+        "moldbreaker" | "teravolt" | "turboblaze"
+    ) {
+        String::new()
+    } else {
+        defender_side.ability.clone()
+    };
+    let attack = resolve(r#gen, mv, move_name, &attacker_side, &defender_side, &field)?;
+    let calc = Calc {
+        r#gen,
+        attacker: attacker_side,
+        defender: defender_side,
+        attack,
+        field,
+        defender_ability,
+    };
+
+    let mut notes = Notes::default();
+    let always_crits = mv
+        .extra
+        .get("willCrit")
+        .is_some_and(|v| v == &Value::Bool(true));
+    let rolls = calc.rolls(always_crits, &mut notes);
+    let crit_rolls = calc.rolls(true, &mut Notes::default());
+    Ok(DamageResult {
+        description: calc.describe(&notes, always_crits),
+        rolls,
+        crit_rolls,
+        hits: calc.attack.hits,
+        max_hp: calc.defender.stats.hp,
+        current_hp: calc.defender.current_hp(),
+        attack_stat: (!calc.uses_target_stat()).then(|| calc.offensive_stat()),
+        defense_stat: calc.defensive_stat(),
+    })
+}
+
+/// Works out the move's type, power, category and hit count
+fn resolve<'a>(
+    r#gen: u8,
+    mv: &'a Move,
+    move_name: &str,
+    attacker: &Side,
+    defender: &Side,
+    field: &Field,
+) -> Result<Attack<'a>> {
+    let id = to_id(&mv.name);
+    let mut r#type: Type = mv
+        .r#type
+        .parse()
+        .map_err(|_| anyhow!("{} has an unknown type", mv.name))?;
+    let mut power = u32::from(mv.base_power);
+
+    if id == "hiddenpower" {
+        // "Hidden Power [Ice]" carries its type in the name
+        if let Some(start) = move_name.find('[')
+            && let Some(end) = move_name.find(']')
+            && let Ok(hidden) = move_name[start + 1..end].parse()
+        {
+            r#type = hidden;
+        }
+        power = if r#gen >= 6 { 60 } else { 70 };
+    }
+    match id.as_str() {
+        "weatherball" if field.weather.is_some() => {
+            r#type = match field.weather {
+                Some(Weather::Sun) => Type::Fire,
+                Some(Weather::Rain) => Type::Water,
+                Some(Weather::Sand) => Type::Rock,
+                _ => Type::Ice,
+            };
+            power *= 2;
+        }
+        "terrainpulse" if attacker.grounded() => {
+            if let Some(terrain) = field.terrain {
+                r#type = terrain.boosted_type();
+                power *= 2;
+            }
+        }
+        "terablast" | "terastarstorm" => {
+            if let Some(tera) = attacker.combatant.tera_type {
+                r#type = tera;
+            }
+        }
+        _ => {}
+    }
+    if r#type == Type::Normal {
+        let converted = match attacker.ability.as_str() {
+            "pixilate" => Some(Type::Fairy),
+            "aerilate" => Some(Type::Flying),
+            "refrigerate" => Some(Type::Ice),
+            "galvanize" => Some(Type::Electric),
+            _ => None,
+        };
+        if let Some(converted) = converted {
+            r#type = converted;
+        }
+    }
+    if attacker.ability == "normalize" {
+        r#type = Type::Normal;
+    }
+
+    if power == 0 {
+        power = variable_power(&id, attacker, defender)
+            .ok_or_else(|| anyhow!("{} has a power the calculator can't work out", mv.name))?;
+    }
+
+    let physical = if r#gen <= 3 {
+        physical_type(r#type)
+    } else if id == "terablast" && attacker.combatant.tera_type.is_some() {
+        let boosts = attacker.combatant.boosts;
+        attacker.boosted(r#gen, Stat::Atk, boosts.atk)
+            > attacker.boosted(r#gen, Stat::SpA, boosts.spa)
+    } else {
+        mv.category == "Physical"
+    };
+
+    let hits = match mv.extra.get("multihit") {
+        Some(Value::Number(n)) => n.as_u64().unwrap_or(1) as u8,
+        Some(Value::Array(range)) => {
+            let max = range.last().and_then(Value::as_u64).unwrap_or(1) as u8;
+            match attacker.ability.as_str() {
+                "skilllink" => max,
+                _ if attacker.item == "loadeddice" => max.min(4),
+                _ => 3.min(max),
+            }
+        }
+        _ => 1,
+    };
+
+    Ok(Attack {
+        mv,
+        id,
+        r#type,
+        power,
+        physical,
+        hits,
+    })
+}
+
+/// Power of moves whose base power depends on the battlers
+fn variable_power(id: &str, attacker: &Side, defender: &Side) -> Option<u32> {
+    let weight = f64::from(defender.species.weightkg);
+    match id {
+        "lowkick" | "grassknot" => Some(match weight {
+            w if w >= 200.0 => 120,
+            w if w >= 100.0 => 100,
+            w if w >= 50.0 => 80,
+            w if w >= 25.0 => 60,
+            w if w >= 10.0 => 40,
+            _ => 20,
+        }),
+        "heavyslam" | "heatcrash" => {
+            let ratio = f64::from(attacker.species.weightkg) / weight.max(0.1);
+            Some(match ratio {
+                r if r >= 5.0 => 120,
+                r if r >= 4.0 => 100,
+                r if r >= 3.0 => 80,
+                r if r >= 2.0 => 60,
+                _ => 40,
+            })
+        }
+        "gyroball" => {
+            let user = u32::from(attacker.stats.spe).max(1);
+            Some((25 * u32::from(defender.stats.spe) / user + 1).min(150))
+        }
+        "return" | "frustration" => Some(102),
+        _ => None,
+    }
+}
+
+impl Calc<'_> {
+    /// The 16 rolls of one hit
+    fn rolls(&self, crit: bool, notes: &mut Notes) -> Vec<u16> {
+        let attack = &self.attack;
+        let types = self.defender.defending_types();
+        let matchup = self.matchup();
+        if matchup.multiplier(attack.r#type, &types) == 0.0 {
+            return vec![0; 16];
+        }
+        let effectiveness = f64::from(matchup.type_multiplier(attack.r#type, &types));
+
+        if let Some(fixed) = self.fixed_damage() {
+            return vec![fixed; 16];
+        }
+
+        let power = self.power(notes);
+        let attack_stat = self.attack_stat(crit, notes);
+        let defense_stat = self.defense_stat(crit, notes);
+        let damage = if self.r#gen >= 5 {
+            self.modern(power, attack_stat, defense_stat, effectiveness, crit, notes)
+        } else {
+            self.classic(power, attack_stat, defense_stat, &types, crit, notes)
+        };
+        damage
+            .into_iter()
+            .map(|d| d.min(u32::from(u16::MAX)) as u16)
+            .collect()
+    }
+
+    /// Seismic Toss, Night Shade and Super Fang ignore stats
+    fn fixed_damage(&self) -> Option<u16> {
+        match self.attack.mv.extra.get("damage") {
+            Some(Value::String(s)) if s == "level" => {
+                Some(u16::from(self.attacker.combatant.pokemon.effective_level()))
+            }
+            Some(Value::Number(n)) => n.as_u64().map(|n| n as u16),
+            _ => match self.attack.id.as_str() {
+                "superfang" | "naturesmadness" | "ruination" => {
+                    Some((self.defender.current_hp() / 2).max(1))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    fn power(&self, notes: &mut Notes) -> u32 {
+        let (r#gen, attack) = (self.r#gen, &self.attack);
+        let attacker = &self.attacker;
+        let flags = &attack.mv.flags;
+        let mut power = attack.power;
+        let mut mods = Vec::new();
+
+        match attack.id.as_str() {
+            "facade" if attacker.statused() => power *= 2,
+            "hex" if self.defender.statused() => power *= 2,
+            "venoshock" if self.defender.combatant.status == Some(Status::Poison) => power *= 2,
+            "brine" if self.defender.hp_fraction() <= 0.5 => power *= 2,
+            "acrobatics" if attacker.item.is_empty() => power *= 2,
+            "eruption" | "waterspout" | "dragonenergy" => {
+                power = ((f64::from(power) * attacker.hp_fraction()).floor() as u32).max(1)
+            }
+            "knockoff" if r#gen >= 6 && !self.defender.item.is_empty() => mods.push(HALF_UP),
+            "solarbeam" | "solarblade"
+                if matches!(
+                    self.field.weather,
+                    Some(Weather::Rain | Weather::Sand | Weather::Snow)
+                ) =>
+            {
+                mods.push(HALF)
+            }
+            _ => {}
+        }
+
+        let has_secondary = attack.mv.extra.contains_key("secondary")
+            || attack.mv.extra.contains_key("secondaries");
+        let ability_mod = match attacker.ability.as_str() {
+            "technician" if power <= 60 => Some(HALF_UP),
+            "toughclaws" if flags.contains_key("contact") => Some(THIRD_UP),
+            "ironfist" if flags.contains_key("punch") => Some(FIFTH_UP),
+            "strongjaw" if flags.contains_key("bite") => Some(HALF_UP),
+            "megalauncher" if flags.contains_key("pulse") => Some(HALF_UP),
+            "sharpness" if flags.contains_key("slicing") => Some(HALF_UP),
+            "punkrock" if flags.contains_key("sound") => Some(THIRD_UP),
+            "reckless"
+                if attack.mv.extra.contains_key("recoil")
+                    || attack.mv.extra.contains_key("hasCrashDamage") =>
+            {
+                Some(FIFTH_UP)
+            }
+            "sheerforce" if has_secondary => Some(THIRD_UP),
+            "sandforce"
+                if self.field.weather == Some(Weather::Sand)
+                    && matches!(attack.r#type, Type::Rock | Type::Ground | Type::Steel) =>
+            {
+                Some(THIRD_UP)
+            }
+            "pixilate" | "aerilate" | "refrigerate" | "galvanize"
+                if attack.mv.r#type == "Normal" =>
+            {
+                Some(if r#gen >= 7 { FIFTH_UP } else { THIRD_UP })
+            }
+            "normalize" if r#gen >= 7 => Some(FIFTH_UP),
+            _ => None,
+        };
+        if let Some(m) = ability_mod {
+            mods.push(m);
+            notes
+                .attacker
+                .push(ability_name(&attacker.combatant.pokemon));
+        }
+
+        // Gen 3 type items boost the attacking stat instead, see `attack_stat`
+        if r#gen >= 4 {
+            let item_mod = match attacker.item.as_str() {
+                item if type_boosting_item(item) == Some(attack.r#type) => Some(FIFTH_UP),
+                "musclyband" | "muscleband" if attack.physical => Some(TENTH_UP),
+                "wiseglasses" if !attack.physical => Some(TENTH_UP),
+                item if item.ends_with("gem")
+                    && to_id(attack.r#type.name()) == item.trim_end_matches("gem") =>
+                {
+                    Some(if r#gen >= 6 { THIRD_UP } else { HALF_UP })
+                }
+                _ => None,
+            };
+            if let Some(m) = item_mod {
+                mods.push(m);
+                notes.attacker.push(item_name(&attacker.combatant.pokemon));
+            }
+        }
+
+        if let Some(terrain) = self.field.terrain {
+            if attacker.grounded()
+                && terrain.boosted_type() == attack.r#type
+                && terrain != Terrain::Misty
+            {
+                mods.push(if r#gen >= 8 { THIRD_UP } else { HALF_UP });
+            }
+            if self.defender.grounded()
+                && ((terrain == Terrain::Misty && attack.r#type == Type::Dragon)
+                    || (terrain == Terrain::Grassy
+                        && matches!(attack.id.as_str(), "earthquake" | "bulldoze" | "magnitude")))
+            {
+                mods.push(HALF);
+            }
+        }
+
+        match (self.defender_ability.as_str(), attack.r#type) {
+            ("heatproof", Type::Fire) => mods.push(HALF),
+            ("dryskin", Type::Fire) => mods.push(5120),
+            _ => {}
+        }
+
+        modify(r#gen, power, &mods).max(1)
+    }
+
+    /// The move's effectiveness rules for this attacker and defender, the Tera type left to
+    /// [`Side::defending_types`]
+    fn matchup(&self) -> Matchup {
+        Matchup {
+            r#gen: self.r#gen,
+            move_name: Some(self.attack.id.clone()),
+            attacker_ability: Some(self.attacker.ability.clone()),
+            defender_ability: Some(self.defender_ability.clone()),
+            defender_item: Some(self.defender.item.clone()),
+            tera_type: None,
+        }
+    }
+
+    /// Foul Play uses the target's Attack
+    fn uses_target_stat(&self) -> bool {
+        self.attack
+            .mv
+            .extra
+            .get("overrideOffensivePokemon")
+            .is_some_and(|v| v == "target")
+    }
+
+    /// Body Press uses Defense instead of Attack
+    fn offensive_stat(&self) -> Stat {
+        match self
+            .attack
+            .mv
+            .extra
+            .get("overrideOffensiveStat")
+            .and_then(Value::as_str)
+        {
+            Some("def") => Stat::Def,
+            _ if self.attack.physical => Stat::Atk,
+            _ => Stat::SpA,
+        }
+    }
+
+    /// Psyshock and friends hit Defense with a special move
+    fn defensive_stat(&self) -> Stat {
+        match self
+            .attack
+            .mv
+            .extra
+            .get("overrideDefensiveStat")
+            .and_then(Value::as_str)
+        {
+            Some("def") => Stat::Def,
+            Some("spd") => Stat::SpD,
+            _ if self.attack.physical => Stat::Def,
+            _ => Stat::SpD,
+        }
+    }
+
+    fn attack_stat(&self, crit: bool, notes: &mut Notes) -> u32 {
+        let (r#gen, attack) = (self.r#gen, &self.attack);
+        let source = if self.uses_target_stat() {
+            &self.defender
+        } else {
+            &self.attacker
+        };
+        let stat = self.offensive_stat();
+        let mut stage = source.combatant.boosts.get(stat);
+        if self.defender_ability == "unaware" || (crit && stage < 0) {
+            stage = 0;
+        }
+        let value = source.boosted(r#gen, stat, stage);
+
+        let attacker = &self.attacker;
+        let mut mods = Vec::new();
+        let mut note = |mods: &mut Vec<u32>, m: u32, name: String| {
+            mods.push(m);
+            notes.attacker.push(name);
+        };
+        let pkmn = &attacker.combatant.pokemon;
+        let low_hp = attacker.hp_fraction() <= 1.0 / 3.0;
+        match attacker.ability.as_str() {
+            "hugepower" | "purepower" if attack.physical => {
+                note(&mut mods, DOUBLE, ability_name(pkmn))
+            }
+            "hustle" if attack.physical => note(&mut mods, HALF_UP, ability_name(pkmn)),
+            "guts" if attack.physical && attacker.statused() => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "solarpower" if !attack.physical && self.field.weather == Some(Weather::Sun) => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "gorillatactics" if attack.physical => note(&mut mods, HALF_UP, ability_name(pkmn)),
+            "defeatist" if attacker.hp_fraction() <= 0.5 => {
+                note(&mut mods, HALF, ability_name(pkmn))
+            }
+            "blaze" if low_hp && attack.r#type == Type::Fire => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "torrent" if low_hp && attack.r#type == Type::Water => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "overgrow" if low_hp && attack.r#type == Type::Grass => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "swarm" if low_hp && attack.r#type == Type::Bug => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "waterbubble" if attack.r#type == Type::Water => {
+                note(&mut mods, DOUBLE, ability_name(pkmn))
+            }
+            "steelworker" | "steelyspirit" if attack.r#type == Type::Steel => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "transistor" if attack.r#type == Type::Electric => note(
+                &mut mods,
+                if r#gen >= 9 { THIRD_UP } else { HALF_UP },
+                ability_name(pkmn),
+            ),
+            "dragonsmaw" if attack.r#type == Type::Dragon => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "rockypayload" if attack.r#type == Type::Rock => {
+                note(&mut mods, HALF_UP, ability_name(pkmn))
+            }
+            "orichalcumpulse" if attack.physical && self.field.weather == Some(Weather::Sun) => {
+                note(&mut mods, FOUR_THIRDS, ability_name(pkmn))
+            }
+            "hadronengine" if !attack.physical && self.field.terrain == Some(Terrain::Electric) => {
+                note(&mut mods, FOUR_THIRDS, ability_name(pkmn))
+            }
+            _ => {}
+        }
+        if attacker.paradox_boost(&self.field) == Some(stat) {
+            note(&mut mods, THIRD_UP, ability_name(pkmn));
+        }
+
+        match attacker.item.as_str() {
+            "choiceband" if attack.physical => note(&mut mods, HALF_UP, item_name(pkmn)),
+            "choicespecs" if !attack.physical => note(&mut mods, HALF_UP, item_name(pkmn)),
+            "lightball" if to_id(&attacker.species.name).starts_with("pikachu") => {
+                note(&mut mods, DOUBLE, item_name(pkmn))
+            }
+            "thickclub"
+                if attack.physical
+                    && matches!(
+                        to_id(&attacker.species.name).as_str(),
+                        "cubone" | "marowak" | "marowakalola"
+                    ) =>
+            {
+                note(&mut mods, DOUBLE, item_name(pkmn))
+            }
+            item if r#gen == 3 && type_boosting_item(item) == Some(attack.r#type) => {
+                note(&mut mods, TENTH_UP, item_name(pkmn))
+            }
+            _ => {}
+        }
+
+        let defender_mod = match (self.defender_ability.as_str(), attack.r#type) {
+            ("thickfat", Type::Fire | Type::Ice) => Some(HALF),
+            ("waterbubble", Type::Fire) if r#gen >= 7 => Some(HALF),
+            ("purifyingsalt", Type::Ghost) => Some(HALF),
+            _ => None,
+        };
+        if let Some(m) = defender_mod {
+            mods.push(m);
+            notes
+                .defender
+                .push(ability_name(&self.defender.combatant.pokemon));
+        }
+
+        modify(r#gen, value, &mods).max(1)
+    }
+
+    fn defense_stat(&self, crit: bool, notes: &mut Notes) -> u32 {
+        let (r#gen, attack) = (self.r#gen, &self.attack);
+        let defender = &self.defender;
+        let stat = self.defensive_stat();
+        let mut stage = defender.combatant.boosts.get(stat);
+        if self.attacker.ability == "unaware"
+            || attack.mv.extra.contains_key("ignoreDefensive")
+            || (crit && stage > 0)
+        {
+            stage = 0;
+        }
+        let value = defender.boosted(r#gen, stat, stage);
+
+        let pkmn = &defender.combatant.pokemon;
+        let mut mods = Vec::new();
+        // Sand boosts Rock types' Special Defense from Gen 4, snow Ice types' Defense in Gen 9
+        let types = defender.defending_types();
+        match (self.field.weather, stat) {
+            (Some(Weather::Sand), Stat::SpD) if r#gen >= 4 && types.contains(&Type::Rock) => {
+                mods.push(HALF_UP)
+            }
+            (Some(Weather::Snow), Stat::Def) if r#gen >= 9 && types.contains(&Type::Ice) => {
+                mods.push(HALF_UP)
+            }
+            _ => {}
+        }
+        let ability_mod = match self.defender_ability.as_str() {
+            "furcoat" if stat == Stat::Def => Some(DOUBLE),
+            "marvelscale" if stat == Stat::Def && defender.statused() => Some(HALF_UP),
+            "grasspelt" if stat == Stat::Def && self.field.terrain == Some(Terrain::Grassy) => {
+                Some(HALF_UP)
+            }
+            _ if !self.defender_ability.is_empty()
+                && defender.paradox_boost(&self.field) == Some(stat) =>
+            {
+                Some(THIRD_UP)
+            }
+            _ => None,
+        };
+        if let Some(m) = ability_mod {
+            mods.push(m);
+            notes.defender.push(ability_name(pkmn));
+        }
+        let item_mod = match defender.item.as_str() {
+            "eviolite" if !defender.species.evos.is_empty() => Some(HALF_UP),
+            "assaultvest" if stat == Stat::SpD => Some(HALF_UP),
+            _ => None,
+        };
+        if let Some(m) = item_mod {
+            mods.push(m);
+            notes.defender.push(item_name(pkmn));
+        }
+
+        modify(r#gen, value, &mods).max(1)
+    }
+
+    /// The attacker's STAB in 4096ths, counting Terastallization
+    fn stab(&self) -> u32 {
+        let attacker = &self.attacker;
+        let r#type = self.attack.r#type;
+        let original = attacker.types.contains(&r#type);
+        let adaptability = attacker.ability == "adaptability";
+        match attacker.combatant.tera_type {
+            Some(tera) if tera == r#type && original => {
+                if adaptability {
+                    9216
+                } else {
+                    DOUBLE
+                }
+            }
+            Some(tera) if tera == r#type => {
+                if adaptability {
+                    DOUBLE
+                } else {
+                    HALF_UP
+                }
+            }
+            _ if original => {
+                if adaptability {
+                    DOUBLE
+                } else {
+                    HALF_UP
+                }
+            }
+            _ => 4096,
+        }
+    }
+
+    fn weather_mod(&self) -> u32 {
+        match (self.field.weather, self.attack.r#type) {
+            (Some(Weather::Sun), Type::Fire) | (Some(Weather::Rain), Type::Water) => HALF_UP,
+            (Some(Weather::Sun), Type::Water) | (Some(Weather::Rain), Type::Fire) => HALF,
+            _ => 4096,
+        }
+    }
+
+    /// Reflect, Light Screen or Aurora Veil, unless the hit is a crit or goes through them
+    fn screened(&self, crit: bool) -> bool {
+        let screens = self.field.screens;
+        !crit
+            && self.attacker.ability != "infiltrator"
+            && (screens.aurora_veil
+                || (self.attack.physical && screens.reflect)
+                || (!self.attack.physical && screens.light_screen))
+    }
+
+    fn burned(&self) -> bool {
+        self.attack.physical
+            && self.attacker.combatant.status == Some(Status::Burn)
+            && self.attacker.ability != "guts"
+            && !(self.r#gen >= 6 && self.attack.id == "facade")
+    }
+
+    /// Modifiers applied after type effectiveness in Gen 5 onward
+    fn final_mods(&self, effectiveness: f64, crit: bool, notes: &mut Notes) -> Vec<u32> {
+        let attack = &self.attack;
+        let (attacker, defender) = (&self.attacker, &self.defender);
+        let mut mods = Vec::new();
+        if self.screened(crit) {
+            mods.push(HALF);
+        }
+
+        let defender_mod = match self.defender_ability.as_str() {
+            "multiscale" | "shadowshield" if defender.hp_fraction() >= 1.0 => Some(HALF),
+            "fluffy" => {
+                match (
+                    attack.mv.flags.contains_key("contact"),
+                    attack.r#type == Type::Fire,
+                ) {
+                    (true, false) => Some(HALF),
+                    (false, true) => Some(DOUBLE),
+                    _ => None,
+                }
+            }
+            "punkrock" if attack.mv.flags.contains_key("sound") => Some(HALF),
+            "icescales" if !attack.physical => Some(HALF),
+            "filter" | "solidrock" | "prismarmor" if effectiveness > 1.0 => Some(THREE_QUARTERS),
+            _ => None,
+        };
+        if let Some(m) = defender_mod {
+            mods.push(m);
+            notes
+                .defender
+                .push(ability_name(&defender.combatant.pokemon));
+        }
+
+        let pkmn = &attacker.combatant.pokemon;
+        let attacker_mod = match attacker.ability.as_str() {
+            "tintedlens" if effectiveness < 1.0 => Some(DOUBLE),
+            "sniper" if crit => Some(HALF_UP),
+            _ => None,
+        };
+        if let Some(m) = attacker_mod {
+            mods.push(m);
+            notes.attacker.push(ability_name(pkmn));
+        }
+        let item_mod = match attacker.item.as_str() {
+            "expertbelt" if effectiveness > 1.0 => Some(FIFTH_UP),
+            "lifeorb" => Some(LIFE_ORB),
+            _ => None,
+        };
+        if let Some(m) = item_mod {
+            mods.push(m);
+            notes.attacker.push(item_name(pkmn));
+        }
+
+        if let Some(berry) = resist_berry(&defender.item)
+            && berry == attack.r#type
+            && (effectiveness > 1.0 || berry == Type::Normal)
+        {
+            mods.push(HALF);
+            notes.defender.push(item_name(&defender.combatant.pokemon));
+        }
+        mods
+    }
+
+    fn level(&self) -> u32 {
+        u32::from(self.attacker.combatant.pokemon.effective_level())
+    }
+
+    fn modern(
+        &self,
+        power: u32,
+        attack: u32,
+        defense: u32,
+        effectiveness: f64,
+        crit: bool,
+        notes: &mut Notes,
+    ) -> Vec<u32> {
+        let level = self.level();
+        let mut base = (2 * level / 5 + 2) * power * attack / defense / 50 + 2;
+        base = poke_round(f64::from(base) * f64::from(self.weather_mod()) / 4096.0);
+        if crit {
+            base = if self.r#gen >= 6 {
+                base * 3 / 2
+            } else {
+                base * 2
+            };
+        }
+        let stab = self.stab();
+        let final_mod = chain(&self.final_mods(effectiveness, crit, notes));
+        (85..=100)
+            .map(|roll| {
+                let mut damage = base * roll / 100;
+                damage = poke_round(f64::from(damage) * f64::from(stab) / 4096.0);
+                damage = (f64::from(damage) * effectiveness).floor() as u32;
+                if self.burned() {
+                    damage /= 2;
+                }
+                damage = poke_round(f64::from(damage) * f64::from(final_mod) / 4096.0);
+                damage.max(1)
+            })
+            .collect()
+    }
+
+    /// Gens 3 and 4, where each modifier floors and the order differs by generation
+    fn classic(
+        &self,
+        power: u32,
+        attack: u32,
+        defense: u32,
+        types: &[Type],
+        crit: bool,
+        notes: &mut Notes,
+    ) -> Vec<u32> {
+        let (r#gen, level) = (self.r#gen, self.level());
+        let mut base = if r#gen == 4 {
+            (2 * level / 5 + 2) * power * attack / 50 / defense
+        } else {
+            (2 * level / 5 + 2) * attack * power / defense / 50
+        };
+        if self.burned() {
+            base /= 2;
+        }
+        if self.screened(crit) {
+            base /= 2;
+        }
+        base = (f64::from(base) * decimal(self.weather_mod())).floor() as u32;
+        base += 2;
+        if crit {
+            base *= if self.attacker.ability == "sniper" {
+                3
+            } else {
+                2
+            };
+        }
+        if r#gen == 4 && self.attacker.item == "lifeorb" {
+            base = base * 13 / 10;
+            notes
+                .attacker
+                .push(item_name(&self.attacker.combatant.pokemon));
+        }
+
+        let matchup = self.matchup();
+        let effectiveness: f64 = types
+            .iter()
+            .map(|t| f64::from(matchup.against_type(self.attack.r#type, *t)))
+            .product();
+        let mut after = Vec::new();
+        if r#gen == 4 {
+            let defender = &self.defender;
+            match self.defender_ability.as_str() {
+                "filter" | "solidrock" if effectiveness > 1.0 => {
+                    after.push(0.75);
+                    notes
+                        .defender
+                        .push(ability_name(&defender.combatant.pokemon));
+                }
+                _ => {}
+            }
+            if self.attacker.item == "expertbelt" && effectiveness > 1.0 {
+                after.push(1.2);
+                notes
+                    .attacker
+                    .push(item_name(&self.attacker.combatant.pokemon));
+            }
+            if self.attacker.ability == "tintedlens" && effectiveness < 1.0 {
+                after.push(2.0);
+            }
+            if let Some(berry) = resist_berry(&defender.item)
+                && berry == self.attack.r#type
+                && (effectiveness > 1.0 || berry == Type::Normal)
+            {
+                after.push(0.5);
+                notes.defender.push(item_name(&defender.combatant.pokemon));
+            }
+        }
+        let stab = decimal(self.stab());
+
+        (85..=100)
+            .map(|roll| {
+                let mut damage = base;
+                if r#gen == 4 {
+                    damage = damage * roll / 100;
+                }
+                damage = (f64::from(damage) * stab).floor() as u32;
+                for t in types {
+                    let e = f64::from(matchup.against_type(self.attack.r#type, *t));
+                    damage = (f64::from(damage) * e).floor() as u32;
+                }
+                for m in &after {
+                    damage = (f64::from(damage) * m).floor() as u32;
+                }
+                if r#gen == 3 {
+                    damage = damage * roll / 100;
+                }
+                damage.max(1)
+            })
+            .collect()
+    }
+
+    fn describe(&self, notes: &Notes, crit: bool) -> String {
+        let attack = &self.attack;
+        let offensive = match attack
+            .mv
+            .extra
+            .get("overrideOffensiveStat")
+            .and_then(Value::as_str)
+        {
+            Some("def") => Stat::Def,
+            _ if attack.physical => Stat::Atk,
+            _ => Stat::SpA,
+        };
+        let defensive = match attack
+            .mv
+            .extra
+            .get("overrideDefensiveStat")
+            .and_then(Value::as_str)
+        {
+            Some("def") => Stat::Def,
+            _ if attack.physical => Stat::Def,
+            _ => Stat::SpD,
+        };
+        let investment = |side: &Side, stat: Stat| -> String {
+            let pkmn = &side.combatant.pokemon;
+            let sign = match pkmn.nature() {
+                Some(n) if n.plus() == Some(stat) => "+",
+                Some(n) if n.minus() == Some(stat) => "-",
+                _ => "",
+            };
+            let stage = side.combatant.boosts.get(stat);
+            let boost = if stage > 0 {
+                format!("+{} ", stage)
+            } else if stage < 0 {
+                format!("{} ", stage)
+            } else {
+                String::new()
+            };
+            format!("{}{}{} {}", boost, pkmn.evs.get(stat), sign, stat)
+        };
+        let name = |side: &Side, extras: &[String]| -> String {
+            let mut parts: Vec<String> = Vec::new();
+            for extra in extras {
+                if !extra.is_empty() && !parts.contains(extra) {
+                    parts.push(extra.clone());
+                }
+            }
+            if let Some(tera) = side.combatant.tera_type {
+                parts.push(format!("Tera {}", tera.name()));
+            }
+            parts.push(side.species.name.clone());
+            parts.join(" ")
+        };
+
+        let mut text = format!(
+            "{} {} {} vs. {} HP / {} {}",
+            investment(&self.attacker, offensive),
+            name(&self.attacker, &notes.attacker),
+            attack.mv.name,
+            self.defender.combatant.pokemon.evs.hp,
+            investment(&self.defender, defensive),
+            name(&self.defender, &notes.defender),
+        );
+        if let Some(weather) = self.field.weather {
+            text.push_str(&format!(" in {:?}", weather));
+        }
+        if let Some(terrain) = self.field.terrain {
+            text.push_str(&format!(" in {:?} Terrain", terrain));
+        }
+        if self.screened(crit) {
+            let screens = self.field.screens;
+            let screen = if screens.aurora_veil {
+                "Aurora Veil"
+            } else if attack.physical {
+                "Reflect"
+            } else {
+                "Light Screen"
+            };
+            text.push_str(&format!(" through {}", screen));
+        }
+        if crit {
+            text.push_str(" on a critical hit");
+        }
+        text
+    }
+}
@@ -0,0 +1,155 @@
+//! Showdown species names mapped to PokeAPI slugs and back.
+//!
+//! Most names become slugs by lowercasing and dropping punctuation ("Mr. Mime" is "mr-mime",
+//! "Charizard-Mega-X" is "charizard-mega-x"). The tables cover the rest: species whose default
+//! forme has a slug of its own ("Thundurus" is "thundurus-incarnate"), formes PokeAPI spells
+//! differently, and species PokeAPI describes once for all of their formes.
+
+use crate::id::to_id;
+
+/// Showdown species whose default forme PokeAPI names explicitly
+const DEFAULT_FORMES: &[(&str, &str)] = &[
+    ("Aegislash", "aegislash-shield"),
+    ("Basculegion", "basculegion-male"),
+    ("Basculin", "basculin-red-striped"),
+    ("Darmanitan", "darmanitan-standard"),
+    ("Deoxys", "deoxys-normal"),
+    ("Dudunsparce", "dudunsparce-two-segment"),
+    ("Eiscue", "eiscue-ice"),
+    ("Enamorus", "enamorus-incarnate"),
+    ("Giratina", "giratina-altered"),
+    ("Gourgeist", "gourgeist-average"),
+    ("Indeedee", "indeedee-male"),
+    ("Keldeo", "keldeo-ordinary"),
+    ("Landorus", "landorus-incarnate"),
+    ("Lycanroc", "lycanroc-midday"),
+    ("Maushold", "maushold-family-of-three"),
+    ("Meloetta", "meloetta-aria"),
+    ("Meowstic", "meowstic-male"),
+    ("Mimikyu", "mimikyu-disguised"),
+    ("Minior", "minior-red"),
+    ("Morpeko", "morpeko-full-belly"),
+    ("Oinkologne", "oinkologne-male"),
+    ("Oricorio", "oricorio-baile"),
+    ("Palafin", "palafin-zero"),
+    ("Pumpkaboo", "pumpkaboo-average"),
+    ("Shaymin", "shaymin-land"),
+    ("Squawkabilly", "squawkabilly-green-plumage"),
+    ("Tatsugiri", "tatsugiri-curly"),
+    ("Thundurus", "thundurus-incarnate"),
+    ("Tornadus", "tornadus-incarnate"),
+    ("Toxtricity", "toxtricity-amped"),
+    ("Urshifu", "urshifu-single-strike"),
+    ("Wishiwashi", "wishiwashi-solo"),
+    ("Wormadam", "wormadam-plant"),
+    ("Zygarde", "zygarde-50"),
+];
+
+/// Showdown formes whose slug isn't just the name lowercased
+const RENAMED_FORMES: &[(&str, &str)] = &[
+    ("Basculegion-F", "basculegion-female"),
+    ("Darmanitan-Galar", "darmanitan-galar-standard"),
+    ("Greninja-Bond", "greninja-battle-bond"),
+    ("Indeedee-F", "indeedee-female"),
+    ("Maushold-Four", "maushold-family-of-four"),
+    ("Meowstic-F", "meowstic-female"),
+    ("Minior-Meteor", "minior-red-meteor"),
+    ("Necrozma-Dawn-Wings", "necrozma-dawn"),
+    ("Necrozma-Dusk-Mane", "necrozma-dusk"),
+    ("Ogerpon-Cornerstone", "ogerpon-cornerstone-mask"),
+    ("Ogerpon-Hearthflame", "ogerpon-hearthflame-mask"),
+    ("Ogerpon-Wellspring", "ogerpon-wellspring-mask"),
+    ("Oinkologne-F", "oinkologne-female"),
+    // Pikachu in Ash's caps
+    ("Pikachu-Alola", "pikachu-alola-cap"),
+    ("Pikachu-Hoenn", "pikachu-hoenn-cap"),
+    ("Pikachu-Kalos", "pikachu-kalos-cap"),
+    ("Pikachu-Original", "pikachu-original-cap"),
+    ("Pikachu-Partner", "pikachu-partner-cap"),
+    ("Pikachu-Sinnoh", "pikachu-sinnoh-cap"),
+    ("Pikachu-Unova", "pikachu-unova-cap"),
+    ("Pikachu-World", "pikachu-world-cap"),
+    ("Squawkabilly-Blue", "squawkabilly-blue-plumage"),
+    ("Squawkabilly-White", "squawkabilly-white-plumage"),
+    ("Squawkabilly-Yellow", "squawkabilly-yellow-plumage"),
+    ("Tauros-Paldea-Aqua", "tauros-paldea-aqua-breed"),
+    ("Tauros-Paldea-Blaze", "tauros-paldea-blaze-breed"),
+    ("Tauros-Paldea-Combat", "tauros-paldea-combat-breed"),
+];
+
+/// Species PokeAPI has a single entry for: cosmetic formes ("Gastrodon-East"), formes that
+/// only differ by held item ("Arceus-Fire", "Genesect-Douse") and Cherrim's sunny forme
+const SINGLE_ENTRY_SPECIES: &[&str] = &[
+    "Alcremie",
+    "Arceus",
+    "Burmy",
+    "Cherrim",
+    "Deerling",
+    "Flabébé",
+    "Florges",
+    "Furfrou",
+    "Gastrodon",
+    "Genesect",
+    "Poltchageist",
+    "Polteageist",
+    "Sawsbuck",
+    "Shellos",
+    "Silvally",
+    "Sinistcha",
+    "Sinistea",
+    "Unown",
+    "Vivillon",
+];
+
+/// The PokeAPI slug for a Showdown species name: "Thundurus" is "thundurus-incarnate",
+/// "Gastrodon-East" is "gastrodon" and "Mr. Mime" is "mr-mime"
+pub fn pokeapi_slug(name: &str) -> String {
+    let id = to_id(name);
+    if let Some((_, slug)) = DEFAULT_FORMES
+        .iter()
+        .chain(RENAMED_FORMES)
+        .find(|(showdown, _)| to_id(showdown) == id)
+    {
+        return slug.to_string();
+    }
+    let base = to_id(name.split('-').next().unwrap_or(name));
+    match SINGLE_ENTRY_SPECIES.iter().find(|s| to_id(s) == base) {
+        Some(species) => slugify(species),
+        None => slugify(name),
+    }
+}
+
+/// The Showdown name for a PokeAPI slug: "thundurus-incarnate" is "Thundurus". Slugs outside the
+/// tables are title-cased part by part, which is right for formes ("charizard-mega-x" is
+/// "Charizard-Mega-X") but not for names with spaces; prefer the dex's spelling when it has one.
+pub fn showdown_name(slug: &str) -> String {
+    if let Some((showdown, _)) = DEFAULT_FORMES
+        .iter()
+        .chain(RENAMED_FORMES)
+        .find(|(_, known)| *known == slug)
+    {
+        return showdown.to_string();
+    }
+    slug.split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// "Mr. Mime" -> "mr-mime", "Flabébé" -> "flabebe", "Zygarde-10%" -> "zygarde-10"; the same
+/// rule turns move, item and ability names into PokeAPI slugs ("King's Shield" -> "kings-shield")
+pub fn slugify(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .replace('é', "e")
+        .replace(' ', "-")
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect()
+}
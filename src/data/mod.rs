@@ -0,0 +1,630 @@
+//! Offline snapshot of Showdown's dex: species, moves, items, abilities, learnsets and formats.
+//!
+//! The JSON under `data/` is written by `scripts/export-showdown-data.js` (`just data`) from
+//! Showdown's dex, and the same files can be put in the directory named by `POKEBRAINS_DATA_DIR`
+//! to replace the bundled ones. `manifest.json` says whether the files are a full export.
+//!
+//! Until the bundle is regenerated it is a hand-picked subset: 83 species common in singles OU,
+//! the 212 moves, 48 items and 125 abilities their sets use, and learnsets for 71 of them holding
+//! only those moves. Something missing from a subset is unknown rather than nonexistent, so
+//! callers check `Dex::is_complete` before treating a miss as an answer.
+
+pub mod formes;
+
+use crate::id::{closest, to_id};
+use crate::stats::BaseStats;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Environment variable pointing at a directory with replacement data files
+pub const DATA_DIR_ENV: &str = "POKEBRAINS_DATA_DIR";
+
+/// The newest generation in the bundled data
+pub const LATEST_GEN: u8 = 9;
+
+const POKEDEX: &str = include_str!("../../data/pokedex.json");
+const MOVES: &str = include_str!("../../data/moves.json");
+const ITEMS: &str = include_str!("../../data/items.json");
+const ABILITIES: &str = include_str!("../../data/abilities.json");
+const LEARNSETS: &str = include_str!("../../data/learnsets.json");
+const FORMATS_DATA: &str = include_str!("../../data/formats-data.json");
+const FORMATS: &str = include_str!("../../data/formats.json");
+const MANIFEST: &str = include_str!("../../data/manifest.json");
+
+/// The dex files whose coverage decides whether a miss is an answer
+const DEX_FILES: [&str; 5] = [
+    "pokedex.json",
+    "moves.json",
+    "items.json",
+    "abilities.json",
+    "learnsets.json",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Species {
+    pub num: i32,
+    pub name: String,
+    pub types: Vec<String>,
+    pub base_stats: BaseStats,
+    /// Ability slots "0", "1" and "H" (hidden)
+    pub abilities: BTreeMap<String, String>,
+    #[serde(default)]
+    pub weightkg: f32,
+    pub r#gen: u8,
+    pub base_species: Option<String>,
+    pub forme: Option<String>,
+    pub changes_from: Option<String>,
+    pub battle_only: Option<String>,
+    pub required_item: Option<String>,
+    pub required_move: Option<String>,
+    pub required_ability: Option<String>,
+    pub prevo: Option<String>,
+    #[serde(default)]
+    pub evos: Vec<String>,
+    #[serde(default)]
+    pub other_formes: Vec<String>,
+    /// Formes that only change appearance ("Gastrodon-East") and share this entry
+    #[serde(default)]
+    pub cosmetic_formes: Vec<String>,
+    /// Older data keyed by the last generation it applies to
+    #[serde(default)]
+    past: BTreeMap<u8, PastSpecies>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PastSpecies {
+    types: Option<Vec<String>>,
+    base_stats: Option<BaseStats>,
+    abilities: Option<BTreeMap<String, String>>,
+}
+
+impl Species {
+    /// The species as it was in `gen`, with past types, stats and abilities applied
+    pub fn in_gen(&self, r#gen: u8) -> Species {
+        let mut species = self.clone();
+        // For each field, the override with the smallest "through gen" key still >= gen wins
+        let mut types = None;
+        let mut base_stats = None;
+        let mut abilities = None;
+        for (_, past) in self.past.range(r#gen..).rev() {
+            types = past.types.clone().or(types);
+            base_stats = past.base_stats.or(base_stats);
+            abilities = past.abilities.clone().or(abilities);
+        }
+        if let Some(types) = types {
+            species.types = types;
+        }
+        if let Some(base_stats) = base_stats {
+            species.base_stats = base_stats;
+        }
+        if let Some(abilities) = abilities {
+            species.abilities = abilities;
+        }
+        if r#gen <= 1 {
+            species.base_stats.spd = species.base_stats.spa;
+        }
+        species
+    }
+
+    pub fn is_mega(&self) -> bool {
+        matches!(self.forme.as_deref(), Some(f) if f.starts_with("Mega"))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Accuracy {
+    /// Moves that never miss are `true` in Showdown data
+    Always(bool),
+    Percent(u8),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Move {
+    pub num: i32,
+    pub name: String,
+    pub r#type: String,
+    pub category: String,
+    pub base_power: u16,
+    pub accuracy: Accuracy,
+    pub pp: u8,
+    #[serde(default)]
+    pub priority: i8,
+    #[serde(default)]
+    pub target: String,
+    pub r#gen: u8,
+    #[serde(default)]
+    pub flags: BTreeMap<String, u8>,
+    pub last_gen: Option<u8>,
+    pub status: Option<String>,
+    #[serde(default)]
+    pub boosts: BTreeMap<String, i8>,
+    /// `true`, or the type that is immune to it (Sheer Cold)
+    pub ohko: Option<Value>,
+    /// Secondary effects, recoil, drain and the rest of the Showdown fields
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+impl Move {
+    pub fn exists_in(&self, r#gen: u8) -> bool {
+        self.r#gen <= r#gen && self.last_gen.is_none_or(|last| r#gen <= last)
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains_key(flag)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    pub num: i32,
+    pub name: String,
+    pub r#gen: u8,
+    #[serde(default)]
+    pub short_desc: String,
+    pub mega_stone: Option<String>,
+    pub mega_evolves: Option<String>,
+    #[serde(default)]
+    pub item_user: Vec<String>,
+    #[serde(default)]
+    pub is_choice: bool,
+    #[serde(default)]
+    pub is_gem: bool,
+    #[serde(default)]
+    pub is_berry: bool,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ability {
+    pub num: i32,
+    pub name: String,
+    pub r#gen: u8,
+    #[serde(default)]
+    pub short_desc: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct LearnsetEntry {
+    #[serde(default)]
+    learnset: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatsEntry {
+    pub tier: Option<String>,
+    pub is_nonstandard: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatDef {
+    pub name: String,
+    pub r#gen: u8,
+    pub game_type: Option<String>,
+    pub max_level: Option<u8>,
+    pub team_size: Option<usize>,
+    #[serde(default)]
+    pub ruleset: Vec<String>,
+    #[serde(default)]
+    pub banlist: Vec<String>,
+    #[serde(default)]
+    pub unbanlist: Vec<String>,
+}
+
+/// What `manifest.json` records about where the data files came from
+#[derive(Debug, Clone, Deserialize)]
+struct Manifest {
+    complete: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FormatsFile {
+    #[serde(default)]
+    rulesets: HashMap<String, Vec<String>>,
+    formats: HashMap<String, FormatDef>,
+}
+
+/// All bundled data, keyed by id
+pub struct Dex {
+    species: HashMap<String, Species>,
+    moves: HashMap<String, Move>,
+    items: HashMap<String, Item>,
+    abilities: HashMap<String, Ability>,
+    learnsets: HashMap<String, LearnsetEntry>,
+    formats_data: HashMap<String, HashMap<String, FormatsEntry>>,
+    formats: FormatsFile,
+    /// Cosmetic forme id to the id of the species entry it shares
+    cosmetic: HashMap<String, String>,
+    /// Whether the species, moves, items, abilities and learnsets all came from a full export
+    complete: bool,
+}
+
+impl Dex {
+    /// The shared dex: the override directory when `POKEBRAINS_DATA_DIR` is set, otherwise the
+    /// snapshot compiled into the binary
+    pub fn get() -> &'static Dex {
+        static DEX: OnceLock<Dex> = OnceLock::new();
+        DEX.get_or_init(|| match std::env::var(DATA_DIR_ENV) {
+            Ok(dir) => Dex::load(Path::new(&dir)).unwrap_or_else(|e| {
+                eprintln!("Falling back to bundled data: {:#}", e);
+                Dex::bundled()
+            }),
+            Err(_) => Dex::bundled(),
+        })
+    }
+
+    pub fn bundled() -> Dex {
+        let mut dex = Dex::parse(
+            POKEDEX,
+            MOVES,
+            ITEMS,
+            ABILITIES,
+            LEARNSETS,
+            FORMATS_DATA,
+            FORMATS,
+        )
+        .expect("bundled data is valid");
+        dex.complete = bundled_manifest().complete;
+        dex
+    }
+
+    /// Loads data files from `dir`, using the bundled copy for any file that is missing. A
+    /// `manifest.json` in `dir` says whether its files are a full export; without one, the dex
+    /// counts as complete when each dex and learnset file is either in `dir` or bundled from a
+    /// full export.
+    pub fn load(dir: &Path) -> Result<Dex> {
+        let read = |name: &str, bundled: &'static str| -> Result<String> {
+            let path = dir.join(name);
+            if path.exists() {
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))
+            } else {
+                Ok(bundled.to_string())
+            }
+        };
+
+        let mut dex = Dex::parse(
+            &read("pokedex.json", POKEDEX)?,
+            &read("moves.json", MOVES)?,
+            &read("items.json", ITEMS)?,
+            &read("abilities.json", ABILITIES)?,
+            &read("learnsets.json", LEARNSETS)?,
+            &read("formats-data.json", FORMATS_DATA)?,
+            &read("formats.json", FORMATS)?,
+        )?;
+        let manifest = dir.join("manifest.json");
+        dex.complete = if manifest.exists() {
+            let text = std::fs::read_to_string(&manifest)
+                .with_context(|| format!("Failed to read {}", manifest.display()))?;
+            serde_json::from_str::<Manifest>(&text)
+                .with_context(|| format!("Failed to parse {}", manifest.display()))?
+                .complete
+        } else {
+            let bundled_complete = bundled_manifest().complete;
+            DEX_FILES
+                .iter()
+                .all(|name| bundled_complete || dir.join(name).exists())
+        };
+        Ok(dex)
+    }
+
+    fn parse(
+        pokedex: &str,
+        moves: &str,
+        items: &str,
+        abilities: &str,
+        learnsets: &str,
+        formats_data: &str,
+        formats: &str,
+    ) -> Result<Dex> {
+        fn json<T: DeserializeOwned>(name: &str, text: &str) -> Result<T> {
+            serde_json::from_str(text).with_context(|| format!("Failed to parse {}", name))
+        }
+
+        let species: HashMap<String, Species> = json("pokedex.json", pokedex)?;
+        let cosmetic = species
+            .iter()
+            .flat_map(|(id, s)| s.cosmetic_formes.iter().map(|f| (to_id(f), id.clone())))
+            .collect();
+
+        Ok(Dex {
+            species,
+            moves: json("moves.json", moves)?,
+            items: json("items.json", items)?,
+            abilities: json("abilities.json", abilities)?,
+            learnsets: json("learnsets.json", learnsets)?,
+            formats_data: json("formats-data.json", formats_data)?,
+            formats: json("formats.json", formats)?,
+            cosmetic,
+            complete: false,
+        })
+    }
+
+    /// Whether this is a full export. A subset isn't, so a name or learnset entry missing from
+    /// it may still exist.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// A species by id. Cosmetic formes give their base species' entry.
+    pub fn get_species(&self, name: &str) -> Option<&Species> {
+        let id = to_id(name);
+        self.species
+            .get(&id)
+            .or_else(|| self.species.get(self.cosmetic.get(&id)?))
+    }
+
+    /// Every species entry, in no particular order
+    pub fn all_species(&self) -> impl Iterator<Item = &Species> {
+        self.species.values()
+    }
+
+    pub fn get_move(&self, name: &str) -> Option<&Move> {
+        let id = to_id(name);
+        // "Hidden Power [Ice]" is stored once as "hiddenpower"
+        let id = if id.starts_with("hiddenpower") {
+            "hiddenpower".to_string()
+        } else {
+            id
+        };
+        self.moves.get(&id)
+    }
+
+    pub fn get_item(&self, name: &str) -> Option<&Item> {
+        self.items.get(&to_id(name))
+    }
+
+    pub fn get_ability(&self, name: &str) -> Option<&Ability> {
+        self.abilities.get(&to_id(name))
+    }
+
+    /// Looks a species up leniently: exact id first, then forme names written the other way
+    /// round ("Alolan Muk", "Mega Charizard X"), then PokeAPI slugs ("thundurus-incarnate").
+    /// Typos are not corrected, see `suggest_species`.
+    pub fn resolve_species(&self, name: &str) -> Option<&Species> {
+        self.get_species(name)
+            .or_else(|| self.get_species(&forme_first_to_last(name)?))
+            .or_else(|| self.get_species(&formes::showdown_name(&name.to_lowercase())))
+    }
+
+    /// The Showdown spelling of a species name, keeping cosmetic formes: "gastrodon east" is
+    /// "Gastrodon-East", "Alolan Muk" is "Muk-Alola"
+    pub fn species_name(&self, name: &str) -> Option<&str> {
+        let id = to_id(name);
+        if let Some(base) = self.cosmetic.get(&id) {
+            return self.species[base]
+                .cosmetic_formes
+                .iter()
+                .find(|f| to_id(f) == id)
+                .map(String::as_str);
+        }
+        self.resolve_species(name).map(|s| s.name.as_str())
+    }
+
+    /// The species closest to a name that matches nothing, for "did you mean" hints. The
+    /// bundled data is a subset, so this is only ever a suggestion: the closest match to
+    /// "Latias" is Latios.
+    pub fn suggest_species(&self, name: &str) -> Option<&Species> {
+        fuzzy(&self.species, name)
+    }
+
+    /// The move closest to a name that matches nothing, for "did you mean" hints
+    pub fn suggest_move(&self, name: &str) -> Option<&Move> {
+        fuzzy(&self.moves, name)
+    }
+
+    /// The item closest to a name that matches nothing, for "did you mean" hints
+    pub fn suggest_item(&self, name: &str) -> Option<&Item> {
+        fuzzy(&self.items, name)
+    }
+
+    /// The ability closest to a name that matches nothing, for "did you mean" hints
+    pub fn suggest_ability(&self, name: &str) -> Option<&Ability> {
+        fuzzy(&self.abilities, name)
+    }
+
+    /// A species as it was in `gen`, or `None` if it hadn't been introduced yet
+    pub fn species_in(&self, name: &str, r#gen: u8) -> Option<Species> {
+        self.get_species(name)
+            .filter(|s| s.r#gen <= r#gen)
+            .map(|s| s.in_gen(r#gen))
+    }
+
+    /// A move, if it can be used in `gen`
+    pub fn move_in(&self, name: &str, r#gen: u8) -> Option<&Move> {
+        self.get_move(name).filter(|m| m.exists_in(r#gen))
+    }
+
+    /// An item, if it exists in `gen`; Gen 1 has no held items
+    pub fn item_in(&self, name: &str, r#gen: u8) -> Option<&Item> {
+        self.get_item(name)
+            .filter(|i| r#gen >= 2 && i.r#gen <= r#gen)
+    }
+
+    /// An ability, if it exists in `gen`; abilities start in Gen 3
+    pub fn ability_in(&self, name: &str, r#gen: u8) -> Option<&Ability> {
+        self.get_ability(name)
+            .filter(|a| r#gen >= 3 && a.r#gen <= r#gen)
+    }
+
+    /// Tier and availability of a species in a generation
+    pub fn formats_entry(&self, r#gen: u8, species: &str) -> Option<&FormatsEntry> {
+        self.formats_data
+            .get(&format!("gen{}", r#gen))
+            .and_then(|table| table.get(&to_id(species)))
+    }
+
+    pub fn format(&self, id: &str) -> Option<&FormatDef> {
+        self.formats.formats.get(&to_id(id))
+    }
+
+    /// Rules of a format with named rulesets such as "Standard" expanded
+    pub fn expand_rules(&self, rules: &[String]) -> Vec<String> {
+        let mut out = Vec::new();
+        for rule in rules {
+            match self.formats.rulesets.get(rule) {
+                Some(nested) => out.extend(self.expand_rules(nested)),
+                None => out.push(rule.clone()),
+            }
+        }
+        out
+    }
+
+    /// Learnset sources ("5M", "4L1", "3E", ...) of `move_id` for a species, following formes
+    /// back to the base species and evolutions back through their prevos
+    pub fn learn_sources<'a>(&'a self, species: &'a Species, move_id: &str) -> Vec<&'a str> {
+        self.learners_of(species, move_id)
+            .into_iter()
+            .map(|(_, source)| source)
+            .collect()
+    }
+
+    /// Like `learn_sources`, paired with the species each source belongs to, which is a prevo
+    /// for moves only learned before evolving
+    pub fn learners_of<'a>(
+        &'a self,
+        species: &'a Species,
+        move_id: &str,
+    ) -> Vec<(&'a Species, &'a str)> {
+        self.learnsets_of(species)
+            .into_iter()
+            .filter_map(|(learner, learnset)| Some((learner, learnset.get(move_id)?)))
+            .flat_map(|(learner, sources)| sources.iter().map(move |s| (learner, s.as_str())))
+            .collect()
+    }
+
+    /// Whether a species has any bundled learnset, its own or one it inherits
+    pub fn has_learnset(&self, species: &Species) -> bool {
+        !self.learnsets_of(species).is_empty()
+    }
+
+    /// The species whose learnset stands in for a forme without one of its own, like Muk for
+    /// Muk-Alola. `None` when the species has its own learnset, or no learnset at all.
+    pub fn learnset_fallback(&self, species: &Species) -> Option<&Species> {
+        if self.learnsets.contains_key(&to_id(&species.name)) {
+            return None;
+        }
+        let base = species
+            .changes_from
+            .as_ref()
+            .or(species.base_species.as_ref())?;
+        self.learnsets
+            .contains_key(&to_id(base))
+            .then(|| self.get_species(base))
+            .flatten()
+    }
+
+    /// Every move a species can know in `gen`, sorted by name
+    pub fn learnable_moves(&self, species: &Species, r#gen: u8) -> Vec<&Move> {
+        let mut moves: Vec<&Move> = self
+            .learnsets_of(species)
+            .into_iter()
+            .flat_map(|(_, learnset)| learnset.keys())
+            .filter(|id| self.can_learn(species, id, r#gen))
+            .filter_map(|id| self.move_in(id, r#gen))
+            .collect();
+        moves.sort_by(|a, b| a.name.cmp(&b.name));
+        moves.dedup_by(|a, b| a.name == b.name);
+        moves
+    }
+
+    /// Learnsets of a species and each of its prevos, falling back to the base species for
+    /// formes without their own
+    fn learnsets_of<'a>(
+        &'a self,
+        species: &'a Species,
+    ) -> Vec<(&'a Species, &'a HashMap<String, Vec<String>>)> {
+        let mut learnsets = Vec::new();
+        let mut current = Some(species);
+        while let Some(s) = current {
+            let id = to_id(&s.name);
+            let entry = self.learnsets.get(&id).or_else(|| {
+                let base = s.changes_from.as_ref().or(s.base_species.as_ref())?;
+                self.learnsets.get(&to_id(base))
+            });
+            if let Some(entry) = entry {
+                learnsets.push((s, &entry.learnset));
+            }
+            let prevo = s.prevo.as_ref().or_else(|| {
+                // Formes inherit the base species' prevo
+                let base = self.get_species(s.base_species.as_ref()?)?;
+                base.prevo.as_ref()
+            });
+            current = prevo.and_then(|p| self.get_species(p));
+        }
+        learnsets
+    }
+
+    /// Whether a species can know a move in a format of the given generation. `false` only
+    /// means the learnsets don't list it, which settles nothing unless `is_complete`, or that
+    /// the species can't be brought to that generation's games at all.
+    pub fn can_learn(&self, species: &Species, move_id: &str, r#gen: u8) -> bool {
+        self.obtainable_in(species, r#gen)
+            && self
+                .learn_sources(species, move_id)
+                .iter()
+                .any(|source| source_usable_in(source, r#gen))
+    }
+
+    /// Whether a species can be brought to games of `gen`, which rules out the ones Showdown
+    /// marks as past, like Ferrothorn in Gen 9. Species without a tier entry count as obtainable.
+    pub fn obtainable_in(&self, species: &Species, r#gen: u8) -> bool {
+        self.formats_entry(r#gen, &species.name)
+            .is_none_or(|entry| entry.is_nonstandard.as_deref() != Some("Past"))
+    }
+}
+
+/// Whether a learnset source such as "4M" lets a Pokémon know the move in `gen`
+pub fn source_usable_in(source: &str, r#gen: u8) -> bool {
+    let Some(source_gen) = source.chars().next().and_then(|c| c.to_digit(10)) else {
+        return false;
+    };
+    let source_gen = source_gen as u8;
+    // Moves from Gen 1-2 don't transfer forward past the GB games
+    source_gen <= r#gen && (r#gen <= 2 || source_gen >= 3)
+}
+
+fn bundled_manifest() -> Manifest {
+    serde_json::from_str(MANIFEST).expect("bundled manifest is valid")
+}
+
+fn fuzzy<'a, T>(table: &'a HashMap<String, T>, name: &str) -> Option<&'a T> {
+    let id = closest(name, table.keys().map(String::as_str))?;
+    table.get(id)
+}
+
+/// Rewrites "Alolan Muk" as "Muk-Alola" and "Mega Charizard X" as "Charizard-Mega-X"
+fn forme_first_to_last(name: &str) -> Option<String> {
+    let words: Vec<&str> = name
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let (first, rest) = words.split_first()?;
+    let forme = match to_id(first).as_str() {
+        "alola" | "alolan" => "Alola",
+        "galar" | "galarian" => "Galar",
+        "hisui" | "hisuian" => "Hisui",
+        "paldea" | "paldean" => "Paldea",
+        "mega" => "Mega",
+        "primal" => "Primal",
+        _ => return None,
+    };
+    let (base, tail) = rest.split_first()?;
+    let mut parts = vec![*base, forme];
+    parts.extend(tail);
+    Some(parts.join("-"))
+}
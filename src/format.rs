@@ -0,0 +1,173 @@
+use crate::data::Dex;
+use crate::id::to_id;
+use std::fmt;
+
+/// Clauses a format can enforce at team validation time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Clause {
+    /// At most one Pokémon per species
+    Species,
+    /// At most one of each held item
+    Item,
+    /// No one-hit KO moves
+    Ohko,
+    /// No moves that raise evasion
+    EvasionMoves,
+    /// No Bright Powder or Lax Incense
+    EvasionItems,
+    /// No Sand Veil or Snow Cloak
+    EvasionAbilities,
+    /// No moves that put the target to sleep
+    SleepMoves,
+    /// No Swagger
+    Swagger,
+    /// At most one Pokémon with Baton Pass
+    BatonPass,
+    /// No Gems
+    Gems,
+}
+
+impl Clause {
+    pub const ALL: [Clause; 10] = [
+        Clause::Species,
+        Clause::Item,
+        Clause::Ohko,
+        Clause::EvasionMoves,
+        Clause::EvasionItems,
+        Clause::EvasionAbilities,
+        Clause::SleepMoves,
+        Clause::Swagger,
+        Clause::BatonPass,
+        Clause::Gems,
+    ];
+
+    /// Looks up a clause by its Showdown rule name (e.g. "OHKO Clause")
+    pub fn from_name(name: &str) -> Option<Clause> {
+        let id = to_id(name);
+        Clause::ALL
+            .into_iter()
+            .find(|clause| to_id(&clause.to_string()) == id)
+    }
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Clause::Species => write!(f, "Species Clause"),
+            Clause::Item => write!(f, "Item Clause"),
+            Clause::Ohko => write!(f, "OHKO Clause"),
+            Clause::EvasionMoves => write!(f, "Evasion Moves Clause"),
+            Clause::EvasionItems => write!(f, "Evasion Items Clause"),
+            Clause::EvasionAbilities => write!(f, "Evasion Abilities Clause"),
+            Clause::SleepMoves => write!(f, "Sleep Moves Clause"),
+            Clause::Swagger => write!(f, "Swagger Clause"),
+            Clause::BatonPass => write!(f, "Baton Pass Clause"),
+            Clause::Gems => write!(f, "Gems Clause"),
+        }
+    }
+}
+
+/// Team-building rules of a Showdown format such as `gen5ou`
+#[derive(Debug, Clone)]
+pub struct Format {
+    pub id: String,
+    pub r#gen: u8,
+    pub tier: String,
+    pub max_team_size: usize,
+    pub max_level: u8,
+    pub clauses: Vec<Clause>,
+    /// Banned tiers, species, abilities, items and moves; "A ++ B" bans a combination
+    pub banlist: Vec<String>,
+    /// Entries exempted from the banlist
+    pub unbanlist: Vec<String>,
+}
+
+impl Format {
+    /// Derives format rules from a Showdown format id (e.g. "gen5ou", "gen9vgc2024regg", "gen8lc").
+    /// Formats in the bundled data get their full ruleset and banlist, others are inferred from the id.
+    pub fn from_id(id: &str) -> Self {
+        let id = to_id(id);
+
+        // "gen5ou" -> (5, "ou"), formats without a prefix are current gen
+        let (r#gen, tier) = match id.strip_prefix("gen") {
+            Some(rest) => {
+                let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+                let r#gen = digits.parse::<u8>().unwrap_or(9).clamp(1, 9);
+                (r#gen, rest[digits.len()..].to_string())
+            }
+            None => (9, id.clone()),
+        };
+
+        let is_vgc = tier.starts_with("vgc") || tier.starts_with("battlestadium") || tier == "bss";
+        let max_level = if tier == "lc" {
+            5
+        } else if is_vgc {
+            50
+        } else {
+            100
+        };
+
+        let mut clauses = vec![Clause::Species];
+        if is_vgc {
+            clauses.push(Clause::Item);
+        }
+
+        let mut format = Format {
+            id,
+            r#gen,
+            tier,
+            max_team_size: 6,
+            max_level,
+            clauses,
+            banlist: Vec::new(),
+            unbanlist: Vec::new(),
+        };
+
+        let dex = Dex::get();
+        if let Some(def) = dex.format(&format.id) {
+            format.r#gen = def.r#gen;
+            format.max_level = def.max_level.unwrap_or(format.max_level);
+            format.max_team_size = def.team_size.unwrap_or(format.max_team_size);
+            format.clauses = dex
+                .expand_rules(&def.ruleset)
+                .iter()
+                .filter_map(|rule| Clause::from_name(rule))
+                .collect();
+            format.banlist = def.banlist.clone();
+            format.unbanlist = def.unbanlist.clone();
+        }
+
+        format
+    }
+
+    pub fn has_clause(&self, clause: Clause) -> bool {
+        self.clauses.contains(&clause)
+    }
+
+    /// Whether a single name (tier, species, ability, item or move) is banned
+    pub fn is_banned(&self, name: &str) -> bool {
+        let id = to_id(name);
+        let listed = |list: &[String]| list.iter().any(|entry| to_id(entry) == id);
+        !id.is_empty() && listed(&self.banlist) && !listed(&self.unbanlist)
+    }
+
+    /// Banned combinations ("Drizzle ++ Swift Swim"), split into their parts
+    pub fn banned_combinations(&self) -> Vec<Vec<String>> {
+        self.banlist
+            .iter()
+            .filter(|entry| entry.contains("++"))
+            .map(|entry| {
+                entry
+                    .split("++")
+                    .map(|part| part.trim().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
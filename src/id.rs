@@ -0,0 +1,60 @@
+//! Showdown ids and "did you mean" matching for species, move, item and ability names.
+
+/// Showdown's id for a name: lowercase ASCII letters and digits only ("Mr. Mime" -> "mrmime")
+pub fn to_id(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Edit distance between two strings, counting a swap of neighbouring characters as one edit
+pub fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Rows for i - 2, i - 1 and i
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (prev[j] + 1).min(row[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut prev);
+        std::mem::swap(&mut prev, &mut row);
+    }
+    prev[b.len()]
+}
+
+/// Most edits a name may be away from a known id and still count as a typo of it
+fn max_typos(id: &str) -> usize {
+    (id.len() / 4).clamp(1, 3)
+}
+
+/// The known id closest to `name`, if it is within a few typos. Ties go to the
+/// alphabetically first id so results don't depend on iteration order.
+pub fn closest<'a>(name: &str, known: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let id = to_id(name);
+    if id.is_empty() {
+        return None;
+    }
+    known
+        .into_iter()
+        .map(|candidate| (distance(&id, candidate), candidate))
+        .filter(|(d, _)| *d <= max_typos(&id))
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// ", did you mean X?" for an error message, or nothing without a suggestion
+pub fn did_you_mean(suggestion: Option<&str>) -> String {
+    suggestion
+        .map(|s| format!(", did you mean {}?", s))
+        .unwrap_or_default()
+}
@@ -0,0 +1,1715 @@
+//! Inference of opposing sets from what the battle reveals.
+//!
+//! Each opposing species gets a [`SetBelief`]: weighted hypotheses over item, ability, nature and
+//! EV spread, started from usage statistics (or the sets database when the format has no usage
+//! loaded) and updated by Bayes' rule as [`Observation`]s come in. Moves are tracked per
+//! hypothesis as the chance of running each move, conditioned on the moves already seen.
+//!
+//! [`OpponentSets`] turns raw Showdown protocol lines into observations for the opponent's
+//! side: used moves, item and ability reveals, Choice lock, Leftovers recovery (or its absence)
+//! and the damage of hits between the active Pokémon. Move order within a turn bounds the
+//! opponent's speed; see [`speed`]. Hits also narrow its investment in the stats they used,
+//! whatever the usage data says; see [`reverse`].
+
+pub mod reverse;
+pub mod speed;
+
+pub use reverse::{Hit, Investment, NatureEffect, Position, ReverseCalc};
+pub use speed::{SpeedBounds, SpeedComparison};
+
+use crate::analysis::Weather;
+use crate::damage::{self, Boosts, Combatant, DamageResult, Field, Screens, Status, Terrain};
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::{EVs, Pokemon, Team};
+use crate::sets::{SetsDex, SmogonSet};
+use crate::stats::Stat;
+use crate::types::Type;
+use crate::usage::{SpeciesUsage, UsageDex};
+use anyhow::{Result, bail};
+use speed::{effective_speed, item_factor, speed_modifier, speed_stat};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Likelihood of evidence a hypothesis says shouldn't happen. Kept above zero since sets in
+/// the data are incomplete and the damage model ignores some effects.
+const UNLIKELY: f64 = 0.02;
+
+/// Hypotheses below this share of the total are dropped after an update
+const PRUNE_BELOW: f64 = 1e-4;
+
+const MAX_ITEMS: usize = 8;
+const MAX_SPREADS: usize = 8;
+
+const CHOICE_ITEMS: [&str; 3] = ["choiceband", "choicespecs", "choicescarf"];
+const RESIDUAL_HEAL_ITEMS: [&str; 2] = ["leftovers", "blacksludge"];
+
+/// Something the battle revealed about an opposing Pokémon
+#[derive(Debug, Clone)]
+pub enum Observation {
+    Move(String),
+    Item(String),
+    Ability(String),
+    /// It used two different moves without switching out, so it holds no Choice item
+    NotChoiceLocked,
+    /// It used the same move again without switching out
+    RepeatedMove,
+    /// It ended a turn below full HP without recovering from Leftovers or Black Sludge
+    NoResidualHeal,
+    /// One of its moves took `fraction` of the max HP of our known `defender`
+    DamageDealt {
+        move_name: String,
+        defender: Pokemon,
+        fraction: f64,
+        knocked_out: bool,
+    },
+    /// Our known `attacker`'s move took `fraction` of its max HP
+    DamageTaken {
+        move_name: String,
+        attacker: Pokemon,
+        fraction: f64,
+        knocked_out: bool,
+    },
+    /// A hit between it and one of ours under known conditions, with its own set standing in
+    /// for whatever it really runs
+    Damage(Box<Hit>),
+    /// It moved before one of ours, so its speed stat with its item is at least this
+    SpeedAtLeast(f64),
+    /// It moved after one of ours, so its speed stat with its item is at most this
+    SpeedAtMost(f64),
+}
+
+#[derive(Debug, Clone)]
+struct Hypothesis {
+    item: Option<String>,
+    ability: Option<String>,
+    nature: Option<String>,
+    /// `None` when the source has no spread data
+    evs: Option<EVs>,
+    /// Index into [`SetBelief::move_tables`]
+    moves: usize,
+    weight: f64,
+}
+
+/// Probability distribution over the set of one opposing Pokémon
+#[derive(Debug, Clone)]
+pub struct SetBelief {
+    species: String,
+    level: u8,
+    r#gen: u8,
+    hypotheses: Vec<Hypothesis>,
+    /// Chance of running each move, per source set
+    move_tables: Vec<Vec<(String, f64)>>,
+    revealed_moves: Vec<String>,
+    revealed_item: Option<String>,
+    revealed_ability: Option<String>,
+    speed: SpeedBounds,
+    /// What hits have shown about its investment, one per stat
+    investments: Vec<ReverseCalc>,
+}
+
+type NatureAndEvs = (Option<String>, Option<EVs>);
+
+fn normalize<T>(options: &mut [(T, f64)]) {
+    let total: f64 = options.iter().map(|(_, w)| w).sum();
+    if total > 0.0 {
+        for (_, w) in options.iter_mut() {
+            *w /= total;
+        }
+    }
+}
+
+fn spread_text(evs: &EVs) -> String {
+    let parts: Vec<String> = Stat::ALL
+        .into_iter()
+        .filter(|s| evs.get(*s) > 0)
+        .map(|s| format!("{} {}", evs.get(s), s.short_name()))
+        .collect();
+    if parts.is_empty() {
+        "no EVs".to_string()
+    } else {
+        parts.join(" / ")
+    }
+}
+
+impl SetBelief {
+    /// The belief for `species` in `format`: from usage stats when loaded, otherwise from the
+    /// sets database, otherwise only the species' possible abilities
+    pub fn new(format: &str, species: &str, level: u8) -> SetBelief {
+        let r#gen = gen_of(format);
+        let mut belief = if let Ok(usage) = UsageDex::get().species(format, species) {
+            SetBelief::from_usage(usage)
+        } else {
+            match SetsDex::get().sets(format, species) {
+                [] => SetBelief::unknown(species),
+                sets => SetBelief::from_sets(species, sets),
+            }
+        };
+        belief.level = level;
+        belief.r#gen = r#gen;
+        belief
+    }
+
+    /// Every combination of the most common items, abilities and spreads, weighted as if they
+    /// were independent
+    pub fn from_usage(usage: &SpeciesUsage) -> SetBelief {
+        let mut items: Vec<(Option<String>, f64)> = usage
+            .items
+            .iter()
+            .take(MAX_ITEMS)
+            .map(|(item, share)| (Some(item.clone()), *share))
+            .collect();
+        if items.is_empty() {
+            items.push((None, 1.0));
+        }
+        normalize(&mut items);
+        let mut abilities: Vec<(Option<String>, f64)> = usage
+            .abilities
+            .iter()
+            .map(|(ability, share)| (Some(ability.clone()), *share))
+            .collect();
+        if abilities.is_empty() {
+            abilities.push((None, 1.0));
+        }
+        normalize(&mut abilities);
+        let mut spreads: Vec<(NatureAndEvs, f64)> = usage
+            .spreads
+            .iter()
+            .take(MAX_SPREADS)
+            .map(|s| ((Some(s.nature.clone()), Some(s.evs.clone())), s.share))
+            .collect();
+        if spreads.is_empty() {
+            spreads.push(((None, None), 1.0));
+        }
+        normalize(&mut spreads);
+
+        let mut hypotheses = Vec::new();
+        for (item, item_weight) in &items {
+            for (ability, ability_weight) in &abilities {
+                for ((nature, evs), spread_weight) in &spreads {
+                    hypotheses.push(Hypothesis {
+                        item: item.clone(),
+                        ability: ability.clone(),
+                        nature: nature.clone(),
+                        evs: evs.clone(),
+                        moves: 0,
+                        weight: item_weight * ability_weight * spread_weight,
+                    });
+                }
+            }
+        }
+
+        let moves = usage
+            .moves
+            .iter()
+            .map(|(name, share)| (name.clone(), share.min(1.0)))
+            .collect();
+        SetBelief::with(&usage.name, hypotheses, vec![moves])
+    }
+
+    /// Each listed set weighted by its position (the first is the most common), split evenly
+    /// over its item, ability and spread options
+    pub fn from_sets(species: &str, sets: &[SmogonSet]) -> SetBelief {
+        let mut hypotheses = Vec::new();
+        let mut move_tables = Vec::new();
+
+        for (rank, set) in sets.iter().enumerate() {
+            let set_weight = 1.0 / (rank + 1) as f64;
+            let options = |values: &[String]| -> Vec<Option<String>> {
+                if values.is_empty() {
+                    vec![None]
+                } else {
+                    values.iter().cloned().map(Some).collect()
+                }
+            };
+            let items = options(&set.item);
+            let abilities = options(&set.ability);
+            let natures = options(&set.nature);
+            let spreads: Vec<Option<EVs>> = if set.evs.is_empty() {
+                vec![None]
+            } else {
+                set.evs.iter().map(|e| Some(stats_from_map(e))).collect()
+            };
+            let share =
+                set_weight / (items.len() * abilities.len() * natures.len() * spreads.len()) as f64;
+
+            for item in &items {
+                for ability in &abilities {
+                    for nature in &natures {
+                        for evs in &spreads {
+                            hypotheses.push(Hypothesis {
+                                item: item.clone(),
+                                ability: ability.clone(),
+                                nature: nature.clone(),
+                                evs: evs.clone(),
+                                moves: move_tables.len(),
+                                weight: share,
+                            });
+                        }
+                    }
+                }
+            }
+
+            // A slot with alternatives runs each of them a fraction of the time
+            let mut table: Vec<(String, f64)> = Vec::new();
+            for slot in &set.moves {
+                for name in slot {
+                    let chance = 1.0 / slot.len() as f64;
+                    match table.iter_mut().find(|(m, _)| to_id(m) == to_id(name)) {
+                        Some((_, p)) => *p = (*p + chance).min(1.0),
+                        None => table.push((name.clone(), chance)),
+                    }
+                }
+            }
+            move_tables.push(table);
+        }
+
+        let name = sets.first().map_or(species, |s| s.species.as_str());
+        SetBelief::with(name, hypotheses, move_tables)
+    }
+
+    /// Nothing known but the species' abilities
+    pub fn unknown(species: &str) -> SetBelief {
+        let dex_species = Dex::get().get_species(species);
+        let abilities: Vec<Option<String>> = dex_species
+            .map(|s| s.abilities.values().cloned().map(Some).collect())
+            .filter(|a: &Vec<_>| !a.is_empty())
+            .unwrap_or_else(|| vec![None]);
+        let weight = 1.0 / abilities.len() as f64;
+        let hypotheses = abilities
+            .into_iter()
+            .map(|ability| Hypothesis {
+                item: None,
+                ability,
+                nature: None,
+                evs: None,
+                moves: 0,
+                weight,
+            })
+            .collect();
+        let name = dex_species.map_or(species, |s| s.name.as_str());
+        SetBelief::with(name, hypotheses, vec![Vec::new()])
+    }
+
+    fn with(
+        species: &str,
+        hypotheses: Vec<Hypothesis>,
+        move_tables: Vec<Vec<(String, f64)>>,
+    ) -> SetBelief {
+        let mut belief = SetBelief {
+            species: species.to_string(),
+            level: 100,
+            r#gen: 9,
+            hypotheses,
+            move_tables,
+            revealed_moves: Vec::new(),
+            revealed_item: None,
+            revealed_ability: None,
+            speed: SpeedBounds::default(),
+            investments: Vec::new(),
+        };
+        belief.renormalize();
+        belief
+    }
+
+    pub fn species(&self) -> &str {
+        &self.species
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Generation used for the damage model, 9 unless the belief came from [`SetBelief::new`]
+    pub fn set_gen(&mut self, r#gen: u8) {
+        self.r#gen = r#gen;
+    }
+
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level;
+    }
+
+    pub fn revealed_moves(&self) -> &[String] {
+        &self.revealed_moves
+    }
+
+    pub fn revealed_item(&self) -> Option<&str> {
+        self.revealed_item.as_deref()
+    }
+
+    pub fn revealed_ability(&self) -> Option<&str> {
+        self.revealed_ability.as_deref()
+    }
+
+    /// What move order has shown about its speed
+    pub fn speed_bounds(&self) -> SpeedBounds {
+        self.speed
+    }
+
+    /// What hits have shown about its investment in the stats they used
+    pub fn investments(&self) -> &[ReverseCalc] {
+        &self.investments
+    }
+
+    fn renormalize(&mut self) {
+        let total: f64 = self.hypotheses.iter().map(|h| h.weight).sum();
+        if total <= 0.0 {
+            return;
+        }
+        for h in &mut self.hypotheses {
+            h.weight /= total;
+        }
+        self.hypotheses.retain(|h| h.weight >= PRUNE_BELOW);
+        let total: f64 = self.hypotheses.iter().map(|h| h.weight).sum();
+        for h in &mut self.hypotheses {
+            h.weight /= total;
+        }
+    }
+
+    /// Multiplies every hypothesis by the likelihood of the evidence under it. Evidence no
+    /// hypothesis explains at all is ignored rather than wiping the belief.
+    fn update(&mut self, likelihood: impl Fn(&SetBelief, &Hypothesis) -> f64) {
+        let likelihoods: Vec<f64> = self
+            .hypotheses
+            .iter()
+            .map(|h| likelihood(self, h))
+            .collect();
+        let total: f64 = self
+            .hypotheses
+            .iter()
+            .zip(&likelihoods)
+            .map(|(h, l)| h.weight * l)
+            .sum();
+        if total <= 0.0 {
+            return;
+        }
+        for (h, l) in self.hypotheses.iter_mut().zip(likelihoods) {
+            h.weight *= l;
+        }
+        self.renormalize();
+    }
+
+    pub fn observe(&mut self, observation: Observation) {
+        match observation {
+            Observation::Move(name) => {
+                if self.revealed_moves.iter().any(|m| to_id(m) == to_id(&name)) {
+                    return;
+                }
+                self.update(|belief, h| {
+                    belief
+                        .table_chance(h.moves, &name)
+                        .unwrap_or(UNLIKELY)
+                        .max(UNLIKELY)
+                });
+                self.revealed_moves.push(name);
+            }
+            Observation::Item(item) => {
+                let id = to_id(&item);
+                if self
+                    .hypotheses
+                    .iter()
+                    .any(|h| h.item.as_deref().map(to_id) == Some(id.clone()))
+                {
+                    self.update(|_, h| {
+                        f64::from(u8::from(h.item.as_deref().map(to_id) == Some(id.clone())))
+                    });
+                } else {
+                    // The data never saw this item; keep the rest of each hypothesis
+                    for h in &mut self.hypotheses {
+                        h.item = Some(item.clone());
+                    }
+                }
+                self.revealed_item = Some(item);
+            }
+            Observation::Ability(ability) => {
+                let id = to_id(&ability);
+                if self
+                    .hypotheses
+                    .iter()
+                    .any(|h| h.ability.as_deref().map(to_id) == Some(id.clone()))
+                {
+                    self.update(|_, h| {
+                        f64::from(u8::from(
+                            h.ability.as_deref().map(to_id) == Some(id.clone()),
+                        ))
+                    });
+                } else {
+                    for h in &mut self.hypotheses {
+                        h.ability = Some(ability.clone());
+                    }
+                }
+                self.revealed_ability = Some(ability);
+            }
+            Observation::NotChoiceLocked => {
+                self.update(|_, h| {
+                    if holds_any(h, &CHOICE_ITEMS) {
+                        0.0
+                    } else {
+                        1.0
+                    }
+                });
+            }
+            Observation::RepeatedMove => {
+                self.update(|_, h| {
+                    if holds_any(h, &CHOICE_ITEMS) {
+                        1.0
+                    } else {
+                        0.8
+                    }
+                });
+            }
+            Observation::NoResidualHeal => {
+                self.update(|_, h| {
+                    if holds_any(h, &RESIDUAL_HEAL_ITEMS) {
+                        UNLIKELY
+                    } else {
+                        1.0
+                    }
+                });
+            }
+            Observation::DamageDealt {
+                move_name,
+                defender,
+                fraction,
+                knocked_out,
+            } => self.observe_hit(Hit {
+                move_name,
+                attacker: Combatant::from(self.base_set()),
+                defender: Combatant::from(defender),
+                field: Field::default(),
+                crit: false,
+                fraction,
+                knocked_out,
+                opponent: Position::Attacker,
+            }),
+            Observation::DamageTaken {
+                move_name,
+                attacker,
+                fraction,
+                knocked_out,
+            } => self.observe_hit(Hit {
+                move_name,
+                attacker: Combatant::from(attacker),
+                defender: Combatant::from(self.base_set()),
+                field: Field::default(),
+                crit: false,
+                fraction,
+                knocked_out,
+                opponent: Position::Defender,
+            }),
+            Observation::Damage(hit) => self.observe_hit(*hit),
+            Observation::SpeedAtLeast(speed) => {
+                self.speed.at_least(speed);
+                self.update_speed();
+            }
+            Observation::SpeedAtMost(speed) => {
+                self.speed.at_most(speed);
+                self.update_speed();
+            }
+        }
+    }
+
+    /// What is known for sure: species, level and the revealed or likeliest ability, with the
+    /// revealed item
+    fn base_set(&self) -> Pokemon {
+        Pokemon {
+            name: self.species.clone(),
+            species: Some(self.species.clone()),
+            item: self.revealed_item.clone(),
+            ability: self
+                .revealed_ability
+                .clone()
+                .or_else(|| self.abilities().into_iter().next().map(|(a, _)| a)),
+            level: Some(self.level),
+            ..Default::default()
+        }
+    }
+
+    /// Weighs each hypothesis by whether its set explains the hit, and narrows the investment
+    /// over every spread. Hypotheses without spread data are only checked on their item.
+    /// Multi-hit moves get a damage line per hit and are left out.
+    fn observe_hit(&mut self, hit: Hit) {
+        let hit = hit.with_opponent(self.base_set());
+        let reversed = reverse::reverse(self.r#gen, &hit).ok();
+        self.update(|belief, h| {
+            let fits = match belief.hypothesis_pokemon(h) {
+                Some(pkmn) => hit.fits(belief.r#gen, &pkmn),
+                None => reversed
+                    .as_ref()
+                    .filter(|r| !r.candidates.is_empty())
+                    .map(|r| r.allows_item(h.item.as_deref())),
+            };
+            match fits {
+                Some(false) => UNLIKELY,
+                _ => 1.0,
+            }
+        });
+
+        let Some(reversed) = reversed.filter(|r| !r.candidates.is_empty()) else {
+            return;
+        };
+        match self
+            .investments
+            .iter_mut()
+            .find(|i| i.position == reversed.position && i.stat == reversed.stat)
+        {
+            Some(known) => {
+                known.narrow(&reversed);
+            }
+            None => self.investments.push(reversed),
+        }
+    }
+
+    fn update_speed(&mut self) {
+        let bounds = self.speed;
+        self.update(|belief, h| match belief.hypothesis_speeds(h) {
+            Some(speeds) if !speeds.iter().any(|s| bounds.contains(*s)) => UNLIKELY,
+            _ => 1.0,
+        });
+    }
+
+    /// Speeds the hypothesis allows with its item: one, or two when the item is unknown and
+    /// could be a Choice Scarf. `None` without spread data.
+    fn hypothesis_speeds(&self, h: &Hypothesis) -> Option<Vec<f64>> {
+        let pkmn = self.hypothesis_pokemon(h)?;
+        let stat = f64::from(speed_stat(self.r#gen, &pkmn)?);
+        Some(match h.item {
+            Some(ref item) => vec![stat * item_factor(Some(item))],
+            None if self.r#gen >= 4 => vec![stat, stat * 1.5],
+            None => vec![stat],
+        })
+    }
+
+    /// Slowest and fastest it can be with its item: over the spreads still in the belief when
+    /// there are any, otherwise from no investment and a hindering nature up to full
+    /// investment, a boosting nature and a Choice Scarf. Narrowed by the observed bounds.
+    pub fn speed_range(&self) -> Option<(f64, f64)> {
+        let speeds: Vec<f64> = self
+            .hypotheses
+            .iter()
+            .filter_map(|h| self.hypothesis_speeds(h))
+            .flatten()
+            .filter(|s| self.speed.contains(*s))
+            .collect();
+        let (mut low, mut high) = if speeds.is_empty() {
+            let extreme = |ev: u16, iv: u16, nature: &str| {
+                let mut pkmn = Pokemon {
+                    name: self.species.clone(),
+                    nature: Some(nature.to_string()),
+                    level: Some(self.level),
+                    ivs: Some(EVs::max_ivs()),
+                    ..Default::default()
+                };
+                pkmn.evs.set(Stat::Spe, ev);
+                if let Some(ref mut ivs) = pkmn.ivs {
+                    ivs.set(Stat::Spe, iv);
+                }
+                speed_stat(self.r#gen, &pkmn).map(f64::from)
+            };
+            let scarf = match self.revealed_item {
+                Some(ref item) => item_factor(Some(item)),
+                None if self.r#gen >= 4 => 1.5,
+                None => 1.0,
+            };
+            let low = extreme(0, 0, "Brave")? * scarf.min(1.0);
+            let high = extreme(252, 31, "Timid")? * scarf.max(1.0);
+            (low, high)
+        } else {
+            speeds
+                .iter()
+                .fold((f64::MAX, f64::MIN), |(lo, hi), s| (lo.min(*s), hi.max(*s)))
+        };
+        if let Some(min) = self.speed.min {
+            low = low.max(min);
+        }
+        if let Some(max) = self.speed.max {
+            high = high.min(max);
+        }
+        Some((low.min(high), high.max(low)))
+    }
+
+    /// Chance it outspeeds `speed`, ties counting half, over the hypotheses the observed bounds
+    /// allow. `None` without spread data.
+    fn probability_faster(&self, speed: f64) -> Option<f64> {
+        let mut faster = 0.0;
+        let mut total = 0.0;
+        for h in &self.hypotheses {
+            let Some(speeds) = self.hypothesis_speeds(h) else {
+                continue;
+            };
+            let allowed: Vec<f64> = speeds
+                .into_iter()
+                .filter(|s| self.speed.contains(*s))
+                .collect();
+            if allowed.is_empty() {
+                continue;
+            }
+            let share = h.weight / allowed.len() as f64;
+            for s in allowed {
+                total += share;
+                if s > speed {
+                    faster += share;
+                } else if s == speed {
+                    faster += share / 2.0;
+                }
+            }
+        }
+        (total > 0.0).then(|| faster / total)
+    }
+
+    /// How its speed compares to our `own` Pokémon's, both unboosted and with their items
+    pub fn compare_speed(&self, own: &Pokemon) -> Option<SpeedComparison> {
+        let own_speed = effective_speed(self.r#gen, own)?;
+        let opponent_range = self.speed_range()?;
+        Some(SpeedComparison {
+            opponent: self.species.clone(),
+            own: own.species.clone().unwrap_or_else(|| own.name.clone()),
+            own_speed,
+            opponent_range,
+            probability_faster: self.probability_faster(own_speed),
+            trick_room: false,
+        })
+    }
+
+    fn table_chance(&self, table: usize, move_name: &str) -> Option<f64> {
+        self.move_tables[table]
+            .iter()
+            .find(|(m, _)| to_id(m) == to_id(move_name))
+            .map(|(_, p)| *p)
+    }
+
+    /// The hypothesis as a battler, `None` without spread data
+    fn hypothesis_pokemon(&self, h: &Hypothesis) -> Option<Pokemon> {
+        Some(Pokemon {
+            name: self.species.clone(),
+            species: Some(self.species.clone()),
+            item: h.item.clone(),
+            ability: h.ability.clone(),
+            nature: h.nature.clone(),
+            evs: h.evs.clone()?,
+            level: Some(self.level),
+            ..Default::default()
+        })
+    }
+
+    fn marginal(&self, value: impl Fn(&Hypothesis) -> Option<&String>) -> Vec<(String, f64)> {
+        let mut totals: Vec<(String, f64)> = Vec::new();
+        for h in &self.hypotheses {
+            let Some(name) = value(h) else {
+                continue;
+            };
+            match totals.iter_mut().find(|(n, _)| n == name) {
+                Some((_, w)) => *w += h.weight,
+                None => totals.push((name.clone(), h.weight)),
+            }
+        }
+        totals.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        totals
+    }
+
+    /// Items with their probabilities, most likely first
+    pub fn items(&self) -> Vec<(String, f64)> {
+        self.marginal(|h| h.item.as_ref())
+    }
+
+    pub fn abilities(&self) -> Vec<(String, f64)> {
+        self.marginal(|h| h.ability.as_ref())
+    }
+
+    /// Natures and EV spreads with their probabilities, most likely first
+    pub fn spreads(&self) -> Vec<(String, EVs, f64)> {
+        let mut totals: Vec<(String, EVs, f64)> = Vec::new();
+        for h in &self.hypotheses {
+            let (Some(nature), Some(evs)) = (&h.nature, &h.evs) else {
+                continue;
+            };
+            match totals.iter_mut().find(|(n, e, _)| n == nature && e == evs) {
+                Some((_, _, w)) => *w += h.weight,
+                None => totals.push((nature.clone(), evs.clone(), h.weight)),
+            }
+        }
+        totals.sort_by(|a, b| b.2.total_cmp(&a.2));
+        totals
+    }
+
+    /// Chance of running a move given the moves already seen
+    pub fn move_probability(&self, move_name: &str) -> f64 {
+        if self
+            .revealed_moves
+            .iter()
+            .any(|m| to_id(m) == to_id(move_name))
+        {
+            return 1.0;
+        }
+        let open_slots = 4usize.saturating_sub(self.revealed_moves.len()) as f64;
+        self.hypotheses
+            .iter()
+            .map(|h| {
+                let Some(chance) = self.table_chance(h.moves, move_name) else {
+                    return 0.0;
+                };
+                let unrevealed: f64 = self.move_tables[h.moves]
+                    .iter()
+                    .filter(|(m, _)| !self.revealed_moves.iter().any(|r| to_id(r) == to_id(m)))
+                    .map(|(_, p)| p)
+                    .sum();
+                h.weight * (open_slots * chance / unrevealed.max(open_slots)).min(1.0)
+            })
+            .sum()
+    }
+
+    /// Moves with their probabilities, revealed ones first
+    pub fn moves(&self) -> Vec<(String, f64)> {
+        let mut moves: Vec<(String, f64)> = self
+            .revealed_moves
+            .iter()
+            .map(|m| (m.clone(), 1.0))
+            .collect();
+        for table in &self.move_tables {
+            for (name, _) in table {
+                if !moves.iter().any(|(m, _)| to_id(m) == to_id(name)) {
+                    moves.push((name.clone(), self.move_probability(name)));
+                }
+            }
+        }
+        moves.sort_by(|a, b| b.1.total_cmp(&a.1));
+        moves
+    }
+
+    pub fn item_probability(&self, item: &str) -> f64 {
+        share_of(&self.items(), item)
+    }
+
+    pub fn ability_probability(&self, ability: &str) -> f64 {
+        share_of(&self.abilities(), ability)
+    }
+
+    /// Probability the Pokémon has `name`, whether it is a move, an item or an ability
+    pub fn probability(&self, name: &str) -> f64 {
+        let dex = Dex::get();
+        if dex.get_move(name).is_some() {
+            self.move_probability(name)
+        } else if dex.get_item(name).is_some() {
+            self.item_probability(name)
+        } else {
+            self.ability_probability(name)
+        }
+    }
+
+    /// The single most likely combination of item, ability and spread, with the revealed moves
+    /// and then the likeliest others. Without spread data, the stats hits have shown take the
+    /// most invested spread that fits them.
+    pub fn most_likely_set(&self) -> Pokemon {
+        let best = self
+            .hypotheses
+            .iter()
+            .max_by(|a, b| a.weight.total_cmp(&b.weight));
+        let moves = self
+            .moves()
+            .into_iter()
+            .filter(|(_, p)| *p > 0.0)
+            .take(4)
+            .map(|(m, _)| m)
+            .collect();
+        let mut set = Pokemon {
+            name: self.species.clone(),
+            species: Some(self.species.clone()),
+            item: self
+                .revealed_item
+                .clone()
+                .or_else(|| best.and_then(|h| h.item.clone())),
+            ability: self
+                .revealed_ability
+                .clone()
+                .or_else(|| best.and_then(|h| h.ability.clone())),
+            nature: best.and_then(|h| h.nature.clone()),
+            evs: best.and_then(|h| h.evs.clone()).unwrap_or_default(),
+            level: (self.level != 100).then_some(self.level),
+            moves,
+            ..Default::default()
+        };
+        if best.is_none_or(|h| h.evs.is_none()) {
+            for investment in &self.investments {
+                let Some(strongest) = investment.strongest() else {
+                    continue;
+                };
+                set.evs.set(investment.stat, strongest.evs);
+                if investment.position == Position::Defender {
+                    set.evs.hp = strongest.hp_evs;
+                }
+                if set.nature.is_none() && strongest.nature != NatureEffect::Neutral {
+                    set.nature = Some(strongest.nature.nature(investment.stat).to_string());
+                }
+                if set.item.is_none() {
+                    set.item = strongest.item.clone();
+                }
+            }
+        }
+        set
+    }
+}
+
+fn share_of(options: &[(String, f64)], name: &str) -> f64 {
+    options
+        .iter()
+        .filter(|(option, _)| to_id(option) == to_id(name))
+        .map(|(_, p)| p)
+        .sum()
+}
+
+fn holds_any(h: &Hypothesis, items: &[&str]) -> bool {
+    h.item
+        .as_deref()
+        .is_some_and(|item| items.contains(&to_id(item).as_str()))
+}
+
+fn stats_from_map(values: &BTreeMap<String, u16>) -> EVs {
+    let mut evs = EVs::default();
+    for stat in Stat::ALL {
+        evs.set(
+            stat,
+            values
+                .get(&stat.short_name().to_lowercase())
+                .copied()
+                .unwrap_or(0),
+        );
+    }
+    evs
+}
+
+/// "gen6ou" -> 6, defaulting to the current generation
+fn gen_of(format: &str) -> u8 {
+    to_id(format)
+        .strip_prefix("gen")
+        .and_then(|rest| rest.chars().next())
+        .and_then(|c| c.to_digit(10))
+        .map_or(9, |d| d as u8)
+}
+
+fn percent(p: f64) -> String {
+    format!("{:.0}%", p * 100.0)
+}
+
+impl fmt::Display for SetBelief {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let likely = |options: Vec<(String, f64)>| -> String {
+            let shown: Vec<String> = options
+                .into_iter()
+                .filter(|(_, p)| *p >= 0.05)
+                .take(3)
+                .map(|(name, p)| format!("{} {}", name, percent(p)))
+                .collect();
+            if shown.is_empty() {
+                "unknown".to_string()
+            } else {
+                shown.join(", ")
+            }
+        };
+
+        write!(f, "{}", self.species)?;
+        if self.level != 100 {
+            write!(f, " (L{})", self.level)?;
+        }
+        match self.revealed_item {
+            Some(ref item) => write!(f, " | Item: {} (revealed)", item)?,
+            None => write!(f, " | Item: {}", likely(self.items()))?,
+        }
+        match self.revealed_ability {
+            Some(ref ability) => write!(f, " | Ability: {} (revealed)", ability)?,
+            None => write!(f, " | Ability: {}", likely(self.abilities()))?,
+        }
+        if let Some((nature, evs, p)) = self.spreads().into_iter().next() {
+            write!(
+                f,
+                " | Spread: {} {} {}",
+                nature,
+                spread_text(&evs),
+                percent(p)
+            )?;
+        }
+        if self.speed.is_known() {
+            write!(f, " | Speed: {}", self.speed)?;
+        }
+        for investment in &self.investments {
+            write!(f, " | {}", investment)?;
+        }
+
+        let moves: Vec<String> = self
+            .moves()
+            .into_iter()
+            .filter(|(_, p)| *p >= 0.1)
+            .take(6)
+            .map(|(name, p)| {
+                if self.revealed_moves.contains(&name) {
+                    format!("{} (revealed)", name)
+                } else {
+                    format!("{} {}", name, percent(p))
+                }
+            })
+            .collect();
+        if !moves.is_empty() {
+            write!(f, " | Moves: {}", moves.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// What we know about the Pokémon active on one side
+#[derive(Debug, Clone, Default)]
+struct Active {
+    species: String,
+    hp: f64,
+    last_move: Option<String>,
+    healed: bool,
+    boosts: Boosts,
+    status: Option<Status>,
+}
+
+/// A hit waiting for its damage line
+#[derive(Debug, Clone)]
+struct PendingHit {
+    attacker_side: usize,
+    move_name: String,
+    crit: bool,
+}
+
+/// The first move a side used in a turn
+#[derive(Debug, Clone)]
+struct TurnMove {
+    side: usize,
+    species: String,
+    move_name: String,
+    /// Speed modifier of the user when it moved, see [`speed_modifier`]
+    modifier: f64,
+    trick_room: bool,
+}
+
+const OWN: usize = 0;
+const FOE: usize = 1;
+
+/// Beliefs about every opposing Pokémon, kept up to date from the battle's protocol lines
+#[derive(Debug, Clone, Default)]
+pub struct OpponentSets {
+    format: String,
+    r#gen: u8,
+    own_team: Vec<Pokemon>,
+    beliefs: BTreeMap<String, SetBelief>,
+    active: [Active; 2],
+    pending: Option<PendingHit>,
+    weather: Option<Weather>,
+    terrain: Option<Terrain>,
+    screens: [Screens; 2],
+    /// The species that Terastallized on each side, and into what
+    tera: [Option<(String, Type)>; 2],
+    /// Species whose item is gone on each side, so residual healing says nothing about the
+    /// opponent's and Unburden may be active
+    item_lost: [Vec<String>; 2],
+    trick_room: bool,
+    tailwind: [bool; 2],
+    turn_moves: Vec<TurnMove>,
+    /// Something other than speed decided the order this turn, like Quick Claw
+    order_disrupted: bool,
+}
+
+/// "Landorus-Therian, L82, M" -> ("Landorus-Therian", 82)
+fn parse_details(details: &str) -> (String, u8) {
+    let mut parts = details.split(',').map(str::trim);
+    let species = parts.next().unwrap_or_default().to_string();
+    let level = parts
+        .find_map(|p| p.strip_prefix('L').and_then(|l| l.parse().ok()))
+        .unwrap_or(100);
+    (species, level)
+}
+
+/// "45/100 par" -> 0.45, "0 fnt" -> 0
+fn parse_hp(hp: &str) -> Option<f64> {
+    let value = hp.split_whitespace().next()?;
+    match value.split_once('/') {
+        Some((current, max)) => {
+            let (current, max): (f64, f64) = (current.parse().ok()?, max.parse().ok()?);
+            (max > 0.0).then(|| current / max)
+        }
+        None => value.parse::<f64>().ok().map(|v| v.min(1.0)),
+    }
+}
+
+/// The value after a tag like `[from] item: `, and the side named by `[of]`
+fn tagged<'a>(parts: &[&'a str], tag: &str) -> Option<&'a str> {
+    parts
+        .iter()
+        .find_map(|p| p.trim().strip_prefix(tag))
+        .map(str::trim)
+}
+
+impl OpponentSets {
+    pub fn new() -> Self {
+        OpponentSets {
+            r#gen: 9,
+            ..Default::default()
+        }
+    }
+
+    /// Our own sets, so hits between the active Pokémon can be checked against a damage range
+    pub fn set_own_team(&mut self, team: &Team) {
+        self.own_team = team.pokemon.clone();
+    }
+
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    pub fn belief(&self, species: &str) -> Option<&SetBelief> {
+        self.beliefs.get(&to_id(species))
+    }
+
+    pub fn beliefs(&self) -> impl Iterator<Item = &SetBelief> {
+        self.beliefs.values()
+    }
+
+    /// Probability an opposing species has a move, item or ability
+    pub fn probability(&self, species: &str, name: &str) -> Option<f64> {
+        self.belief(species).map(|b| b.probability(name))
+    }
+
+    pub fn most_likely_set(&self, species: &str) -> Option<Pokemon> {
+        self.belief(species).map(SetBelief::most_likely_set)
+    }
+
+    fn belief_mut(&mut self, species: &str, level: u8) -> &mut SetBelief {
+        let (format, r#gen) = (self.format.clone(), self.r#gen);
+        self.beliefs.entry(to_id(species)).or_insert_with(|| {
+            let mut belief = SetBelief::new(&format, species, level);
+            belief.set_gen(r#gen);
+            belief
+        })
+    }
+
+    fn observe(&mut self, observation: Observation) {
+        let species = self.active[FOE].species.clone();
+        if let Some(belief) = self.beliefs.get_mut(&to_id(&species)) {
+            belief.observe(observation);
+        }
+    }
+
+    fn own_set(&self, species: &str) -> Option<Pokemon> {
+        self.own_team
+            .iter()
+            .find(|p| to_id(p.species.as_deref().unwrap_or(&p.name)) == to_id(species))
+            .cloned()
+    }
+
+    /// Side index of a "p2a: Nickname" reference, relative to the user's slot
+    fn side(&self, reference: &str, user_slot: &str) -> Option<usize> {
+        let player = reference.trim().get(..2)?;
+        if !player.starts_with('p') {
+            return None;
+        }
+        Some(if player == user_slot { OWN } else { FOE })
+    }
+
+    /// Reads one protocol line. `user_slot` is "p1" or "p2"; lines before it is known only
+    /// set the format.
+    pub fn observe_line(&mut self, line: &str, user_slot: Option<&str>) {
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() < 2 {
+            return;
+        }
+        match parts[1] {
+            "gen" => {
+                if let Some(r#gen) = parts.get(2).and_then(|g| g.trim().parse().ok()) {
+                    self.r#gen = r#gen;
+                }
+                return;
+            }
+            "tier" => {
+                if let Some(tier) = parts.get(2) {
+                    self.format = to_id(tier);
+                }
+                return;
+            }
+            _ => {}
+        }
+        let Some(user_slot) = user_slot else {
+            return;
+        };
+        let side = |reference: &str| self.side(reference, user_slot);
+
+        match parts[1] {
+            "poke" if parts.len() >= 4 => {
+                if side(parts[2]) == Some(FOE) {
+                    let (species, level) = parse_details(parts[3]);
+                    self.belief_mut(&species, level);
+                }
+            }
+            "switch" | "drag" if parts.len() >= 4 => {
+                let Some(s) = side(parts[2]) else {
+                    return;
+                };
+                let (species, level) = parse_details(parts[3]);
+                if s == FOE {
+                    self.belief_mut(&species, level);
+                }
+                self.active[s] = Active {
+                    species,
+                    hp: parts.get(4).and_then(|hp| parse_hp(hp)).unwrap_or(1.0),
+                    status: parts
+                        .get(4)
+                        .and_then(|hp| hp.split_whitespace().nth(1))
+                        .and_then(Status::from_id),
+                    ..Default::default()
+                };
+                self.pending = None;
+            }
+            "move" if parts.len() >= 4 => self.on_move(&parts, user_slot),
+            "-crit" => {
+                if let Some(ref mut hit) = self.pending {
+                    hit.crit = true;
+                }
+            }
+            "-damage" if parts.len() >= 4 => self.on_damage(&parts, user_slot),
+            "-heal" if parts.len() >= 4 => {
+                let Some(s) = side(parts[2]) else {
+                    return;
+                };
+                if let Some(hp) = parse_hp(parts[3]) {
+                    self.active[s].hp = hp;
+                }
+                self.on_effect(&parts, user_slot);
+                if s == FOE
+                    && tagged(&parts, "[from] item:")
+                        .is_some_and(|i| RESIDUAL_HEAL_ITEMS.contains(&to_id(i).as_str()))
+                {
+                    self.active[FOE].healed = true;
+                }
+            }
+            "-item" if parts.len() >= 4 => {
+                // A traded item says nothing about what either side started with
+                if tagged(&parts, "[from] move:")
+                    .is_some_and(|m| matches!(to_id(m).as_str(), "trick" | "switcheroo"))
+                {
+                    let species = self.active[FOE].species.clone();
+                    self.item_lost[FOE].push(to_id(&species));
+                    return;
+                }
+                if side(parts[2]) == Some(FOE) {
+                    self.observe(Observation::Item(parts[3].to_string()));
+                }
+            }
+            "-enditem" if parts.len() >= 4 => {
+                let Some(s) = side(parts[2]) else {
+                    return;
+                };
+                if s == FOE {
+                    self.observe(Observation::Item(parts[3].to_string()));
+                }
+                let species = self.active[s].species.clone();
+                self.item_lost[s].push(to_id(&species));
+            }
+            "-mega" if parts.len() >= 5 => {
+                if side(parts[2]) == Some(FOE) {
+                    self.observe(Observation::Item(parts[4].to_string()));
+                }
+            }
+            "-ability" if parts.len() >= 4 => {
+                if side(parts[2]) == Some(FOE) {
+                    // Trace shows the copied ability, with Trace itself as the source
+                    let ability = tagged(&parts, "[from] ability:").unwrap_or(parts[3]);
+                    self.observe(Observation::Ability(ability.to_string()));
+                }
+            }
+            "-boost" | "-unboost" | "-setboost" | "-clearnegativeboost" => {
+                if let Some(s) = parts.get(2).and_then(|p| side(p)) {
+                    self.on_boost(s, &parts);
+                }
+            }
+            "-clearallboost" => {
+                for active in &mut self.active {
+                    active.boosts = Boosts::default();
+                }
+            }
+            "-clearboost" => {
+                if let Some(s) = parts.get(2).and_then(|p| side(p)) {
+                    self.active[s].boosts = Boosts::default();
+                }
+            }
+            "-swapboost" | "-copyboost" | "-invertboost" => self.order_disrupted = true,
+            "-status" if parts.len() >= 4 => {
+                if let Some(s) = side(parts[2]) {
+                    self.active[s].status = Status::from_id(parts[3]);
+                }
+                self.on_effect(&parts, user_slot);
+            }
+            "-curestatus" if parts.len() >= 4 => {
+                if let Some(s) = side(parts[2]) {
+                    self.active[s].status = None;
+                }
+                self.on_effect(&parts, user_slot);
+            }
+            "-terastallize" if parts.len() >= 4 => {
+                if let (Some(s), Ok(r#type)) = (side(parts[2]), parts[3].trim().parse()) {
+                    self.tera[s] = Some((to_id(&self.active[s].species), r#type));
+                }
+            }
+            "-fieldstart" | "-fieldend" if parts.len() >= 3 => {
+                let condition = parts[2].trim_start_matches("move:");
+                let started = parts[1] == "-fieldstart";
+                if to_id(condition) == "trickroom" {
+                    self.trick_room = started;
+                } else if let Some(terrain) = Terrain::from_name(condition) {
+                    self.terrain = started.then_some(terrain);
+                }
+                self.on_effect(&parts, user_slot);
+            }
+            "-activate" if parts.len() >= 4 => {
+                // Items, abilities and moves that let a Pokémon act out of speed order
+                let effect = to_id(parts[3].split(':').next_back().unwrap_or_default());
+                if matches!(
+                    effect.as_str(),
+                    "quickclaw" | "quickdraw" | "custapberry" | "quash" | "afteryou"
+                ) {
+                    self.order_disrupted = true;
+                }
+                self.on_effect(&parts, user_slot);
+            }
+            "-weather" if parts.len() >= 3 => {
+                self.weather = match to_id(parts[2]).as_str() {
+                    "raindance" | "primordialsea" => Some(Weather::Rain),
+                    "sunnyday" | "desolateland" => Some(Weather::Sun),
+                    "sandstorm" => Some(Weather::Sand),
+                    "hail" | "snow" | "snowscape" => Some(Weather::Snow),
+                    _ => None,
+                };
+                self.on_effect(&parts, user_slot);
+            }
+            "-sidestart" | "-sideend" if parts.len() >= 4 => {
+                let condition = to_id(parts[3].trim_start_matches("move:"));
+                let started = parts[1] == "-sidestart";
+                if let Some(s) = side(parts[2]) {
+                    let screens = &mut self.screens[s];
+                    match condition.as_str() {
+                        "reflect" => screens.reflect = started,
+                        "lightscreen" => screens.light_screen = started,
+                        "auroraveil" => screens.aurora_veil = started,
+                        "tailwind" => self.tailwind[s] = started,
+                        _ => {}
+                    }
+                }
+            }
+            "upkeep" => {
+                let foe = &self.active[FOE];
+                let species = to_id(&foe.species);
+                let still_holding = !self.item_lost[FOE].contains(&species)
+                    && self
+                        .beliefs
+                        .get(&species)
+                        .is_some_and(|b| b.revealed_item().is_none());
+                if !foe.species.is_empty()
+                    && foe.hp > 0.0
+                    && foe.hp < 1.0
+                    && !foe.healed
+                    && still_holding
+                {
+                    self.observe(Observation::NoResidualHeal);
+                }
+            }
+            "turn" => {
+                self.pending = None;
+                self.active[FOE].healed = false;
+                self.on_turn_end();
+            }
+            _ => self.on_effect(&parts, user_slot),
+        }
+    }
+
+    fn on_move(&mut self, parts: &[&str], user_slot: &str) {
+        let Some(s) = self.side(parts[2], user_slot) else {
+            return;
+        };
+        let move_name = parts[3].to_string();
+        // Moves called by other moves or abilities aren't part of the set
+        let called = parts[4..].iter().any(|p| p.trim().starts_with("[from]"));
+        if !called {
+            self.record_order(s, parts[2], &move_name);
+        }
+        self.pending = parts
+            .get(4)
+            .and_then(|target| self.side(target, user_slot))
+            .filter(|target| *target != s && !called)
+            .map(|_| PendingHit {
+                attacker_side: s,
+                move_name: move_name.clone(),
+                crit: false,
+            });
+        if s != FOE || called {
+            return;
+        }
+
+        let previous = self.active[FOE].last_move.replace(move_name.clone());
+        match previous {
+            Some(ref previous) if to_id(previous) == to_id(&move_name) => {
+                self.observe(Observation::RepeatedMove)
+            }
+            Some(_) => self.observe(Observation::NotChoiceLocked),
+            None => {}
+        }
+        if to_id(&move_name) != "struggle" {
+            self.observe(Observation::Move(move_name));
+        }
+    }
+
+    fn on_damage(&mut self, parts: &[&str], user_slot: &str) {
+        let Some(s) = self.side(parts[2], user_slot) else {
+            return;
+        };
+        let Some(hp) = parse_hp(parts[3]) else {
+            return;
+        };
+        let before = self.active[s].hp;
+        self.active[s].hp = hp;
+
+        if parts.len() > 4 {
+            // Rocky Helmet and friends damage the attacker; older logs leave out "[of]"
+            if let Some(item) = tagged(parts, "[from] item:")
+                && tagged(parts, "[of]").is_none()
+                && matches!(
+                    to_id(item).as_str(),
+                    "rockyhelmet" | "jabocaberry" | "rowapberry"
+                )
+            {
+                if s == OWN {
+                    self.observe(Observation::Item(item.to_string()));
+                }
+                return;
+            }
+            self.on_effect(parts, user_slot);
+            return;
+        }
+
+        let Some(hit) = self.pending.take() else {
+            return;
+        };
+        let attacker_side = hit.attacker_side;
+        if attacker_side == s {
+            return;
+        }
+        let (Some(attacker), Some(mut defender)) =
+            (self.combatant(attacker_side), self.combatant(s))
+        else {
+            return;
+        };
+        defender.hp = Some(before);
+        self.observe(Observation::Damage(Box::new(Hit {
+            move_name: hit.move_name,
+            attacker,
+            defender,
+            field: self.field(s),
+            crit: hit.crit,
+            fraction: before - hp,
+            knocked_out: hp <= 0.0,
+            opponent: if attacker_side == FOE {
+                Position::Attacker
+            } else {
+                Position::Defender
+            },
+        })));
+    }
+
+    fn on_boost(&mut self, s: usize, parts: &[&str]) {
+        let boosts = &mut self.active[s].boosts;
+        if parts[1] == "-clearnegativeboost" {
+            for stat in Stat::ALL {
+                boosts.set(stat, boosts.get(stat).max(0));
+            }
+            return;
+        }
+        // Accuracy and evasion don't parse as stats and aren't tracked
+        let Some(stat) = parts.get(3).and_then(|stat| stat.parse::<Stat>().ok()) else {
+            return;
+        };
+        let Some(amount) = parts.get(4).and_then(|a| a.trim().parse::<i8>().ok()) else {
+            return;
+        };
+        let stage = boosts.get(stat);
+        boosts.set(
+            stat,
+            match parts[1] {
+                "-boost" => stage.saturating_add(amount),
+                "-unboost" => stage.saturating_sub(amount),
+                _ => amount,
+            },
+        );
+    }
+
+    /// Notes the first move each side uses in a turn, with what affects its speed at the time
+    fn record_order(&mut self, s: usize, reference: &str, move_name: &str) {
+        // With more than one Pokémon a side, the first mover isn't the only one to compare to
+        if !reference
+            .trim()
+            .get(2..3)
+            .is_some_and(|slot| slot == "a" || slot == ":")
+        {
+            self.order_disrupted = true;
+        }
+        if self.turn_moves.iter().any(|m| m.side == s) {
+            return;
+        }
+        let active = &self.active[s];
+        let mut modifier = speed_modifier(
+            self.r#gen,
+            active.boosts.spe,
+            active.status == Some(Status::Paralysis),
+            self.tailwind[s],
+        );
+        if s == OWN {
+            match self.own_ability_factor(&active.species) {
+                Some(factor) => modifier *= factor,
+                // Our speed this turn isn't known, so the order says nothing about theirs
+                None => self.order_disrupted = true,
+            }
+        }
+        self.turn_moves.push(TurnMove {
+            side: s,
+            species: self.active[s].species.clone(),
+            move_name: move_name.to_string(),
+            modifier,
+            trick_room: self.trick_room,
+        });
+    }
+
+    /// Speed multiplier of our own Pokémon's ability right now. `None` when it changes our speed
+    /// by an amount we don't work out, like Protosynthesis boosting whichever stat is highest.
+    fn own_ability_factor(&self, species: &str) -> Option<f64> {
+        let Some(pokemon) = self.own_set(species) else {
+            return Some(1.0);
+        };
+        // Booster Energy keeps the boost going after it is used up
+        let booster = pokemon.item.as_deref().map(to_id).as_deref() == Some("boosterenergy");
+        let ability = pokemon.ability.as_deref().map(to_id).unwrap_or_default();
+        let doubled = match ability.as_str() {
+            "swiftswim" => self.weather == Some(Weather::Rain),
+            "chlorophyll" => self.weather == Some(Weather::Sun),
+            "sandrush" => self.weather == Some(Weather::Sand),
+            "slushrush" => self.weather == Some(Weather::Snow),
+            "surgesurfer" => self.terrain == Some(Terrain::Electric),
+            "unburden" => self.item_lost[OWN].contains(&to_id(species)),
+            // Quick Feet also cancels paralysis's drop
+            "quickfeet" if self.active[OWN].status.is_some() => return None,
+            "protosynthesis" if self.weather == Some(Weather::Sun) || booster => return None,
+            "quarkdrive" if self.terrain == Some(Terrain::Electric) || booster => return None,
+            "slowstart" => return None,
+            _ => false,
+        };
+        Some(if doubled { 2.0 } else { 1.0 })
+    }
+
+    /// Priority of a move, raised by abilities like Prankster. `None` when an ability the
+    /// Pokémon may or may not have would change it.
+    fn priority(&self, s: usize, species: &str, move_name: &str) -> Option<i8> {
+        let mv = Dex::get().get_move(move_name)?;
+        let boost = |ability: &str| -> i8 {
+            match to_id(ability).as_str() {
+                "prankster" if mv.category == "Status" => 1,
+                "galewings" if mv.r#type == "Flying" => 1,
+                "triage" if mv.flags.contains_key("heal") => 3,
+                _ => 0,
+            }
+        };
+        let boosts: Vec<i8> = if s == OWN {
+            vec![
+                self.own_set(species)
+                    .and_then(|p| p.ability)
+                    .map_or(0, |a| boost(&a)),
+            ]
+        } else {
+            let belief = self.belief(species)?;
+            match belief.revealed_ability() {
+                Some(ability) => vec![boost(ability)],
+                None => belief.abilities().iter().map(|(a, _)| boost(a)).collect(),
+            }
+        };
+        let first = boosts.first().copied().unwrap_or(0);
+        boosts
+            .iter()
+            .all(|b| *b == first)
+            .then_some(mv.priority + first)
+    }
+
+    /// Whether an ability or item could have changed the opponent's speed in ways we don't track
+    fn speed_uncertain(&self, belief: &SetBelief) -> bool {
+        let could_hold = |item: &str| match belief.revealed_item() {
+            Some(revealed) => to_id(revealed) == to_id(item),
+            None => belief.item_probability(item) > 0.0,
+        };
+        let abilities: Vec<String> = match belief.revealed_ability() {
+            Some(ability) => vec![to_id(ability)],
+            None => belief.abilities().iter().map(|(a, _)| to_id(a)).collect(),
+        };
+        abilities.iter().any(|a| match a.as_str() {
+            "quarkdrive" => self.terrain == Some(Terrain::Electric) || could_hold("Booster Energy"),
+            "protosynthesis" => self.weather == Some(Weather::Sun) || could_hold("Booster Energy"),
+            "swiftswim" => self.weather == Some(Weather::Rain),
+            "chlorophyll" => self.weather == Some(Weather::Sun),
+            "sandrush" => self.weather == Some(Weather::Sand),
+            "slushrush" => self.weather == Some(Weather::Snow),
+            "surgesurfer" => self.terrain == Some(Terrain::Electric),
+            "unburden" => self.item_lost[FOE].contains(&to_id(belief.species())),
+            "quickfeet" | "slowstart" => true,
+            _ => false,
+        }) || could_hold("Lagging Tail")
+            || could_hold("Full Incense")
+    }
+
+    /// Bounds the opponent's speed from the order the two sides moved in this turn, when both
+    /// used moves of the same priority
+    fn on_turn_end(&mut self) {
+        let moves = std::mem::take(&mut self.turn_moves);
+        if std::mem::take(&mut self.order_disrupted) {
+            return;
+        }
+        let (Some(own_at), Some(foe_at)) = (
+            moves.iter().position(|m| m.side == OWN),
+            moves.iter().position(|m| m.side == FOE),
+        ) else {
+            return;
+        };
+        let (own, foe) = (&moves[own_at], &moves[foe_at]);
+        if [&own.move_name, &foe.move_name]
+            .iter()
+            .any(|m| to_id(m) == "pursuit")
+        {
+            return;
+        }
+        let (Some(own_priority), Some(foe_priority)) = (
+            self.priority(OWN, &own.species, &own.move_name),
+            self.priority(FOE, &foe.species, &foe.move_name),
+        ) else {
+            return;
+        };
+        let Some(belief) = self.belief(&foe.species) else {
+            return;
+        };
+        if own_priority != foe_priority || self.speed_uncertain(belief) {
+            return;
+        }
+        let Some(own_speed) = self
+            .own_set(&own.species)
+            .and_then(|p| effective_speed(self.r#gen, &p))
+        else {
+            return;
+        };
+
+        let bound = own_speed * own.modifier / foe.modifier;
+        // Under Trick Room the slower Pokémon moves first
+        let observation = if (foe_at < own_at) != moves[0].trick_room {
+            Observation::SpeedAtLeast(bound)
+        } else {
+            Observation::SpeedAtMost(bound)
+        };
+        if let Some(belief) = self.beliefs.get_mut(&to_id(&foe.species)) {
+            belief.observe(observation);
+        }
+    }
+
+    /// The active Pokémon of a side as it stands: our set, or the opponent's most likely one
+    fn combatant(&self, s: usize) -> Option<Combatant> {
+        let active = &self.active[s];
+        let pokemon = if s == OWN {
+            self.own_set(&active.species)?
+        } else {
+            self.most_likely_set(&active.species)?
+        };
+        let tera_type = self.tera[s]
+            .as_ref()
+            .filter(|(species, _)| *species == to_id(&active.species))
+            .map(|(_, r#type)| *r#type);
+        Some(Combatant {
+            pokemon,
+            boosts: active.boosts,
+            status: active.status,
+            hp: Some(active.hp),
+            tera_type,
+        })
+    }
+
+    /// Weather, terrain and the screens up on the `defender` side
+    fn field(&self, defender: usize) -> Field {
+        Field {
+            weather: self.weather,
+            terrain: self.terrain,
+            screens: self.screens[defender],
+        }
+    }
+
+    fn active_hit(&self, attacker: usize, move_name: &str) -> Result<DamageResult> {
+        let defender = 1 - attacker;
+        let (Some(from), Some(to)) = (self.combatant(attacker), self.combatant(defender)) else {
+            bail!("No known active Pokémon on both sides");
+        };
+        damage::calculate(self.r#gen, &from, &to, move_name, &self.field(defender))
+    }
+
+    /// Damage of our active Pokémon's `move_name` against the opponent's most likely set,
+    /// with the current boosts, statuses, HP and field
+    pub fn damage_to_opponent(&self, move_name: &str) -> Result<DamageResult> {
+        self.active_hit(OWN, move_name)
+    }
+
+    /// Damage of `move_name` from the opponent's most likely set against our active Pokémon
+    pub fn damage_from_opponent(&self, move_name: &str) -> Result<DamageResult> {
+        self.active_hit(FOE, move_name)
+    }
+
+    /// Our active Pokémon's attacks against the opponent's, most damaging first
+    pub fn active_damage(&self) -> Vec<DamageResult> {
+        let Some(own) = self.own_set(&self.active[OWN].species) else {
+            return Vec::new();
+        };
+        let mut results: Vec<DamageResult> = own
+            .moves
+            .iter()
+            .filter_map(|m| self.damage_to_opponent(m).ok())
+            .collect();
+        results.sort_by_key(|r| std::cmp::Reverse(r.max()));
+        results
+    }
+
+    /// How the opposing `opponent` compares in speed to our `own` Pokémon
+    pub fn compare_speed(&self, opponent: &str, own: &str) -> Option<SpeedComparison> {
+        let mut comparison = self.belief(opponent)?.compare_speed(&self.own_set(own)?)?;
+        comparison.trick_room = self.trick_room;
+        Some(comparison)
+    }
+
+    /// Whether the opposing `opponent` is faster than our `own` Pokémon, when what we've seen
+    /// settles it
+    pub fn is_faster(&self, opponent: &str, own: &str) -> Option<bool> {
+        self.compare_speed(opponent, own)?.opponent_faster()
+    }
+
+    /// The speed comparison between the two active Pokémon
+    pub fn active_speed(&self) -> Option<SpeedComparison> {
+        self.compare_speed(&self.active[FOE].species, &self.active[OWN].species)
+    }
+
+    /// Items and abilities named as the source of an effect: `[from] item: X` belongs to the
+    /// `[of]` Pokémon when given, otherwise to the one the line is about
+    fn on_effect(&mut self, parts: &[&str], user_slot: &str) {
+        let owner = tagged(parts, "[of]").or_else(|| parts.get(2).copied());
+        if owner.and_then(|o| self.side(o, user_slot)) != Some(FOE) {
+            return;
+        }
+        if let Some(item) = tagged(parts, "[from] item:") {
+            self.observe(Observation::Item(item.to_string()));
+        }
+        if let Some(ability) = tagged(parts, "[from] ability:") {
+            self.observe(Observation::Ability(ability.to_string()));
+        }
+    }
+}
+
+impl fmt::Display for OpponentSets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.beliefs.values().map(|b| b.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
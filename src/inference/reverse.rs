@@ -0,0 +1,448 @@
+//! Reverse damage calculation: what an opposing Pokémon's set has to be for a hit to have done
+//! the damage it did.
+//!
+//! Our own side's HP is shown exactly and the opponent's only in percent, so a hit from the
+//! opponent pins its attacking investment down closely and a hit on it bounds its bulk more
+//! loosely. Every nature effect and EV amount in the stat the hit used (and in HP, for a
+//! defender) is tried with each item that would change the damage, keeping the
+//! [`Investment`]s whose rolls match what happened.
+
+use crate::damage::{self, Combatant, DamageResult, Field};
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::{EVs, Pokemon};
+use crate::stats::{Nature, Stat};
+use crate::types::Type;
+use anyhow::{Result, anyhow, bail};
+use std::fmt;
+
+/// Percent HP is rounded in the protocol both before and after the hit
+const PERCENT_SLACK: f64 = 0.0101;
+
+/// Items named for the type they boost, one per type
+const TYPE_ITEMS: [&str; 18] = [
+    "Silk Scarf",
+    "Black Belt",
+    "Sharp Beak",
+    "Poison Barb",
+    "Soft Sand",
+    "Hard Stone",
+    "Silver Powder",
+    "Spell Tag",
+    "Metal Coat",
+    "Charcoal",
+    "Mystic Water",
+    "Miracle Seed",
+    "Magnet",
+    "Twisted Spoon",
+    "Never-Melt Ice",
+    "Dragon Fang",
+    "Black Glasses",
+    "Pixie Plate",
+];
+
+/// Which side of a hit the opposing Pokémon was on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Position {
+    Attacker,
+    Defender,
+}
+
+/// How a nature affects one stat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NatureEffect {
+    Hindering,
+    Neutral,
+    Boosting,
+}
+
+impl NatureEffect {
+    pub const ALL: [NatureEffect; 3] = [
+        NatureEffect::Hindering,
+        NatureEffect::Neutral,
+        NatureEffect::Boosting,
+    ];
+
+    /// The effect of `nature` on `stat`, neutral when it's unknown
+    pub fn of(nature: Option<&str>, stat: Stat) -> NatureEffect {
+        match nature.and_then(|n| n.parse::<Nature>().ok()) {
+            Some(n) if n.plus() == Some(stat) => NatureEffect::Boosting,
+            Some(n) if n.minus() == Some(stat) => NatureEffect::Hindering,
+            _ => NatureEffect::Neutral,
+        }
+    }
+
+    /// A nature with this effect on `stat`, trading against Special Attack (or Attack, for
+    /// Special Attack itself) the way competitive sets do: Adamant, Impish, Modest...
+    pub fn nature(self, stat: Stat) -> Nature {
+        let other = if stat == Stat::SpA {
+            Stat::Atk
+        } else {
+            Stat::SpA
+        };
+        let wanted = match self {
+            NatureEffect::Boosting => Some((stat, other)),
+            NatureEffect::Hindering => Some((other, stat)),
+            NatureEffect::Neutral => None,
+        };
+        Nature::ALL
+            .into_iter()
+            .find(|n| n.modifiers() == wanted)
+            .unwrap_or(Nature::Serious)
+    }
+
+    /// "+", "" or "-", the way calc descriptions mark natures
+    fn symbol(self) -> &'static str {
+        match self {
+            NatureEffect::Hindering => "-",
+            NatureEffect::Neutral => "",
+            NatureEffect::Boosting => "+",
+        }
+    }
+}
+
+/// One way the opposing Pokémon could be built, as far as a hit can tell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Investment {
+    /// An item that changes the damage, `None` for any other
+    pub item: Option<String>,
+    pub nature: NatureEffect,
+    /// EVs in the stat the hit used
+    pub evs: u16,
+    /// HP EVs, 0 when it was the attacker
+    pub hp_evs: u16,
+}
+
+/// A hit between two Pokémon and the damage it did
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub move_name: String,
+    pub attacker: Combatant,
+    /// With its HP from before the hit
+    pub defender: Combatant,
+    pub field: Field,
+    pub crit: bool,
+    /// Share of the defender's max HP it took
+    pub fraction: f64,
+    pub knocked_out: bool,
+    /// Where the opposing Pokémon was. When it was the defender, its HP is only known in
+    /// percent.
+    pub opponent: Position,
+}
+
+impl Hit {
+    /// The opposing Pokémon's set in the hit
+    pub fn opponent(&self) -> &Pokemon {
+        match self.opponent {
+            Position::Attacker => &self.attacker.pokemon,
+            Position::Defender => &self.defender.pokemon,
+        }
+    }
+
+    /// The hit with the opposing Pokémon's set swapped for `pkmn`
+    pub fn with_opponent(&self, pkmn: Pokemon) -> Hit {
+        let mut hit = self.clone();
+        match self.opponent {
+            Position::Attacker => hit.attacker.pokemon = pkmn,
+            Position::Defender => hit.defender.pokemon = pkmn,
+        }
+        hit
+    }
+
+    pub fn calculate(&self, r#gen: u8) -> Result<DamageResult> {
+        damage::calculate(
+            r#gen,
+            &self.attacker,
+            &self.defender,
+            &self.move_name,
+            &self.field,
+        )
+    }
+
+    /// Whether the damage could have come from `result`'s rolls against `max_hp`. A knockout
+    /// only shows that a roll reached the HP the defender had left.
+    fn matches(&self, result: &DamageResult, max_hp: u16) -> bool {
+        let rolls = if self.crit {
+            &result.crit_rolls
+        } else {
+            &result.rolls
+        };
+        let max_hp = f64::from(max_hp.max(1));
+        let slack = match self.opponent {
+            Position::Defender => PERCENT_SLACK,
+            Position::Attacker => 0.5 / max_hp,
+        };
+        if self.knocked_out {
+            rolls
+                .last()
+                .is_some_and(|r| f64::from(*r) / max_hp + slack >= self.fraction)
+        } else {
+            rolls
+                .iter()
+                .any(|r| (f64::from(*r) / max_hp - self.fraction).abs() <= slack)
+        }
+    }
+
+    /// Whether the hit fits the opposing Pokémon having exactly `pkmn`'s set, `None` when the
+    /// hit can't be calculated or hit more than once
+    pub fn fits(&self, r#gen: u8, pkmn: &Pokemon) -> Option<bool> {
+        let result = self
+            .with_opponent(pkmn.clone())
+            .calculate(r#gen)
+            .ok()
+            .filter(|r| r.hits == 1)?;
+        Some(self.matches(&result, result.max_hp))
+    }
+}
+
+/// Every investment of the opposing Pokémon that fits one or more hits
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReverseCalc {
+    pub moves: Vec<String>,
+    pub position: Position,
+    /// The stat the hits used on the opposing Pokémon's side
+    pub stat: Stat,
+    pub candidates: Vec<Investment>,
+}
+
+/// Items that would change the damage the opposing Pokémon does or takes with `stat`
+fn damage_items(r#gen: u8, hit: &Hit, stat: Stat) -> Vec<Option<String>> {
+    let mut items = vec![None];
+    let mut add = |item: &str, since: u8| {
+        if r#gen >= since {
+            items.push(Some(item.to_string()));
+        }
+    };
+    match (hit.opponent, stat) {
+        (Position::Attacker, _) => {
+            match stat {
+                Stat::Atk => add("Choice Band", 3),
+                Stat::SpA => add("Choice Specs", 4),
+                _ => {}
+            }
+            add("Life Orb", 4);
+            let move_type = Dex::get()
+                .get_move(&hit.move_name)
+                .and_then(|m| m.r#type.parse::<Type>().ok());
+            if let Some(item) = TYPE_ITEMS
+                .iter()
+                .find(|i| damage::type_boosting_item(&to_id(i)) == move_type)
+            {
+                add(item, 3);
+            }
+        }
+        (Position::Defender, Stat::SpD) => add("Assault Vest", 6),
+        (Position::Defender, _) => {}
+    }
+    let evolves = Dex::get()
+        .get_species(
+            hit.opponent()
+                .species
+                .as_deref()
+                .unwrap_or(&hit.opponent().name),
+        )
+        .is_some_and(|s| !s.evos.is_empty());
+    if hit.opponent == Position::Defender && evolves {
+        add("Eviolite", 5);
+    }
+    items
+}
+
+/// Investments of the opposing Pokémon that fit `hit`. Its species, level, ability and, when
+/// set, item are taken from the hit; everything else about its stats is tried.
+pub fn reverse(r#gen: u8, hit: &Hit) -> Result<ReverseCalc> {
+    let base = hit.calculate(r#gen)?;
+    if base.hits != 1 {
+        bail!("{} hits more than once", hit.move_name);
+    }
+    let stat = match hit.opponent {
+        Position::Attacker => base
+            .attack_stat
+            .ok_or_else(|| anyhow!("{} uses the target's stat", hit.move_name))?,
+        Position::Defender => base.defense_stat,
+    };
+
+    let opponent = hit.opponent();
+    let species = Dex::get()
+        .get_species(opponent.species.as_deref().unwrap_or(&opponent.name))
+        .ok_or_else(|| anyhow!("Unknown species: {}", opponent.name))?
+        .in_gen(r#gen);
+    // HP EVs only change the max HP, not the rolls
+    let hp_options: Vec<(u16, u16)> = match hit.opponent {
+        Position::Attacker => vec![(0, base.max_hp)],
+        Position::Defender => (0..=252)
+            .step_by(4)
+            .map(|hp_evs| {
+                let mut pkmn = opponent.clone();
+                pkmn.evs = EVs::default();
+                pkmn.evs.set(Stat::HP, hp_evs);
+                (hp_evs, pkmn.stats(r#gen, &species.base_stats).hp)
+            })
+            .collect(),
+    };
+    let items = match opponent.item {
+        Some(ref item) => vec![Some(item.clone())],
+        None => damage_items(r#gen, hit, stat),
+    };
+
+    let mut candidates = Vec::new();
+    for item in &items {
+        for nature in NatureEffect::ALL {
+            for evs in (0..=252).step_by(4) {
+                let mut pkmn = opponent.clone();
+                pkmn.item = item.clone();
+                pkmn.nature = Some(nature.nature(stat).to_string());
+                pkmn.evs = EVs::default();
+                pkmn.evs.set(stat, evs);
+                let result = hit.with_opponent(pkmn).calculate(r#gen)?;
+                for (hp_evs, max_hp) in &hp_options {
+                    if hit.matches(&result, *max_hp) {
+                        candidates.push(Investment {
+                            item: item.clone(),
+                            nature,
+                            evs,
+                            hp_evs: *hp_evs,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Ok(ReverseCalc {
+        moves: vec![hit.move_name.clone()],
+        position: hit.opponent,
+        stat,
+        candidates,
+    })
+}
+
+impl ReverseCalc {
+    /// Damage items still possible, with `None` for holding none of them
+    pub fn items(&self) -> Vec<Option<String>> {
+        let mut items: Vec<Option<String>> = Vec::new();
+        for c in &self.candidates {
+            if !items.contains(&c.item) {
+                items.push(c.item.clone());
+            }
+        }
+        items
+    }
+
+    /// The candidate item `item` counts as: itself when it changes the damage, otherwise none
+    fn item_of(&self, item: Option<&str>) -> Option<String> {
+        let id = item.map(to_id)?;
+        self.candidates
+            .iter()
+            .filter_map(|c| c.item.as_ref())
+            .find(|c| to_id(c) == id)
+            .cloned()
+    }
+
+    /// Whether `item` is still possible, any item that doesn't change the damage counting as
+    /// none
+    pub fn allows_item(&self, item: Option<&str>) -> bool {
+        self.items().contains(&self.item_of(item))
+    }
+
+    /// Whether the set fits: its item, its nature's effect on the stat and its EVs
+    pub fn allows(&self, pkmn: &Pokemon) -> bool {
+        let item = self.item_of(pkmn.item.as_deref());
+        let nature = NatureEffect::of(pkmn.nature.as_deref(), self.stat);
+        let evs = pkmn.evs.get(self.stat) / 4 * 4;
+        let hp_evs = match self.position {
+            Position::Attacker => 0,
+            Position::Defender => pkmn.evs.hp / 4 * 4,
+        };
+        self.candidates
+            .iter()
+            .any(|c| c.item == item && c.nature == nature && c.evs == evs && c.hp_evs == hp_evs)
+    }
+
+    /// Keeps the candidates `other` also allows, when it is about the same stat. Returns
+    /// whether it did; hits that contradict each other leave it as it was.
+    pub fn narrow(&mut self, other: &ReverseCalc) -> bool {
+        if other.position != self.position || other.stat != self.stat {
+            return false;
+        }
+        let kept: Vec<Investment> = self
+            .candidates
+            .iter()
+            .filter(|c| other.candidates.contains(c))
+            .cloned()
+            .collect();
+        if kept.is_empty() {
+            return false;
+        }
+        self.candidates = kept;
+        for name in &other.moves {
+            if !self.moves.contains(name) {
+                self.moves.push(name.clone());
+            }
+        }
+        true
+    }
+
+    /// The most invested candidate, the one a standard set would be. Ties go to the item
+    /// tried first: none, then Choice items, then the rest.
+    pub fn strongest(&self) -> Option<&Investment> {
+        self.candidates
+            .iter()
+            .rev()
+            .max_by_key(|c| (c.evs + c.hp_evs, c.nature))
+    }
+}
+
+impl fmt::Display for ReverseCalc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): ",
+            self.stat.short_name(),
+            self.moves.join(", ")
+        )?;
+        if self.candidates.is_empty() {
+            return write!(f, "no spread fits");
+        }
+        let range = |values: Vec<u16>| {
+            let (low, high) = (
+                values.iter().min().copied().unwrap_or(0),
+                values.iter().max().copied().unwrap_or(0),
+            );
+            if low == high {
+                low.to_string()
+            } else {
+                format!("{}-{}", low, high)
+            }
+        };
+        let mut groups = Vec::new();
+        for item in self.items() {
+            let mut spreads = Vec::new();
+            for nature in NatureEffect::ALL.into_iter().rev() {
+                let matching: Vec<&Investment> = self
+                    .candidates
+                    .iter()
+                    .filter(|c| c.item == item && c.nature == nature)
+                    .collect();
+                if matching.is_empty() {
+                    continue;
+                }
+                let evs = range(matching.iter().map(|c| c.evs).collect());
+                let stat = format!("{}{} {}", evs, nature.symbol(), self.stat.short_name());
+                spreads.push(match self.position {
+                    Position::Attacker => stat,
+                    Position::Defender => format!(
+                        "{} HP / {}",
+                        range(matching.iter().map(|c| c.hp_evs).collect()),
+                        stat
+                    ),
+                });
+            }
+            groups.push(format!(
+                "{} {}",
+                item.as_deref().unwrap_or("any other item"),
+                spreads.join(" or ")
+            ));
+        }
+        write!(f, "{}", groups.join("; "))
+    }
+}
@@ -0,0 +1,149 @@
+//! Speed bounds from the order moves happen in within a turn.
+//!
+//! When both active Pokémon use moves of the same priority, the faster one goes first, or the
+//! slower one under Trick Room. Since our own speed is known, every such turn bounds the
+//! opponent's: its speed stat with its item's multiplier, once the stages, paralysis and
+//! Tailwind in effect that turn are taken out. [`SpeedComparison`] answers whether an opposing
+//! Pokémon outspeeds one of ours from those bounds and the spreads the belief still allows.
+
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::Pokemon;
+use crate::stats::{Stat, stage_multiplier};
+use std::fmt;
+
+/// In-game speeds are floored at each step, so bounds get a point of slack
+const SLACK: f64 = 1.0;
+
+/// Bounds on an opposing Pokémon's speed stat, item included. Ties are broken at random, so
+/// both ends are inclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpeedBounds {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl SpeedBounds {
+    pub fn at_least(&mut self, speed: f64) {
+        self.min = Some(self.min.map_or(speed, |min| min.max(speed)));
+    }
+
+    pub fn at_most(&mut self, speed: f64) {
+        self.max = Some(self.max.map_or(speed, |max| max.min(speed)));
+    }
+
+    pub fn is_known(&self) -> bool {
+        self.min.is_some() || self.max.is_some()
+    }
+
+    pub fn contains(&self, speed: f64) -> bool {
+        self.min.is_none_or(|min| speed + SLACK >= min)
+            && self.max.is_none_or(|max| speed - SLACK <= max)
+    }
+}
+
+impl fmt::Display for SpeedBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => write!(f, "{:.0}-{:.0}", min, max),
+            (Some(min), None) => write!(f, ">= {:.0}", min),
+            (None, Some(max)) => write!(f, "<= {:.0}", max),
+            (None, None) => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Multiplier on a Pokémon's speed from its stat stage, paralysis and Tailwind on its side
+pub fn speed_modifier(r#gen: u8, stage: i8, paralyzed: bool, tailwind: bool) -> f64 {
+    let (num, den) = stage_multiplier(r#gen, stage);
+    let mut modifier = num as f64 / den as f64;
+    if paralyzed {
+        modifier *= if r#gen >= 7 { 0.5 } else { 0.25 };
+    }
+    if tailwind {
+        modifier *= 2.0;
+    }
+    modifier
+}
+
+/// Speed multiplier of a held item
+pub fn item_factor(item: Option<&str>) -> f64 {
+    match item.map(to_id).as_deref() {
+        Some("choicescarf") => 1.5,
+        Some("ironball" | "machobrace") => 0.5,
+        Some(id) if id.starts_with("power") && id != "powerherb" => 0.5,
+        _ => 1.0,
+    }
+}
+
+/// The Pokémon's speed stat in `gen`, `None` for species the dex doesn't know
+pub fn speed_stat(r#gen: u8, pkmn: &Pokemon) -> Option<u16> {
+    let species = Dex::get().get_species(pkmn.species.as_deref().unwrap_or(&pkmn.name))?;
+    Some(
+        pkmn.stats(r#gen, &species.in_gen(r#gen).base_stats)
+            .get(Stat::Spe),
+    )
+}
+
+/// Speed stat with the item's multiplier, what [`SpeedBounds`] are about
+pub fn effective_speed(r#gen: u8, pkmn: &Pokemon) -> Option<f64> {
+    Some(f64::from(speed_stat(r#gen, pkmn)?) * item_factor(pkmn.item.as_deref()))
+}
+
+/// How an opposing Pokémon's speed compares to one of ours, both with their items and before
+/// any boosts
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedComparison {
+    pub opponent: String,
+    pub own: String,
+    pub own_speed: f64,
+    /// Slowest and fastest the opponent can be given what we've seen
+    pub opponent_range: (f64, f64),
+    /// Chance it is faster over the belief's spreads and items, `None` without spread data
+    pub probability_faster: Option<f64>,
+    /// Trick Room is up, so the slower Pokémon moves first
+    pub trick_room: bool,
+}
+
+impl SpeedComparison {
+    /// Whether the opponent is faster, when the range settles it. Ties count as neither.
+    pub fn opponent_faster(&self) -> Option<bool> {
+        let (min, max) = self.opponent_range;
+        if min > self.own_speed {
+            Some(true)
+        } else if max < self.own_speed {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the opponent moves first when both use moves of the same priority
+    pub fn opponent_moves_first(&self) -> Option<bool> {
+        self.opponent_faster()
+            .map(|faster| faster != self.trick_room)
+    }
+}
+
+impl fmt::Display for SpeedComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = self.opponent_range;
+        let verdict = match self.opponent_faster() {
+            Some(true) => "is faster than".to_string(),
+            Some(false) => "is slower than".to_string(),
+            None => match self.probability_faster {
+                Some(p) => format!("is faster ({:.0}% likely) than", p * 100.0),
+                None => "may be faster than".to_string(),
+            },
+        };
+        write!(
+            f,
+            "{} ({:.0}-{:.0} Spe) {} your {} ({:.0} Spe)",
+            self.opponent, min, max, verdict, self.own, self.own_speed
+        )?;
+        if self.trick_room {
+            write!(f, ", but Trick Room is up")?;
+        }
+        Ok(())
+    }
+}
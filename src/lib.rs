@@ -1,13 +1,34 @@
 mod agent;
+pub mod analysis;
 mod client;
+pub mod damage;
+pub mod data;
+pub mod format;
+pub mod id;
+pub mod inference;
+pub mod library;
+pub mod lookup;
 mod parser;
-mod pokeapi;
+pub mod pokeapi;
+pub mod search;
+pub mod sets;
+pub mod stats;
 pub mod tools;
+pub mod types;
+pub mod usage;
+pub mod validator;
 
 pub use agent::{BattleAgent, ModelType};
 pub use anyhow::Result;
 pub use client::ShowdownClient;
 pub use colored::Colorize;
+pub use lookup::{
+    AbilityInfo, Comparison, ItemInfo, LearnCheck, LearnsetInfo, MoveInfo, TypeMatchups, can_learn,
+    compare, fetch_ability_info, fetch_item_info, fetch_learnset, fetch_move_info, type_matchups,
+};
 pub use parser::logs::{BattleEvents, Token};
-pub use parser::team::Team;
-pub use pokeapi::{PokemonInfo, fetch_pokemon_info, pretty_display};
+pub use parser::team::{EVs, Pokemon, Team};
+pub use pokeapi::{
+    PokeApiClient, PokemonInfo, fetch_pokemon_info, fetch_pokemon_info_in, pretty_display,
+    print_pokemon,
+};
@@ -0,0 +1,424 @@
+//! Directory-backed team library.
+//!
+//! Each team is a Showdown text file (`<id>.txt`) so the directory stays readable and editable
+//! by hand, and `library.json` next to them holds the metadata. Text files without an index
+//! entry, like the ones already in `teams/`, show up with default metadata.
+
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::Team;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Environment variable overriding the library directory
+pub const LIBRARY_DIR_ENV: &str = "POKEBRAINS_LIBRARY_DIR";
+
+const INDEX_FILE: &str = "library.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Tie,
+}
+
+impl std::str::FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match to_id(s).as_str() {
+            "win" | "w" => Ok(Outcome::Win),
+            "loss" | "lose" | "l" => Ok(Outcome::Loss),
+            "tie" | "draw" | "t" => Ok(Outcome::Tie),
+            _ => Err(anyhow!("Unknown battle outcome: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-{}", self.wins, self.losses, self.ties)
+    }
+}
+
+/// Metadata of a stored team
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamEntry {
+    /// File stem of the team file, unique within the library
+    #[serde(skip)]
+    pub id: String,
+    pub name: String,
+    pub format: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Unix timestamps in seconds
+    pub created: u64,
+    pub updated: u64,
+    #[serde(default)]
+    pub record: Record,
+}
+
+impl fmt::Display for TeamEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} \"{}\"", self.id, self.name)?;
+        if let Some(ref format) = self.format {
+            write!(f, " [{}]", format)?;
+        }
+        if !self.tags.is_empty() {
+            write!(f, " #{}", self.tags.join(" #"))?;
+        }
+        write!(f, " ({})", self.record)
+    }
+}
+
+/// Filters for [`TeamLibrary::search`]; every given filter has to match
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TeamQuery {
+    pub format: Option<String>,
+    pub species: Vec<String>,
+    pub tags: Vec<String>,
+    /// Words that must appear in the team name
+    pub words: Vec<String>,
+}
+
+impl TeamQuery {
+    /// Reads a loose query like "teams with Ferrothorn in gen5ou #rain": format ids, species
+    /// names (up to two words, like "Tapu Koko"), `#tags` and name words
+    pub fn parse(query: &str) -> TeamQuery {
+        const FILLER: [&str; 9] = [
+            "team", "teams", "with", "in", "for", "and", "the", "a", "using",
+        ];
+        let dex = Dex::get();
+        let words: Vec<&str> = query.split_whitespace().collect();
+        let mut parsed = TeamQuery::default();
+        let mut i = 0;
+
+        while i < words.len() {
+            let word = words[i];
+            if let Some(tag) = word.strip_prefix('#') {
+                parsed.tags.push(tag.to_string());
+            } else if i + 1 < words.len()
+                && let Some(species) = dex.get_species(&format!("{} {}", word, words[i + 1]))
+            {
+                parsed.species.push(species.name.clone());
+                i += 1;
+            } else if let Some(species) = dex.get_species(word) {
+                parsed.species.push(species.name.clone());
+            } else if is_format_id(word) {
+                parsed.format = Some(to_id(word));
+            } else if !FILLER.contains(&word.to_lowercase().as_str()) {
+                parsed.words.push(word.to_string());
+            }
+            i += 1;
+        }
+        parsed
+    }
+
+    fn matches(&self, entry: &TeamEntry, team: &Team) -> bool {
+        let format_ok = self
+            .format
+            .as_ref()
+            .is_none_or(|f| entry.format.as_deref().map(to_id) == Some(to_id(f)));
+        let tags_ok = self
+            .tags
+            .iter()
+            .all(|t| entry.tags.iter().any(|e| to_id(e) == to_id(t)));
+        let words_ok = self
+            .words
+            .iter()
+            .all(|w| entry.name.to_lowercase().contains(&w.to_lowercase()));
+        let species_ok = self.species.iter().all(|s| {
+            team.pokemon
+                .iter()
+                .any(|p| to_id(p.species.as_deref().unwrap_or(&p.name)) == to_id(s))
+        });
+        format_ok && tags_ok && words_ok && species_ok
+    }
+}
+
+/// "gen5ou", "gen9vgc2024" and the like
+fn is_format_id(word: &str) -> bool {
+    to_id(word)
+        .strip_prefix("gen")
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    #[serde(default)]
+    teams: BTreeMap<String, TeamEntry>,
+}
+
+/// A directory of teams with metadata
+#[derive(Debug, Clone)]
+pub struct TeamLibrary {
+    dir: PathBuf,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl TeamLibrary {
+    /// `POKEBRAINS_LIBRARY_DIR` when set, otherwise `teams/`
+    pub fn default_dir() -> PathBuf {
+        std::env::var(LIBRARY_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("teams"))
+    }
+
+    /// Opens the library in `dir`, creating the directory if needed
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(TeamLibrary { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn team_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.txt", id))
+    }
+
+    fn read_index(&self) -> Result<Index> {
+        let path = self.dir.join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(Index::default());
+        }
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut index: Index = serde_json::from_str(&text)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        for (id, entry) in index.teams.iter_mut() {
+            entry.id = id.clone();
+        }
+        Ok(index)
+    }
+
+    fn write_index(&self, index: &Index) -> Result<()> {
+        let path = self.dir.join(INDEX_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(index)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Every team in the library, sorted by id
+    pub fn list(&self) -> Result<Vec<TeamEntry>> {
+        let mut index = self.read_index()?;
+        for file in std::fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|e| e != "txt") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if index.teams.contains_key(id) {
+                continue;
+            }
+            let modified = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            index.teams.insert(
+                id.to_string(),
+                TeamEntry {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    format: None,
+                    tags: Vec::new(),
+                    created: modified,
+                    updated: modified,
+                    record: Record::default(),
+                },
+            );
+        }
+        // Index entries whose file was deleted by hand are dropped
+        Ok(index
+            .teams
+            .into_values()
+            .filter(|e| self.team_path(&e.id).exists())
+            .collect())
+    }
+
+    pub fn entry(&self, id: &str) -> Result<TeamEntry> {
+        self.list()?
+            .into_iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| anyhow!("No team '{}' in {}", id, self.dir.display()))
+    }
+
+    pub async fn load(&self, id: &str) -> Result<Team> {
+        self.entry(id)?;
+        Team::deserialize_from_file(&self.team_path(id).to_string_lossy()).await
+    }
+
+    /// Stores a new team and returns its entry. The id is derived from the name.
+    pub fn save(
+        &self,
+        name: &str,
+        team: &Team,
+        format: Option<&str>,
+        tags: &[String],
+    ) -> Result<TeamEntry> {
+        let mut index = self.read_index()?;
+        let existing = self.list()?;
+        let base = match to_id(name) {
+            id if id.is_empty() => "team".to_string(),
+            id => id,
+        };
+        let mut id = base.clone();
+        let mut n = 2;
+        while existing.iter().any(|e| e.id == id) {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+
+        std::fs::write(self.team_path(&id), team.serialize())
+            .with_context(|| format!("Failed to write {}", self.team_path(&id).display()))?;
+        let time = now();
+        let entry = TeamEntry {
+            id: id.clone(),
+            name: name.to_string(),
+            format: format.map(to_id),
+            tags: tags.to_vec(),
+            created: time,
+            updated: time,
+            record: Record::default(),
+        };
+        index.teams.insert(id, entry.clone());
+        self.write_index(&index)?;
+        Ok(entry)
+    }
+
+    /// Replaces a stored team, keeping its metadata
+    pub fn update(&self, id: &str, team: &Team) -> Result<TeamEntry> {
+        self.modify(id, |_| ())?;
+        std::fs::write(self.team_path(id), team.serialize())
+            .with_context(|| format!("Failed to write {}", self.team_path(id).display()))?;
+        self.entry(id)
+    }
+
+    fn modify(&self, id: &str, change: impl FnOnce(&mut TeamEntry)) -> Result<TeamEntry> {
+        let mut entry = self.entry(id)?;
+        change(&mut entry);
+        entry.updated = now();
+        let mut index = self.read_index()?;
+        index.teams.insert(id.to_string(), entry.clone());
+        self.write_index(&index)?;
+        Ok(entry)
+    }
+
+    pub fn set_format(&self, id: &str, format: &str) -> Result<TeamEntry> {
+        self.modify(id, |e| e.format = Some(to_id(format)))
+    }
+
+    pub fn add_tags(&self, id: &str, tags: &[String]) -> Result<TeamEntry> {
+        self.modify(id, |e| {
+            for tag in tags {
+                if !e.tags.iter().any(|t| to_id(t) == to_id(tag)) {
+                    e.tags.push(tag.clone());
+                }
+            }
+        })
+    }
+
+    pub fn remove_tags(&self, id: &str, tags: &[String]) -> Result<TeamEntry> {
+        self.modify(id, |e| {
+            e.tags
+                .retain(|t| !tags.iter().any(|tag| to_id(t) == to_id(tag)))
+        })
+    }
+
+    /// Adds a battle result to the team's win record
+    pub fn record_result(&self, id: &str, outcome: Outcome) -> Result<TeamEntry> {
+        self.modify(id, |e| match outcome {
+            Outcome::Win => e.record.wins += 1,
+            Outcome::Loss => e.record.losses += 1,
+            Outcome::Tie => e.record.ties += 1,
+        })
+    }
+
+    /// Deletes the team file and its metadata
+    pub fn remove(&self, id: &str) -> Result<()> {
+        self.entry(id)?;
+        std::fs::remove_file(self.team_path(id))?;
+        let mut index = self.read_index()?;
+        if index.teams.remove(id).is_some() {
+            self.write_index(&index)?;
+        }
+        Ok(())
+    }
+
+    /// Teams matching every filter of the query
+    pub async fn search(&self, query: &TeamQuery) -> Result<Vec<TeamEntry>> {
+        let mut found = Vec::new();
+        for entry in self.list()? {
+            let team = self.load(&entry.id).await?;
+            if query.matches(&entry, &team) {
+                found.push(entry);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Imports a team file: Showdown text, packed text, or the JSON/TOML of [`Team::to_json`]
+    /// and [`Team::to_toml`], picked by extension. The name defaults to the file stem.
+    pub async fn import(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        format: Option<&str>,
+        tags: &[String],
+    ) -> Result<TeamEntry> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let team = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Team::from_json(&text)?,
+            Some("toml") => Team::from_toml(&text)?,
+            _ if !text.trim().contains('\n') && text.contains('|') => {
+                Team::deserialize_packed(&text)
+            }
+            _ => Team::deserialize(&text).await,
+        };
+        if team.pokemon.is_empty() {
+            return Err(anyhow!("{} contains no Pokémon", path.display()));
+        }
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("team");
+        self.save(name.unwrap_or(stem), &team, format, tags)
+    }
+
+    /// Writes a team to `path` as Showdown text, packed text (`.packed`), JSON, TOML,
+    /// Markdown (`.md`) or HTML, picked by extension
+    pub async fn export(&self, id: &str, path: &Path) -> Result<()> {
+        let entry = self.entry(id)?;
+        let team = self.load(id).await?;
+        let output = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => team.to_json()?,
+            Some("toml") => team.to_toml()?,
+            Some("md") => team.to_markdown(&entry.name),
+            Some("html") => team.to_html(&entry.name),
+            Some("packed") => team.serialize_packed(),
+            _ => team.serialize(),
+        };
+        std::fs::write(path, output).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
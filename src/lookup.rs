@@ -0,0 +1,1006 @@
+//! Readable summaries of bundled moves, items, abilities, learnsets and type matchups for the
+//! agent's tools and `pokefetcher`.
+
+use crate::data::{Ability, Accuracy, Dex, Item, Move, Species, source_usable_in};
+use crate::id::{did_you_mean, to_id};
+use crate::pokeapi::{AbilityDetails, ItemDetails, MoveDetails, PokeApiClient};
+use crate::search::{SpeciesMatch, StatKey};
+use crate::stats::Stat;
+use crate::types::{Matchup, Type, type_effectiveness_in};
+use anyhow::{Result, bail};
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// A move as the agent needs to know it
+#[derive(Debug, Clone, Serialize)]
+pub struct MoveInfo {
+    pub name: String,
+    pub r#type: String,
+    pub category: String,
+    pub base_power: u16,
+    /// `None` for moves that never miss
+    pub accuracy: Option<u8>,
+    pub pp: u8,
+    pub priority: i8,
+    pub target: String,
+    /// Showdown flags such as "contact", "sound" or "punch"
+    pub flags: Vec<String>,
+    /// Status, stat changes, secondary effects, recoil and the like, in words
+    pub effects: Vec<String>,
+    /// Generation the move was introduced in
+    pub r#gen: u8,
+}
+
+impl MoveInfo {
+    pub fn from_dex(mv: &Move) -> MoveInfo {
+        MoveInfo {
+            name: mv.name.clone(),
+            r#type: mv.r#type.clone(),
+            category: mv.category.clone(),
+            base_power: mv.base_power,
+            accuracy: match mv.accuracy {
+                Accuracy::Percent(percent) => Some(percent),
+                Accuracy::Always(_) => None,
+            },
+            pp: mv.pp,
+            priority: mv.priority,
+            target: mv.target.clone(),
+            flags: mv.flags.keys().cloned().collect(),
+            effects: move_effects(mv),
+            r#gen: mv.r#gen,
+        }
+    }
+
+    /// The same summary from PokeAPI, which has no flags and describes effects in prose
+    pub fn from_api(mv: &MoveDetails) -> MoveInfo {
+        MoveInfo {
+            name: mv.english_name(),
+            r#type: capitalize(&mv.r#type.name),
+            category: capitalize(&mv.damage_class.name),
+            base_power: mv.power.unwrap_or(0),
+            accuracy: mv.accuracy,
+            pp: mv.pp.unwrap_or(0),
+            priority: mv.priority,
+            target: mv.target.name.clone(),
+            flags: Vec::new(),
+            effects: mv.effect().into_iter().collect(),
+            r#gen: mv.introduced().unwrap_or(0),
+        }
+    }
+
+    pub fn to_readable_form(&self) -> String {
+        let mut s = format!("Move: {}\n", self.name);
+        s.push_str(&format!(
+            "Type: {} | Category: {} | Generation: {}\n",
+            self.r#type, self.category, self.r#gen
+        ));
+        let accuracy = self
+            .accuracy
+            .map_or("never misses".to_string(), |a| format!("{}%", a));
+        s.push_str(&format!(
+            "Power: {} | Accuracy: {} | PP: {} | Priority: {:+}\n",
+            self.base_power, accuracy, self.pp, self.priority
+        ));
+        s.push_str(&format!("Target: {}\n", self.target));
+        if !self.flags.is_empty() {
+            s.push_str(&format!("Flags: {}\n", self.flags.join(", ")));
+        }
+        if !self.effects.is_empty() {
+            s.push_str("Effects:\n");
+            for effect in &self.effects {
+                s.push_str(&format!("  - {}\n", effect));
+            }
+        }
+        s
+    }
+}
+
+/// Looks a move up in the bundled data, or on PokeAPI when the bundled subset lacks it, and
+/// checks it exists in `gen`
+pub async fn fetch_move_info(name: &str, r#gen: u8) -> Result<MoveInfo> {
+    let dex = Dex::get();
+    let Some(mv) = dex.get_move(name) else {
+        let suggestion = dex.suggest_move(name).map(|m| m.name.as_str());
+        if dex.is_complete() {
+            bail!("Unknown move: {}{}", name, did_you_mean(suggestion));
+        }
+        let details = PokeApiClient::get().move_details(name).await;
+        let info = MoveInfo::from_api(&not_bundled(details, "move", name, suggestion)?);
+        if info.r#gen > r#gen {
+            bail!("{} is not available in Gen {}", info.name, r#gen);
+        }
+        return Ok(info);
+    };
+    if !mv.exists_in(r#gen) {
+        bail!("{} is not available in Gen {}", mv.name, r#gen);
+    }
+    Ok(MoveInfo::from_dex(mv))
+}
+
+/// An item's effect and the facts teambuilding depends on
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemInfo {
+    pub name: String,
+    pub effect: String,
+    /// Generation the item was introduced in
+    pub r#gen: u8,
+    /// The forme a mega stone turns its holder into
+    pub mega_stone: Option<String>,
+    /// The type of Z-Move a Z crystal powers up
+    pub z_crystal: Option<String>,
+    /// Base power of Fling with this item; `None` when it can't be flung
+    pub fling_power: Option<u16>,
+    pub is_berry: bool,
+    pub is_choice: bool,
+    /// Species the item only works for, such as Charizard for Charizardite X
+    pub users: Vec<String>,
+}
+
+impl ItemInfo {
+    pub fn from_dex(item: &Item) -> ItemInfo {
+        let z_crystal = item.extra.get("zMove").map(|_| {
+            item.extra
+                .get("zMoveType")
+                .and_then(Value::as_str)
+                .unwrap_or("signature")
+                .to_string()
+        });
+        ItemInfo {
+            name: item.name.clone(),
+            effect: item.short_desc.clone(),
+            r#gen: item.r#gen,
+            mega_stone: item.mega_stone.clone(),
+            z_crystal,
+            fling_power: item
+                .extra
+                .get("fling")
+                .and_then(|fling| fling.get("basePower"))
+                .and_then(Value::as_u64)
+                .map(|power| power as u16),
+            is_berry: item.is_berry,
+            is_choice: item.is_choice,
+            users: item.item_user.clone(),
+        }
+    }
+
+    /// The same summary from PokeAPI, which doesn't say what Mega Stones and Z Crystals do
+    pub fn from_api(item: &ItemDetails) -> ItemInfo {
+        ItemInfo {
+            name: item.english_name(),
+            effect: item.effect().unwrap_or_default(),
+            r#gen: item.introduced().unwrap_or(0),
+            mega_stone: None,
+            z_crystal: None,
+            fling_power: item.fling_power,
+            is_berry: item.name.ends_with("-berry"),
+            is_choice: item.category.name == "choice",
+            users: Vec::new(),
+        }
+    }
+
+    pub fn to_readable_form(&self) -> String {
+        let mut s = format!("Item: {}\nGeneration: {}\n", self.name, self.r#gen);
+        s.push_str(&format!("Effect: {}\n", self.effect));
+        if let Some(mega) = &self.mega_stone {
+            s.push_str(&format!("Mega Stone: evolves into {}\n", mega));
+        }
+        if let Some(r#type) = &self.z_crystal {
+            s.push_str(&format!("Z Crystal: {} Z-Move\n", r#type));
+        }
+        if !self.users.is_empty() {
+            s.push_str(&format!("Only works for: {}\n", self.users.join(", ")));
+        }
+        match self.fling_power {
+            Some(power) => s.push_str(&format!("Fling power: {}\n", power)),
+            None => s.push_str("Fling: fails\n"),
+        }
+        s
+    }
+}
+
+/// An ability's effect and when it was introduced
+#[derive(Debug, Clone, Serialize)]
+pub struct AbilityInfo {
+    pub name: String,
+    pub effect: String,
+    /// Generation the ability was introduced in
+    pub r#gen: u8,
+}
+
+impl AbilityInfo {
+    pub fn from_dex(ability: &Ability) -> AbilityInfo {
+        AbilityInfo {
+            name: ability.name.clone(),
+            effect: ability.short_desc.clone(),
+            r#gen: ability.r#gen,
+        }
+    }
+
+    pub fn from_api(ability: &AbilityDetails) -> AbilityInfo {
+        AbilityInfo {
+            name: ability.english_name(),
+            effect: ability.effect().unwrap_or_default(),
+            r#gen: ability.introduced().unwrap_or(0),
+        }
+    }
+
+    pub fn to_readable_form(&self) -> String {
+        format!(
+            "Ability: {}\nGeneration: {}\nEffect: {}\n",
+            self.name, self.r#gen, self.effect
+        )
+    }
+}
+
+/// Looks an item up in the bundled data, or on PokeAPI when the bundled subset lacks it, and
+/// checks it exists in `gen`
+pub async fn fetch_item_info(name: &str, r#gen: u8) -> Result<ItemInfo> {
+    let dex = Dex::get();
+    let Some(item) = dex.get_item(name) else {
+        let suggestion = dex.suggest_item(name).map(|i| i.name.as_str());
+        if dex.is_complete() {
+            bail!("Unknown item: {}{}", name, did_you_mean(suggestion));
+        }
+        let details = PokeApiClient::get().item_details(name).await;
+        let info = ItemInfo::from_api(&not_bundled(details, "item", name, suggestion)?);
+        // Held items arrived in Gen 2
+        if info.r#gen > r#gen || r#gen < 2 {
+            bail!("{} is not available in Gen {}", info.name, r#gen);
+        }
+        return Ok(info);
+    };
+    if dex.item_in(&item.name, r#gen).is_none() {
+        bail!("{} is not available in Gen {}", item.name, r#gen);
+    }
+    Ok(ItemInfo::from_dex(item))
+}
+
+/// Looks an ability up in the bundled data, or on PokeAPI when the bundled subset lacks it, and
+/// checks it exists in `gen`
+pub async fn fetch_ability_info(name: &str, r#gen: u8) -> Result<AbilityInfo> {
+    let dex = Dex::get();
+    let Some(ability) = dex.get_ability(name) else {
+        let suggestion = dex.suggest_ability(name).map(|a| a.name.as_str());
+        if dex.is_complete() {
+            bail!("Unknown ability: {}{}", name, did_you_mean(suggestion));
+        }
+        let details = PokeApiClient::get().ability_details(name).await;
+        let info = AbilityInfo::from_api(&not_bundled(details, "ability", name, suggestion)?);
+        // Abilities arrived in Gen 3
+        if info.r#gen > r#gen || r#gen < 3 {
+            bail!("{} is not available in Gen {}", info.name, r#gen);
+        }
+        return Ok(info);
+    };
+    if dex.ability_in(&ability.name, r#gen).is_none() {
+        bail!("{} is not available in Gen {}", ability.name, r#gen);
+    }
+    Ok(AbilityInfo::from_dex(ability))
+}
+
+/// How a Pokémon learns a move, from the letter in a Showdown learnset source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LearnMethod {
+    LevelUp,
+    /// TM, HM or TR
+    Machine,
+    Tutor,
+    Egg,
+    Event,
+    DreamWorld,
+    VirtualConsole,
+    /// Learned by changing forme, like Rotom's appliance moves
+    FormeChange,
+}
+
+impl LearnMethod {
+    fn from_code(code: char) -> Option<LearnMethod> {
+        Some(match code {
+            'L' => LearnMethod::LevelUp,
+            'M' => LearnMethod::Machine,
+            'T' => LearnMethod::Tutor,
+            'E' => LearnMethod::Egg,
+            'S' => LearnMethod::Event,
+            'D' => LearnMethod::DreamWorld,
+            'V' => LearnMethod::VirtualConsole,
+            'R' => LearnMethod::FormeChange,
+            _ => return None,
+        })
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LearnMethod::LevelUp => "level-up",
+            LearnMethod::Machine => "TM",
+            LearnMethod::Tutor => "tutor",
+            LearnMethod::Egg => "egg move",
+            LearnMethod::Event => "event",
+            LearnMethod::DreamWorld => "Dream World",
+            LearnMethod::VirtualConsole => "Virtual Console transfer",
+            LearnMethod::FormeChange => "forme change",
+        }
+    }
+}
+
+/// One way of learning a move, such as "8M" (a Gen 8 TM) or "9L12" (level 12 in Gen 9)
+#[derive(Debug, Clone, Serialize)]
+pub struct LearnSource {
+    pub r#gen: u8,
+    pub method: LearnMethod,
+    /// Level the move is learned at, when the data has it
+    pub level: Option<u8>,
+    /// The species that learns it, a prevo for moves it has to learn before evolving
+    pub learner: String,
+}
+
+impl LearnSource {
+    pub fn parse(source: &str, learner: &str) -> Option<LearnSource> {
+        let mut chars = source.chars();
+        let r#gen = chars.next()?.to_digit(10)? as u8;
+        let method = LearnMethod::from_code(chars.next()?)?;
+        Some(LearnSource {
+            r#gen,
+            method,
+            level: chars.as_str().parse().ok(),
+            learner: learner.to_string(),
+        })
+    }
+
+    /// "Gen 8 TM", "Gen 9 level-up (level 12)", "Gen 4 egg move as Togepi"
+    fn describe(&self, species: &str) -> String {
+        let mut s = format!("Gen {} {}", self.r#gen, self.method.label());
+        if let Some(level) = self.level {
+            s.push_str(&format!(" (level {})", level));
+        }
+        if self.learner != species {
+            s.push_str(&format!(" as {}", self.learner));
+        }
+        s
+    }
+}
+
+/// Whether a Pokémon can know a move in a generation, and how
+#[derive(Debug, Clone, Serialize)]
+pub struct LearnCheck {
+    pub species: String,
+    pub r#move: String,
+    pub r#gen: u8,
+    /// `None` when the learnset doesn't list the move but is only partial, so the answer is
+    /// unknown
+    pub learnable: Option<bool>,
+    /// `false` when the species can't be brought to `gen` games, so it learns nothing there
+    pub obtainable: bool,
+    /// Sources that count in `gen`, newest first
+    pub sources: Vec<LearnSource>,
+    /// The species whose learnset was used because `species` has none of its own
+    pub learnset_of: Option<String>,
+}
+
+impl LearnCheck {
+    pub fn to_readable_form(&self) -> String {
+        let mut s = match self.learnable {
+            Some(true) => format!(
+                "{} can learn {} in Gen {} by:\n",
+                self.species, self.r#move, self.r#gen
+            ),
+            Some(false) if !self.obtainable => format!(
+                "{} cannot learn {} in Gen {}: {} is not obtainable in Gen {} games\n",
+                self.species, self.r#move, self.r#gen, self.species, self.r#gen
+            ),
+            Some(false) => format!(
+                "{} cannot learn {} in Gen {}\n",
+                self.species, self.r#move, self.r#gen
+            ),
+            None => format!(
+                "Unknown whether {} can learn {} in Gen {}: the bundled learnset is partial \
+                 and doesn't list it\n",
+                self.species, self.r#move, self.r#gen
+            ),
+        };
+        for source in &self.sources {
+            s.push_str(&format!("  - {}\n", source.describe(&self.species)));
+        }
+        if let Some(ref learnset_of) = self.learnset_of {
+            s.push_str(&format!(
+                "(Using {}'s learnset, {} has none of its own)\n",
+                learnset_of, self.species
+            ));
+        }
+        s
+    }
+}
+
+/// Checks in the bundled learnsets whether a Pokémon can know a move in `gen`. A move missing
+/// from a partial learnset is reported as unknown, not unlearnable.
+pub fn can_learn(species: &str, r#move: &str, r#gen: u8) -> Result<LearnCheck> {
+    let dex = Dex::get();
+    let species = learnset_species(species, r#gen)?;
+    let Some(mv) = dex.get_move(r#move) else {
+        let suggestion = dex.suggest_move(r#move).map(|m| m.name.as_str());
+        bail!("Unknown move: {}{}", r#move, did_you_mean(suggestion));
+    };
+    if !mv.exists_in(r#gen) {
+        bail!("{} is not available in Gen {}", mv.name, r#gen);
+    }
+    let obtainable = dex.obtainable_in(species, r#gen);
+    let mut sources: Vec<LearnSource> = dex
+        .learners_of(species, &to_id(&mv.name))
+        .into_iter()
+        .filter(|(_, source)| obtainable && source_usable_in(source, r#gen))
+        .filter_map(|(learner, source)| LearnSource::parse(source, &learner.name))
+        .collect();
+    sources.sort_by_key(|source| std::cmp::Reverse(source.r#gen));
+    let learnable = if !sources.is_empty() {
+        Some(true)
+    } else if !obtainable || dex.is_complete() {
+        Some(false)
+    } else {
+        None
+    };
+    Ok(LearnCheck {
+        species: species.name.clone(),
+        r#move: mv.name.clone(),
+        r#gen,
+        learnable,
+        obtainable,
+        sources,
+        learnset_of: dex.learnset_fallback(species).map(|s| s.name.clone()),
+    })
+}
+
+/// Every move a Pokémon can know in a generation, grouped by how it is learned there. Moves
+/// it can only bring from an earlier generation are grouped by their newest source.
+#[derive(Debug, Clone, Serialize)]
+pub struct LearnsetInfo {
+    pub species: String,
+    pub r#gen: u8,
+    pub level_up: Vec<String>,
+    pub machine: Vec<String>,
+    pub tutor: Vec<String>,
+    pub egg: Vec<String>,
+    pub event: Vec<String>,
+    /// Dream World, Virtual Console and forme change moves
+    pub other: Vec<String>,
+    /// Whether the moves come from the bundled subset and may be missing some
+    pub partial: bool,
+    /// The species whose learnset was used because `species` has none of its own
+    pub learnset_of: Option<String>,
+}
+
+impl LearnsetInfo {
+    pub fn to_readable_form(&self) -> String {
+        let mut s = format!("Gen {} learnset of {}\n", self.r#gen, self.species);
+        if self.partial {
+            s.push_str("(Partial: only the moves in the bundled data are listed)\n");
+        }
+        if let Some(ref learnset_of) = self.learnset_of {
+            s.push_str(&format!(
+                "(Using {}'s learnset, {} has none of its own)\n",
+                learnset_of, self.species
+            ));
+        }
+        for (label, moves) in [
+            ("Level-up", &self.level_up),
+            ("TM", &self.machine),
+            ("Tutor", &self.tutor),
+            ("Egg moves", &self.egg),
+            ("Event", &self.event),
+            ("Other", &self.other),
+        ] {
+            if !moves.is_empty() {
+                s.push_str(&format!("{}: {}\n", label, moves.join(", ")));
+            }
+        }
+        s
+    }
+}
+
+/// A Pokémon's bundled learnset as of `gen`
+pub fn fetch_learnset(species: &str, r#gen: u8) -> Result<LearnsetInfo> {
+    let dex = Dex::get();
+    let species = learnset_species(species, r#gen)?;
+    let mut info = LearnsetInfo {
+        species: species.name.clone(),
+        r#gen,
+        level_up: Vec::new(),
+        machine: Vec::new(),
+        tutor: Vec::new(),
+        egg: Vec::new(),
+        event: Vec::new(),
+        other: Vec::new(),
+        partial: !dex.is_complete(),
+        learnset_of: dex.learnset_fallback(species).map(|s| s.name.clone()),
+    };
+    for mv in dex.learnable_moves(species, r#gen) {
+        let sources: Vec<LearnSource> = dex
+            .learners_of(species, &to_id(&mv.name))
+            .into_iter()
+            .filter(|(_, source)| source_usable_in(source, r#gen))
+            .filter_map(|(learner, source)| LearnSource::parse(source, &learner.name))
+            .collect();
+        let newest = sources.iter().map(|s| s.r#gen).max().unwrap_or(r#gen);
+        for method in sources
+            .iter()
+            .filter(|s| s.r#gen == newest)
+            .map(|s| s.method)
+        {
+            let group = match method {
+                LearnMethod::LevelUp => &mut info.level_up,
+                LearnMethod::Machine => &mut info.machine,
+                LearnMethod::Tutor => &mut info.tutor,
+                LearnMethod::Egg => &mut info.egg,
+                LearnMethod::Event => &mut info.event,
+                _ => &mut info.other,
+            };
+            if !group.contains(&mv.name) {
+                group.push(mv.name.clone());
+            }
+        }
+    }
+    Ok(info)
+}
+
+/// Weaknesses, resistances and STAB coverage of a Pokémon or a type combination
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeMatchups {
+    /// The Pokémon, or the types joined like "Water/Ground"
+    pub name: String,
+    pub types: Vec<String>,
+    pub r#gen: u8,
+    /// Multiplier taken from each attacking type
+    pub defense: BTreeMap<String, f32>,
+    /// Types that at least one of `types` hits super effectively
+    pub super_effective_against: Vec<String>,
+}
+
+impl TypeMatchups {
+    pub fn to_readable_form(&self) -> String {
+        let types = self.types.join("/");
+        let mut s = if self.name == types {
+            format!("{} in Gen {}\n", self.name, self.r#gen)
+        } else {
+            format!("{} ({}) in Gen {}\n", self.name, types, self.r#gen)
+        };
+        let mut defense: Vec<(&String, f32)> = self.defense.iter().map(|(t, m)| (t, *m)).collect();
+        defense.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let groups: [(&str, &dyn Fn(f32) -> bool); 3] = [
+            ("Weak to", &|m| m > 1.0),
+            ("Resists", &|m| m > 0.0 && m < 1.0),
+            ("Immune to", &|m| m == 0.0),
+        ];
+        for (label, keep) in groups {
+            let members: Vec<String> = defense
+                .iter()
+                .filter(|(_, m)| keep(*m))
+                .map(|(t, m)| format!("{} ({}x)", t, m))
+                .collect();
+            if !members.is_empty() {
+                s.push_str(&format!("{}: {}\n", label, members.join(", ")));
+            }
+        }
+        if !self.super_effective_against.is_empty() {
+            s.push_str(&format!(
+                "Super effective against: {}\n",
+                self.super_effective_against.join(", ")
+            ));
+        }
+        s
+    }
+}
+
+/// Type matchups of a Pokémon, or of types written like "Water/Ground" or "water ground"
+pub async fn type_matchups(name: &str, r#gen: u8) -> Result<TypeMatchups> {
+    let parts: Vec<&str> = name
+        .split(|c: char| c == '/' || c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    let as_types: Option<Vec<Type>> = parts.iter().map(|p| p.parse().ok()).collect();
+    let (name, types) = match as_types {
+        Some(types) if (1..=2).contains(&types.len()) => {
+            if let Some(missing) = types.iter().find(|t| !t.exists_in(r#gen)) {
+                bail!("The {} type does not exist in Gen {}", missing, r#gen);
+            }
+            let names: Vec<&str> = types.iter().map(|t| t.name()).collect();
+            (names.join("/"), types)
+        }
+        _ => {
+            let species = species_match(name, r#gen).await?;
+            let types = species
+                .types
+                .iter()
+                .filter_map(|t| t.parse().ok())
+                .collect();
+            (species.name, types)
+        }
+    };
+
+    let defense = Matchup::new(r#gen)
+        .chart(&types)
+        .into_iter()
+        .map(|(attack, multiplier)| (attack.to_string(), multiplier))
+        .collect();
+    let super_effective_against = Type::ALL
+        .into_iter()
+        .filter(|t| t.exists_in(r#gen))
+        .filter(|defend| {
+            types
+                .iter()
+                .any(|attack| type_effectiveness_in(r#gen, *attack, &[*defend]) > 1.0)
+        })
+        .map(|t| t.to_string())
+        .collect();
+    Ok(TypeMatchups {
+        name,
+        types: types.iter().map(|t| t.to_string()).collect(),
+        r#gen,
+        defense,
+        super_effective_against,
+    })
+}
+
+/// Two Pokémon side by side: their base stats, and how their STAB types hit each other
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    pub r#gen: u8,
+    pub pokemon: [SpeciesMatch; 2],
+    /// Multiplier of each of the first Pokémon's types against the second, then the other way
+    pub stab: [BTreeMap<String, f32>; 2],
+}
+
+impl Comparison {
+    /// A table with the higher of each stat starred, then the STAB matchups and who is faster
+    pub fn to_readable_form(&self) -> String {
+        let [first, second] = &self.pokemon;
+        let width = first.name.len().max(first.types.join("/").len()).max(12) + 2;
+        let mut s = format!(
+            "Gen {:<10}{:<width$}{}\n",
+            self.r#gen, first.name, second.name
+        );
+        let mut row = |label: &str, left: String, right: String| {
+            s.push_str(&format!("{:<14}{:<width$}{}\n", label, left, right));
+        };
+        row("Types", first.types.join("/"), second.types.join("/"));
+        row(
+            "Tier",
+            first.tier.clone().unwrap_or_default(),
+            second.tier.clone().unwrap_or_default(),
+        );
+        let keys = Stat::ALL
+            .map(StatKey::Stat)
+            .into_iter()
+            .chain([StatKey::Total]);
+        for key in keys {
+            let (a, b) = (key.of(&first.base_stats), key.of(&second.base_stats));
+            let star = |mine: u16, theirs: u16| {
+                if mine > theirs {
+                    format!("{}*", mine)
+                } else {
+                    mine.to_string()
+                }
+            };
+            row(&key.to_string(), star(a, b), star(b, a));
+        }
+
+        for pokemon in &self.pokemon {
+            s.push_str(&format!(
+                "{} abilities: {}\n",
+                pokemon.name,
+                pokemon.abilities.join(", ")
+            ));
+        }
+        for ([attacker, defender], stab) in
+            [[first, second], [second, first]].iter().zip(&self.stab)
+        {
+            let hits: Vec<String> = stab.iter().map(|(t, m)| format!("{} {}x", t, m)).collect();
+            s.push_str(&format!(
+                "{}'s STAB on {}: {}\n",
+                attacker.name,
+                defender.name,
+                hits.join(", ")
+            ));
+        }
+        let (a, b) = (first.base_stats.spe, second.base_stats.spe);
+        s.push_str(&match a.cmp(&b) {
+            Ordering::Greater => format!(
+                "{} outspeeds {} ({} vs {})\n",
+                first.name, second.name, a, b
+            ),
+            Ordering::Less => format!(
+                "{} outspeeds {} ({} vs {})\n",
+                second.name, first.name, b, a
+            ),
+            Ordering::Equal => format!("Speed tie at base {}\n", a),
+        });
+        s
+    }
+}
+
+/// Compares two Pokémon as they were in `gen`
+pub async fn compare(first: &str, second: &str, r#gen: u8) -> Result<Comparison> {
+    let pokemon = [
+        species_match(first, r#gen).await?,
+        species_match(second, r#gen).await?,
+    ];
+    let types =
+        |p: &SpeciesMatch| -> Vec<Type> { p.types.iter().filter_map(|t| t.parse().ok()).collect() };
+    let stab = |attacker: &SpeciesMatch, defender: &SpeciesMatch| {
+        let defending = types(defender);
+        types(attacker)
+            .into_iter()
+            .map(|t| (t.to_string(), type_effectiveness_in(r#gen, t, &defending)))
+            .collect()
+    };
+    Ok(Comparison {
+        r#gen,
+        stab: [
+            stab(&pokemon[0], &pokemon[1]),
+            stab(&pokemon[1], &pokemon[0]),
+        ],
+        pokemon,
+    })
+}
+
+/// Resolves a species, and checks it exists in `gen`
+fn species_in_gen(name: &str, r#gen: u8) -> Result<&'static Species> {
+    let dex = Dex::get();
+    let Some(species) = dex.resolve_species(name) else {
+        let suggestion = dex.suggest_species(name).map(|s| s.name.as_str());
+        bail!("Unknown Pokemon: {}{}", name, did_you_mean(suggestion));
+    };
+    if species.r#gen > r#gen {
+        bail!(
+            "{} does not exist in Gen {}; it was introduced in Gen {}",
+            species.name,
+            r#gen,
+            species.r#gen
+        );
+    }
+    Ok(species)
+}
+
+/// A Pokémon as it was in `gen`, from the bundled dex or, for species the bundled subset
+/// lacks, from PokeAPI. Names are never swapped for a similar species; a name PokeAPI doesn't
+/// know either fails with a "did you mean" hint.
+async fn species_match(name: &str, r#gen: u8) -> Result<SpeciesMatch> {
+    let dex = Dex::get();
+    if dex.resolve_species(name).is_some() || dex.is_complete() {
+        return Ok(SpeciesMatch::new(species_in_gen(name, r#gen)?, r#gen));
+    }
+    let suggestion = dex.suggest_species(name).map(|s| s.name.as_str());
+    let info = PokeApiClient::get().pokemon_in(name, r#gen).await;
+    Ok(SpeciesMatch::from_info(&not_bundled(
+        info, "Pokemon", name, suggestion,
+    )?))
+}
+
+/// Passes on a PokeAPI lookup for a name the bundled subset lacks. When PokeAPI fails too, the
+/// error keeps the bundled "did you mean" hint.
+fn not_bundled<T>(
+    result: Result<T>,
+    kind: &str,
+    name: &str,
+    suggestion: Option<&str>,
+) -> Result<T> {
+    match result {
+        Ok(value) => Ok(value),
+        Err(e) if suggestion.is_some() => Err(e.context(format!(
+            "Unknown {}: {}{}",
+            kind,
+            name,
+            did_you_mean(suggestion)
+        ))),
+        Err(e) => Err(e),
+    }
+}
+
+/// Resolves a species that exists in `gen` and has bundled learnset data
+fn learnset_species(name: &str, r#gen: u8) -> Result<&'static Species> {
+    let species = species_in_gen(name, r#gen)?;
+    if !Dex::get().has_learnset(species) {
+        bail!("No bundled learnset for {}", species.name);
+    }
+    Ok(species)
+}
+
+/// Describes what a move does beyond dealing damage, from its Showdown fields
+fn move_effects(mv: &Move) -> Vec<String> {
+    let mut effects = Vec::new();
+    let extra = &mv.extra;
+    let target = if mv.target == "self" {
+        "the user"
+    } else {
+        "the target"
+    };
+
+    if let Some(status) = &mv.status {
+        effects.push(format!("Inflicts {}", condition_name(status)));
+    }
+    if let Some(volatile) = extra.get("volatileStatus").and_then(Value::as_str) {
+        effects.push(format!("Applies {}", condition_name(volatile)));
+    }
+    let boosts: Vec<(&str, i64)> = mv
+        .boosts
+        .iter()
+        .map(|(s, n)| (s.as_str(), *n as i64))
+        .collect();
+    if let Some(text) = boost_text(&boosts, target) {
+        effects.push(capitalize(&text));
+    }
+    if let Some(own) = extra.get("self") {
+        if let Some(text) = boost_text(&value_boosts(own), "the user") {
+            effects.push(capitalize(&text));
+        }
+        if own.get("volatileStatus").and_then(Value::as_str) == Some("lockedmove") {
+            effects.push("Locks the user in for 2-3 turns, then confuses it".to_string());
+        }
+    }
+
+    let secondaries = extra
+        .get("secondaries")
+        .and_then(Value::as_array)
+        .cloned()
+        .or_else(|| extra.get("secondary").map(|s| vec![s.clone()]))
+        .unwrap_or_default();
+    for secondary in secondaries.iter().filter(|s| !s.is_null()) {
+        let chance = secondary
+            .get("chance")
+            .and_then(Value::as_u64)
+            .unwrap_or(100);
+        let condition = secondary
+            .get("status")
+            .or_else(|| secondary.get("volatileStatus"))
+            .and_then(Value::as_str);
+        let effect = if let Some(condition) = condition {
+            Some(format!("of {}", condition_name(condition)))
+        } else if let Some(text) = boost_text(&value_boosts(secondary), "the target") {
+            Some(format!("to {}", text))
+        } else {
+            secondary
+                .get("self")
+                .and_then(|own| boost_text(&value_boosts(own), "the user"))
+                .map(|text| format!("to {}", text))
+        };
+        if let Some(effect) = effect {
+            effects.push(format!("{}% chance {}", chance, effect));
+        }
+    }
+
+    if let Some(percent) = fraction_percent(extra.get("recoil")) {
+        effects.push(format!("Recoil: {}% of the damage dealt", percent));
+    }
+    if let Some(percent) = fraction_percent(extra.get("drain")) {
+        effects.push(format!(
+            "Heals the user by {}% of the damage dealt",
+            percent
+        ));
+    }
+    if let Some(percent) = fraction_percent(extra.get("heal")) {
+        effects.push(format!("Heals the user by {}% of its max HP", percent));
+    }
+    match extra.get("multihit") {
+        Some(Value::Array(range)) if range.len() == 2 => {
+            effects.push(format!("Hits {}-{} times", range[0], range[1]))
+        }
+        Some(hits) if hits.is_u64() => effects.push(format!("Hits {} times", hits)),
+        _ => {}
+    }
+    if extra.get("critRatio").and_then(Value::as_u64) >= Some(2) {
+        effects.push("High critical hit ratio".to_string());
+    }
+    if let Some(ohko) = &mv.ohko {
+        match ohko.as_str() {
+            Some(immune) => effects.push(format!("One-hit KO; {} types are immune", immune)),
+            None => effects.push("One-hit KO".to_string()),
+        }
+    }
+    if extra.get("damage").and_then(Value::as_str) == Some("level") {
+        effects.push("Deals damage equal to the user's level".to_string());
+    }
+    if extra
+        .get("overrideOffensivePokemon")
+        .and_then(Value::as_str)
+        == Some("target")
+    {
+        effects.push("Uses the target's Attack instead of the user's".to_string());
+    }
+    if let Some(stat) = extra.get("overrideDefensiveStat").and_then(Value::as_str) {
+        effects.push(format!("Hits the target's {} instead", stat_name(stat)));
+    }
+    if extra.contains_key("ignoreDefensive") {
+        effects.push("Ignores the target's stat changes".to_string());
+    }
+    if extra.contains_key("selfSwitch") {
+        effects.push("The user switches out".to_string());
+    }
+    if extra.contains_key("forceSwitch") {
+        effects.push("Forces the target to switch out".to_string());
+    }
+    if extra.contains_key("selfdestruct") {
+        effects.push("The user faints".to_string());
+    }
+    for key in ["sideCondition", "slotCondition", "weather", "pseudoWeather"] {
+        if let Some(condition) = extra.get(key).and_then(Value::as_str) {
+            effects.push(format!("Sets up {}", condition.to_lowercase()));
+        }
+    }
+    effects
+}
+
+/// "raises the user's Atk by 2", "lowers the target's Def and SpD by 1"
+fn boost_text(boosts: &[(&str, i64)], whose: &str) -> Option<String> {
+    let mut amounts: Vec<i64> = boosts.iter().map(|(_, n)| *n).collect();
+    amounts.sort_by_key(|n| std::cmp::Reverse(*n));
+    amounts.dedup();
+    let clauses: Vec<String> = amounts
+        .into_iter()
+        .map(|amount| {
+            let stats: Vec<String> = boosts
+                .iter()
+                .filter(|(_, n)| *n == amount)
+                .map(|(stat, _)| stat_name(stat))
+                .collect();
+            let verb = if amount > 0 { "raises" } else { "lowers" };
+            format!(
+                "{} {}'s {} by {}",
+                verb,
+                whose,
+                stats.join(" and "),
+                amount.abs()
+            )
+        })
+        .collect();
+    (!clauses.is_empty()).then(|| clauses.join(" and "))
+}
+
+fn value_boosts(value: &Value) -> Vec<(&str, i64)> {
+    value
+        .get("boosts")
+        .and_then(Value::as_object)
+        .map(|boosts| {
+            boosts
+                .iter()
+                .filter_map(|(stat, n)| Some((stat.as_str(), n.as_i64()?)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `[1, 2]` as 50
+fn fraction_percent(value: Option<&Value>) -> Option<u64> {
+    let fraction = value?.as_array()?;
+    let (numerator, denominator) = (fraction.first()?.as_u64()?, fraction.get(1)?.as_u64()?);
+    (denominator > 0).then(|| numerator * 100 / denominator)
+}
+
+fn stat_name(id: &str) -> String {
+    match id.parse::<Stat>() {
+        Ok(stat) => stat.short_name().to_string(),
+        Err(_) => capitalize(id),
+    }
+}
+
+fn condition_name(id: &str) -> String {
+    match id {
+        "brn" => "burn",
+        "par" => "paralysis",
+        "psn" => "poison",
+        "tox" => "bad poison",
+        "slp" => "sleep",
+        "frz" => "freeze",
+        "flinch" => "flinching",
+        "partiallytrapped" => "partial trapping",
+        other => other,
+    }
+    .to_string()
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -1,3 +1,5 @@
+use crate::inference::OpponentSets;
+
 #[derive(Clone, Debug)]
 pub struct BattleEvents {
     pub team: [Team; 2], // Two teams: [0] = p1, [1] = p2, Supports only singles for now
@@ -9,6 +11,7 @@ pub struct BattleEvents {
     pub battle_started: bool,
     pub is_previewing_team: bool,
     pub is_init_suggestions_generated: bool,
+    pub opponents: OpponentSets, // what the opponent's sets probably are, from revealed info
 }
 
 /// This only useful in team battles
@@ -63,6 +66,7 @@ impl BattleEvents {
             battle_started: false,
             is_previewing_team: false,
             is_init_suggestions_generated: false,
+            opponents: OpponentSets::new(),
         }
     }
 
@@ -73,6 +77,8 @@ impl BattleEvents {
         } else {
             self.add_turns(event);
         }
+        self.opponents
+            .observe_line(event, self.user_slot.as_deref());
     }
 
     /// Returns the current turn number (0 if not started)
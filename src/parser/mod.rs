@@ -1,2 +1,3 @@
 pub mod logs;
+mod render;
 pub mod team;
@@ -0,0 +1,244 @@
+use super::team::{EVs, Pokemon, Team};
+use crate::data::Dex;
+use crate::types::Type;
+
+const STAT_NAMES: [&str; 6] = ["HP", "Atk", "Def", "SpA", "SpD", "Spe"];
+
+/// Stat values with their labels, leaving out the ones equal to `default`
+fn spread(stats: &EVs, default: u16) -> Vec<(u16, &'static str)> {
+    [
+        stats.hp, stats.atk, stats.def, stats.spa, stats.spd, stats.spe,
+    ]
+    .into_iter()
+    .zip(STAT_NAMES)
+    .filter(|(value, _)| *value != default)
+    .collect()
+}
+
+fn join_spread(parts: &[(u16, &str)]) -> String {
+    parts
+        .iter()
+        .map(|(value, stat)| format!("{} {}", value, stat))
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// "Nickname (Species)" when the set has a nickname, otherwise the species
+fn title(pkmn: &Pokemon) -> String {
+    match pkmn.species {
+        Some(ref species) if *species != pkmn.name => format!("{} ({})", pkmn.name, species),
+        _ => pkmn.name.clone(),
+    }
+}
+
+fn species_types(pkmn: &Pokemon) -> Vec<Type> {
+    let name = pkmn.species.as_deref().unwrap_or(&pkmn.name);
+    Dex::get()
+        .get_species(name)
+        .map(|s| s.types.iter().filter_map(|t| t.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn move_type(move_name: &str) -> Option<Type> {
+    // "Hidden Power [Ice]" carries its type in the name
+    if let Some(start) = move_name.find('[')
+        && let Some(end) = move_name.find(']')
+    {
+        return move_name[start + 1..end].parse().ok();
+    }
+    Dex::get().get_move(move_name)?.r#type.parse().ok()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn type_class(r#type: Type) -> String {
+    format!("type-{}", r#type.name().to_lowercase())
+}
+
+impl Team {
+    /// Render the team as Markdown, one section per Pokémon, for pasting into team docs
+    pub fn to_markdown(&self, heading: &str) -> String {
+        let mut output = format!("# {}\n", heading);
+
+        for pkmn in &self.pokemon {
+            output.push_str(&format!("\n## {}", title(pkmn)));
+            if let Some(ref gender) = pkmn.gender {
+                output.push_str(&format!(" ({})", gender));
+            }
+            if let Some(ref item) = pkmn.item {
+                output.push_str(&format!(" @ {}", item));
+            }
+            output.push('\n');
+
+            let types: Vec<&str> = species_types(pkmn).iter().map(|t| t.name()).collect();
+            if !types.is_empty() {
+                output.push_str(&format!("*{}*\n", types.join(" / ")));
+            }
+            output.push('\n');
+
+            let mut details = Vec::new();
+            if let Some(ref ability) = pkmn.ability {
+                details.push(format!("**Ability:** {}", ability));
+            }
+            if let Some(level) = pkmn.level.filter(|l| *l != 100) {
+                details.push(format!("**Level:** {}", level));
+            }
+            if let Some(ref nature) = pkmn.nature {
+                details.push(format!("**Nature:** {}", nature));
+            }
+            let evs = spread(&pkmn.evs, 0);
+            if !evs.is_empty() {
+                details.push(format!("**EVs:** {}", join_spread(&evs)));
+            }
+            if let Some(ref ivs) = pkmn.ivs {
+                let ivs = spread(ivs, 31);
+                if !ivs.is_empty() {
+                    details.push(format!("**IVs:** {}", join_spread(&ivs)));
+                }
+            }
+            for detail in details {
+                output.push_str(&format!("- {}\n", detail));
+            }
+
+            if !pkmn.moves.is_empty() {
+                output.push_str("\n**Moves:**\n\n");
+                for mv in &pkmn.moves {
+                    output.push_str(&format!("1. {}\n", mv));
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Render the team as a standalone HTML page in the style of PokéPaste: the usual
+    /// Showdown text per Pokémon, with type-colored headers, move bullets and stat labels
+    pub fn to_html(&self, heading: &str) -> String {
+        let mut style = String::from(
+            "body { font-family: sans-serif; background: #f4f4f4; color: #222; }\n\
+             article { display: inline-block; vertical-align: top; width: 340px; margin: 8px; \
+             background: #fff; border-radius: 6px; overflow: hidden; }\n\
+             article h2 { margin: 0; padding: 6px 12px; color: #fff; \
+             text-shadow: 0 1px 2px #000; font-size: 1.1em; }\n\
+             pre { margin: 0; padding: 8px 12px; white-space: pre-wrap; }\n\
+             .attr { font-weight: bold; }\n\
+             .stat-hp { color: #FF5959; }\n\
+             .stat-atk { color: #F5AC78; }\n\
+             .stat-def { color: #FAE078; }\n\
+             .stat-spa { color: #9DB7F5; }\n\
+             .stat-spd { color: #A7DB8D; }\n\
+             .stat-spe { color: #FA92B2; }\n",
+        );
+        for r#type in Type::ALL {
+            style.push_str(&format!(
+                ".{} {{ color: {}; font-weight: bold; }}\n",
+                type_class(r#type),
+                r#type.color()
+            ));
+        }
+
+        let mut body = String::new();
+        for pkmn in &self.pokemon {
+            let types = species_types(pkmn);
+            let background = match types.as_slice() {
+                [] => "#888888".to_string(),
+                [single] => single.color().to_string(),
+                [first, second, ..] => {
+                    format!(
+                        "linear-gradient(90deg, {}, {})",
+                        first.color(),
+                        second.color()
+                    )
+                }
+            };
+            body.push_str(&format!(
+                "<article>\n<h2 style=\"background: {}\">{}</h2>\n<pre>",
+                background,
+                escape(&title(pkmn))
+            ));
+
+            match types.first() {
+                Some(t) => body.push_str(&format!(
+                    "<span class=\"{}\">{}</span>",
+                    type_class(*t),
+                    escape(&title(pkmn))
+                )),
+                None => body.push_str(&escape(&title(pkmn))),
+            }
+            if let Some(ref gender) = pkmn.gender {
+                body.push_str(&format!(" ({})", escape(gender)));
+            }
+            if let Some(ref item) = pkmn.item {
+                body.push_str(&format!(" @ {}", escape(item)));
+            }
+            body.push('\n');
+
+            if let Some(ref ability) = pkmn.ability {
+                body.push_str(&format!(
+                    "<span class=\"attr\">Ability:</span> {}\n",
+                    escape(ability)
+                ));
+            }
+            if let Some(level) = pkmn.level.filter(|l| *l != 100) {
+                body.push_str(&format!("<span class=\"attr\">Level:</span> {}\n", level));
+            }
+            let stats_html = |parts: &[(u16, &str)]| {
+                parts
+                    .iter()
+                    .map(|(value, stat)| {
+                        format!(
+                            "<span class=\"stat-{}\">{} {}</span>",
+                            stat.to_lowercase(),
+                            value,
+                            stat
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" / ")
+            };
+            let evs = spread(&pkmn.evs, 0);
+            if !evs.is_empty() {
+                body.push_str(&format!(
+                    "<span class=\"attr\">EVs:</span> {}\n",
+                    stats_html(&evs)
+                ));
+            }
+            if let Some(ref nature) = pkmn.nature {
+                body.push_str(&format!("{} Nature\n", escape(nature)));
+            }
+            if let Some(ref ivs) = pkmn.ivs {
+                let ivs = spread(ivs, 31);
+                if !ivs.is_empty() {
+                    body.push_str(&format!(
+                        "<span class=\"attr\">IVs:</span> {}\n",
+                        stats_html(&ivs)
+                    ));
+                }
+            }
+            for mv in &pkmn.moves {
+                match move_type(mv) {
+                    Some(t) => body.push_str(&format!(
+                        "<span class=\"{}\">-</span> {}\n",
+                        type_class(t),
+                        escape(mv)
+                    )),
+                    None => body.push_str(&format!("- {}\n", escape(mv))),
+                }
+            }
+            body.push_str("</pre>\n</article>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>\n{style}</style>\n</head>\n<body>\n<h1>{title}</h1>\n{body}</body>\n</html>\n",
+            title = escape(heading),
+            style = style,
+            body = body
+        )
+    }
+}
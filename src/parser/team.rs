@@ -1,7 +1,11 @@
+use crate::data::Dex;
+use crate::id::to_id;
 use anyhow::Result;
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EVs {
     pub hp: u16,
     pub atk: u16,
@@ -11,22 +15,33 @@ pub struct EVs {
     pub spe: u16,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Pokemon {
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub species: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub item: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ability: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub gender: Option<String>,
     pub evs: EVs,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ivs: Option<EVs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub shiny: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub level: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub happiness: Option<u8>,
     pub moves: Vec<String>,
 }
 
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Team {
     pub pokemon: Vec<Pokemon>,
 }
@@ -70,6 +85,46 @@ impl Team {
                 continue;
             }
 
+            // Parse IVs line (unlisted stats default to 31)
+            if line.starts_with("IVs:") {
+                if let Some(ref mut pkmn) = current_pokemon {
+                    pkmn.ivs = Some(Self::parse_ivs(line));
+                }
+                continue;
+            }
+
+            // Parse Level / Shiny / Happiness lines
+            if line.starts_with("Level:") {
+                if let Some(ref mut pkmn) = current_pokemon {
+                    pkmn.level = line.trim_start_matches("Level:").trim().parse().ok();
+                }
+                continue;
+            }
+
+            if line.starts_with("Shiny:") {
+                if let Some(ref mut pkmn) = current_pokemon {
+                    let value = line.trim_start_matches("Shiny:").trim();
+                    pkmn.shiny = Some(value.eq_ignore_ascii_case("yes"));
+                }
+                continue;
+            }
+
+            if line.starts_with("Happiness:") {
+                if let Some(ref mut pkmn) = current_pokemon {
+                    pkmn.happiness = line.trim_start_matches("Happiness:").trim().parse().ok();
+                }
+                continue;
+            }
+
+            // Lines we don't model yet, skip them instead of treating them as a new Pokemon
+            if line.starts_with("Tera Type:")
+                || line.starts_with("Hidden Power:")
+                || line.starts_with("Dynamax Level:")
+                || line.starts_with("Gigantamax:")
+            {
+                continue;
+            }
+
             // Parse Ability line
             if line.starts_with("Ability:") {
                 if let Some(ref mut pkmn) = current_pokemon {
@@ -148,6 +203,22 @@ impl Team {
                 output.push_str(&format!("Ability: {}\n", ability));
             }
 
+            if let Some(level) = pkmn.level
+                && level != 100
+            {
+                output.push_str(&format!("Level: {}\n", level));
+            }
+
+            if pkmn.shiny.unwrap_or(false) {
+                output.push_str("Shiny: Yes\n");
+            }
+
+            if let Some(happiness) = pkmn.happiness
+                && happiness != 255
+            {
+                output.push_str(&format!("Happiness: {}\n", happiness));
+            }
+
             // EVs line (only if any EVs are set)
             let ev_parts: Vec<String> = [
                 (pkmn.evs.hp, "HP"),
@@ -171,6 +242,26 @@ impl Team {
                 output.push_str(&format!("{} Nature\n", nature));
             }
 
+            // IVs line (only stats below 31)
+            if let Some(ref ivs) = pkmn.ivs {
+                let iv_parts: Vec<String> = [
+                    (ivs.hp, "HP"),
+                    (ivs.atk, "Atk"),
+                    (ivs.def, "Def"),
+                    (ivs.spa, "SpA"),
+                    (ivs.spd, "SpD"),
+                    (ivs.spe, "Spe"),
+                ]
+                .iter()
+                .filter(|(val, _)| *val != 31)
+                .map(|(val, stat)| format!("{} {}", val, stat))
+                .collect();
+
+                if !iv_parts.is_empty() {
+                    output.push_str(&format!("IVs: {}\n", iv_parts.join(" / ")));
+                }
+            }
+
             // Move lines
             for mv in &pkmn.moves {
                 output.push_str(&format!("- {}\n", mv));
@@ -299,14 +390,6 @@ impl Team {
         self.pokemon
             .iter()
             .map(|pkmn| {
-                // Normalize helper
-                let normalize = |s: &str| -> String {
-                    s.chars()
-                        .filter(|c| c.is_alphanumeric())
-                        .collect::<String>()
-                        .to_lowercase()
-                };
-
                 // EVs: blank for 0, keep all 6 values
                 let evs = [
                     pkmn.evs.hp,
@@ -351,15 +434,12 @@ impl Team {
                 // Compose fields
                 vec![
                     pkmn.name.clone(), // Nickname (usually empty or same as species)
-                    normalize(pkmn.species.as_ref().unwrap_or(&pkmn.name)), // Species
-                    pkmn.item.as_ref().map(|i| normalize(i)).unwrap_or_default(),
-                    pkmn.ability
-                        .as_ref()
-                        .map(|a| normalize(a))
-                        .unwrap_or_default(),
+                    to_id(pkmn.species.as_ref().unwrap_or(&pkmn.name)), // Species
+                    pkmn.item.as_deref().map(to_id).unwrap_or_default(),
+                    pkmn.ability.as_ref().map(|a| to_id(a)).unwrap_or_default(),
                     pkmn.moves
                         .iter()
-                        .map(|m| normalize(m))
+                        .map(|m| to_id(m))
                         .collect::<Vec<_>>()
                         .join(","),
                     pkmn.nature.clone().unwrap_or_default(),
@@ -393,6 +473,80 @@ impl Team {
         Ok(Self::deserialize_packed(&content))
     }
 
+    /// Serialize the team to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(input: &str) -> Result<Self> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    /// Serialize the team to TOML, one `[[pokemon]]` table per member
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+
+    pub fn from_toml(input: &str) -> Result<Self> {
+        Ok(toml::from_str(input)?)
+    }
+
+    /// Rewrites species, item, ability and move names to the dex's spelling when they name a
+    /// dex entry up to case, punctuation and forme order ("Alolan Muk" -> "Muk-Alola",
+    /// "leftovers" -> "Leftovers"). Names that match nothing are left unchanged with a "did
+    /// you mean" note instead, since the bundled dex is a subset and the closest entry may be
+    /// a different Pokémon. Returns one note per correction or suggestion.
+    pub fn correct_names(&mut self) -> Vec<String> {
+        let dex = Dex::get();
+        let mut notes = Vec::new();
+        // `Err` holds the closest dex entry for a name that matches none
+        let mut correct = |name: &mut String, known: Result<&str, Option<&str>>| match known {
+            Ok(known) if known != name => {
+                notes.push(format!("\"{}\" -> \"{}\"", name, known));
+                *name = known.to_string();
+            }
+            Ok(_) | Err(None) => {}
+            Err(Some(suggestion)) => notes.push(format!(
+                "\"{}\" is unknown, did you mean \"{}\"?",
+                name, suggestion
+            )),
+        };
+
+        for pkmn in &mut self.pokemon {
+            let species = pkmn.species.as_mut().unwrap_or(&mut pkmn.name);
+            let known = dex
+                .species_name(species)
+                .ok_or_else(|| dex.suggest_species(species).map(|s| s.name.as_str()));
+            correct(species, known);
+            if let Some(ref mut item) = pkmn.item {
+                let known = dex
+                    .get_item(item)
+                    .map(|i| i.name.as_str())
+                    .ok_or_else(|| dex.suggest_item(item).map(|i| i.name.as_str()));
+                correct(item, known);
+            }
+            if let Some(ref mut ability) = pkmn.ability {
+                let known = dex
+                    .get_ability(ability)
+                    .map(|a| a.name.as_str())
+                    .ok_or_else(|| dex.suggest_ability(ability).map(|a| a.name.as_str()));
+                correct(ability, known);
+            }
+            for move_name in &mut pkmn.moves {
+                // "Hidden Power [Ice]" keeps its type
+                if to_id(move_name).starts_with("hiddenpower") {
+                    continue;
+                }
+                let known = dex
+                    .get_move(move_name)
+                    .map(|m| m.name.as_str())
+                    .ok_or_else(|| dex.suggest_move(move_name).map(|m| m.name.as_str()));
+                correct(move_name, known);
+            }
+        }
+        notes
+    }
+
     /// Alias for deserialize (backwards compatibility)
     #[deprecated(note = "Use deserialize() instead")]
     pub async fn parse(input: &str) -> Self {
@@ -492,8 +646,15 @@ impl Team {
     }
 
     fn parse_evs(line: &str) -> EVs {
-        let mut evs = EVs::default();
-        let ev_str = line.trim_start_matches("EVs:").trim();
+        Self::parse_spread(line.trim_start_matches("EVs:"), EVs::default())
+    }
+
+    fn parse_ivs(line: &str) -> EVs {
+        Self::parse_spread(line.trim_start_matches("IVs:"), EVs::max_ivs())
+    }
+
+    fn parse_spread(ev_str: &str, mut evs: EVs) -> EVs {
+        let ev_str = ev_str.trim();
 
         for part in ev_str.split('/') {
             let part = part.trim();
@@ -1,6 +1,14 @@
-use anyhow::Result;
+use crate::data::{Dex, LATEST_GEN, Species, formes};
+use crate::stats::{BaseStats, Stat};
+use anyhow::{Context, Result, anyhow, bail};
 use colored::Colorize;
+use futures_util::future::{join, join_all};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PokemonInfo {
@@ -13,8 +21,27 @@ pub struct PokemonInfo {
     pub moves: Vec<PokemonMoveSlot>,
     pub stats: Vec<PokemonStat>,
     pub species: NamedAPIResource,
-    #[serde(rename = "past_abilities")]
-    pub r#gen: Vec<PastAbilityGen>,
+    /// Types before a change, each entry listing the last generation they applied to
+    #[serde(default)]
+    pub past_types: Vec<PastTypes>,
+    /// Ability slots before a change, each entry listing the last generation they applied to
+    #[serde(default)]
+    pub past_abilities: Vec<PastAbilities>,
+    /// Base stats before a change, each entry listing the last generation they applied to
+    #[serde(default)]
+    pub past_stats: Vec<PastStats>,
+    /// Generation the Pokémon was introduced in, from its species
+    #[serde(skip)]
+    pub generation: Option<NamedAPIResource>,
+    /// Generation the types, stats, abilities and moves are for; 0 when unknown
+    #[serde(skip)]
+    pub as_of_gen: u8,
+    /// Why the Pokémon can't be used in `as_of_gen` games even though it exists, e.g. "Past"
+    #[serde(skip)]
+    pub unavailable: Option<String>,
+    /// Whether the moves come from the bundled subset and may be missing some
+    #[serde(skip)]
+    pub moves_partial: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,11 +50,33 @@ pub struct PokemonSpecies {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct PastAbilityGen {
+pub struct PastTypes {
+    pub generation: NamedAPIResource,
+    pub types: Vec<PokemonTypeSlot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PastAbilities {
+    pub generation: NamedAPIResource,
+    pub abilities: Vec<PastAbilitySlot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PastStats {
     pub generation: NamedAPIResource,
+    /// Only the stats that changed
+    pub stats: Vec<PokemonStat>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+pub struct PastAbilitySlot {
+    pub is_hidden: bool,
+    pub slot: u8,
+    /// `None` when the slot was empty back then
+    pub ability: Option<NamedAPIResource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PokemonTypeSlot {
     pub r#type: NamedAPIResource,
 }
@@ -35,6 +84,8 @@ pub struct PokemonTypeSlot {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PokemonAbilitySlot {
     pub is_hidden: bool,
+    #[serde(default)]
+    pub slot: u8,
     pub ability: NamedAPIResource,
     #[serde(skip)]
     pub effect: Option<String>, // Added to store fetched effect
@@ -42,9 +93,60 @@ pub struct PokemonAbilitySlot {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AbilityDetails {
-    #[allow(dead_code)]
-    name: String,
+    pub name: String,
+    #[serde(default)]
+    pub names: Vec<LocalizedName>,
+    pub effect_entries: Vec<AbilityEffectEntry>,
+    #[serde(default)]
+    pub generation: Option<NamedAPIResource>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveDetails {
+    pub name: String,
+    #[serde(default)]
+    pub names: Vec<LocalizedName>,
+    pub r#type: NamedAPIResource,
+    /// "physical", "special" or "status"
+    pub damage_class: NamedAPIResource,
+    pub power: Option<u16>,
+    /// `None` for moves that never miss
+    pub accuracy: Option<u8>,
+    pub pp: Option<u8>,
+    #[serde(default)]
+    pub priority: i8,
+    pub target: NamedAPIResource,
+    /// Fills in "$effect_chance" in the effect text
+    pub effect_chance: Option<u8>,
+    #[serde(default)]
+    pub effect_entries: Vec<AbilityEffectEntry>,
+    pub generation: NamedAPIResource,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemDetails {
+    pub name: String,
+    #[serde(default)]
+    pub names: Vec<LocalizedName>,
+    #[serde(default)]
     pub effect_entries: Vec<AbilityEffectEntry>,
+    /// `None` when Fling fails with the item
+    pub fling_power: Option<u16>,
+    pub category: NamedAPIResource,
+    /// The generations the item appears in
+    #[serde(default)]
+    pub game_indices: Vec<GenerationGameIndex>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerationGameIndex {
+    pub generation: NamedAPIResource,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocalizedName {
+    pub name: String,
+    language: NamedAPIResource,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,6 +160,17 @@ pub struct AbilityEffectEntry {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PokemonMoveSlot {
     pub r#move: NamedAPIResource,
+    /// The games the move is learned in and how; empty for moves from the bundled data
+    #[serde(default)]
+    pub version_group_details: Vec<MoveVersionDetails>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveVersionDetails {
+    #[serde(default)]
+    pub level_learned_at: u8,
+    pub move_learn_method: NamedAPIResource,
+    pub version_group: NamedAPIResource,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -66,31 +179,196 @@ pub struct PokemonStat {
     pub stat: NamedAPIResource,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NamedAPIResource {
     pub name: String,
     url: String,
 }
 
 impl PokemonInfo {
+    /// Base stats in the shape expected by the stat calculator
+    pub fn base_stats(&self) -> BaseStats {
+        let mut base = BaseStats::default();
+        for entry in &self.stats {
+            let value = entry.base_stat.max(0) as u16;
+            match entry.stat.name.parse::<Stat>() {
+                Ok(Stat::HP) => base.hp = value,
+                Ok(Stat::Atk) => base.atk = value,
+                Ok(Stat::Def) => base.def = value,
+                Ok(Stat::SpA) => base.spa = value,
+                Ok(Stat::SpD) => base.spd = value,
+                Ok(Stat::Spe) => base.spe = value,
+                Err(_) => {}
+            }
+        }
+        base
+    }
+
+    /// Builds the same summary from the bundled data, as the species was in `gen`
+    pub fn from_dex(species: &Species, r#gen: u8) -> PokemonInfo {
+        let dex = Dex::get();
+        let species = species.in_gen(r#gen);
+        let abilities = if r#gen >= 3 {
+            species
+                .abilities
+                .iter()
+                // Hidden abilities arrived in Gen 5
+                .filter(|(slot, _)| *slot != "H" || r#gen >= 5)
+                .map(|(slot, name)| PokemonAbilitySlot {
+                    is_hidden: slot == "H",
+                    slot: match slot.as_str() {
+                        "0" => 1,
+                        "1" => 2,
+                        _ => 3,
+                    },
+                    ability: named(name),
+                    effect: dex
+                        .get_ability(name)
+                        .map(|a| a.short_desc.clone())
+                        .filter(|desc| !desc.is_empty()),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let stats = POKEAPI_STATS
+            .iter()
+            .zip(Stat::ALL)
+            .map(|(name, stat)| PokemonStat {
+                base_stat: species.base_stats.get(stat) as i32,
+                stat: named(name),
+            })
+            .collect();
+
+        PokemonInfo {
+            id: species.num,
+            name: species.name.clone(),
+            height: 0,
+            // PokeAPI weights are in hectograms
+            weight: (species.weightkg * 10.0).round() as i32,
+            types: species
+                .types
+                .iter()
+                .map(|t| PokemonTypeSlot {
+                    r#type: named(&t.to_lowercase()),
+                })
+                .collect(),
+            abilities,
+            moves: dex
+                .learnable_moves(&species, r#gen)
+                .into_iter()
+                .map(|m| PokemonMoveSlot {
+                    r#move: named(&m.name),
+                    version_group_details: Vec::new(),
+                })
+                .collect(),
+            stats,
+            species: named(species.base_species.as_deref().unwrap_or(&species.name)),
+            past_types: Vec::new(),
+            past_abilities: Vec::new(),
+            past_stats: Vec::new(),
+            generation: Some(named(&generation_name(species.r#gen))),
+            as_of_gen: r#gen,
+            unavailable: dex
+                .formats_entry(r#gen, &species.name)
+                .and_then(|entry| entry.is_nonstandard.clone()),
+            moves_partial: !dex.is_complete(),
+        }
+    }
+
+    /// Rewinds PokeAPI data to `gen` with its past types, abilities and base stats, keeping the
+    /// moves learned in the games up to `gen` that can still be known there
+    pub fn rewind(&mut self, r#gen: u8) {
+        // The earliest change after `gen` holds what applied in `gen`
+        let covering = |generation: &NamedAPIResource| {
+            generation_number(&generation.name).filter(|&last| last >= r#gen)
+        };
+        if let Some(past) = self
+            .past_types
+            .iter()
+            .filter_map(|p| Some((covering(&p.generation)?, p)))
+            .min_by_key(|(last, _)| *last)
+        {
+            self.types = past.1.types.clone();
+        }
+        if let Some((_, past)) = self
+            .past_abilities
+            .iter()
+            .filter_map(|p| Some((covering(&p.generation)?, p)))
+            .min_by_key(|(last, _)| *last)
+        {
+            for old in &past.abilities {
+                self.abilities.retain(|a| a.slot != old.slot);
+                if let Some(ability) = &old.ability {
+                    self.abilities.push(PokemonAbilitySlot {
+                        is_hidden: old.is_hidden,
+                        slot: old.slot,
+                        ability: ability.clone(),
+                        effect: None,
+                    });
+                }
+            }
+            self.abilities.sort_by_key(|a| a.slot);
+        }
+        if let Some((_, past)) = self
+            .past_stats
+            .iter()
+            .filter_map(|p| Some((covering(&p.generation)?, p)))
+            .min_by_key(|(last, _)| *last)
+        {
+            for old in &past.stats {
+                if let Some(stat) = self.stats.iter_mut().find(|s| s.stat.name == old.stat.name) {
+                    stat.base_stat = old.base_stat;
+                }
+            }
+        }
+        // Moves from Gen 1-2 don't transfer forward past the GB games, as in `source_usable_in`
+        let usable = |detail: &MoveVersionDetails| {
+            version_group_generation(&detail.version_group.name)
+                .is_some_and(|learned| learned <= r#gen && (r#gen <= 2 || learned >= 3))
+        };
+        self.moves.retain(|m| {
+            m.version_group_details.is_empty() || m.version_group_details.iter().any(usable)
+        });
+        match r#gen {
+            0..=2 => self.abilities.clear(),
+            3 | 4 => self.abilities.retain(|a| !a.is_hidden),
+            _ => {}
+        }
+        self.as_of_gen = r#gen;
+    }
+
     pub fn to_readable_form(&self) -> String {
         let mut s = String::new();
         s.push_str(&format!("Pokemon: {}\n", self.name.to_uppercase()));
         // Generation
         let generation_name = self
-            .r#gen
-            .first()
-            .map(|g| g.generation.name.to_uppercase())
+            .generation
+            .as_ref()
+            .map(|g| g.name.to_uppercase())
             .unwrap_or_else(|| "Unknown".to_string());
         s.push_str(&format!("Generation: {}\n", generation_name));
+        if self.as_of_gen > 0 {
+            s.push_str(&format!("Data as of: Gen {}\n", self.as_of_gen));
+        }
+        if let Some(reason) = &self.unavailable {
+            s.push_str(&format!(
+                "Availability: not obtainable in Gen {} games ({})\n",
+                self.as_of_gen, reason
+            ));
+        }
         // Types
         let types: Vec<String> = self.types.iter().map(|t| t.r#type.name.clone()).collect();
         s.push_str(&format!("Types:   {}\n", types.join(", ")));
-        // Height & Weight
-        s.push_str(&format!(
-            "Height: {} | Weight: {}\n\n",
-            self.height, self.weight
-        ));
+        // Height & Weight; the bundled data has no heights
+        if self.height > 0 {
+            s.push_str(&format!(
+                "Height: {} | Weight: {}\n\n",
+                self.height, self.weight
+            ));
+        } else {
+            s.push_str(&format!("Weight: {}\n\n", self.weight));
+        }
         // Stats
         s.push_str("Stats:\n");
         for stat in &self.stats {
@@ -114,154 +392,497 @@ impl PokemonInfo {
             }
         }
         // Moves
-        s.push_str(&format!("\nMoves: (Total: {})\n", self.moves.len()));
-        for mv in self.moves.iter().take(10) {
-            s.push_str(&format!("  - {}\n", mv.r#move.name));
-        }
-        if self.moves.len() > 10 {
-            s.push_str(&format!("  ... and {} more\n", self.moves.len() - 10));
+        let moves: Vec<&str> = self.moves.iter().map(|m| m.r#move.name.as_str()).collect();
+        s.push_str(&format!("\nMoves: (Total: {})\n", moves.len()));
+        if self.moves_partial {
+            s.push_str("(Partial: only the moves in the bundled data are listed)\n");
         }
+        s.push_str(&format!("  {}\n", moves.join(", ")));
         s
     }
 }
 
-pub async fn fetch_pokemon_info(pokemon_name: &str) -> Result<PokemonInfo> {
-    let url = format!(
-        "https://pokeapi.co/api/v2/pokemon/{}",
-        pokemon_name.to_lowercase()
-    );
-    let response = reqwest::get(&url).await?;
-
-    if response.status().is_success() {
-        let mut pokemon: PokemonInfo = response.json().await?;
-
-        // Fetch generation from species if past_abilities is empty
-        if pokemon.r#gen.is_empty() {
-            if let Ok(species_resp) = reqwest::get(&pokemon.species.url).await {
-                if species_resp.status().is_success() {
-                    if let Ok(species_data) = species_resp.json::<PokemonSpecies>().await {
-                        // Create a PastAbilityGen from species generation
-                        pokemon.r#gen.push(PastAbilityGen {
-                            generation: species_data.generation,
-                        });
-                    }
-                }
-            }
+/// Stat names as PokeAPI spells them, in `Stat::ALL` order
+const POKEAPI_STATS: [&str; 6] = [
+    "hp",
+    "attack",
+    "defense",
+    "special-attack",
+    "special-defense",
+    "speed",
+];
+
+fn named(name: &str) -> NamedAPIResource {
+    NamedAPIResource {
+        name: name.to_string(),
+        url: String::new(),
+    }
+}
+
+const NUMERALS: [&str; 9] = ["i", "ii", "iii", "iv", "v", "vi", "vii", "viii", "ix"];
+
+/// "generation-iv", as PokeAPI names generations
+fn generation_name(r#gen: u8) -> String {
+    let numeral = NUMERALS
+        .get(r#gen.saturating_sub(1) as usize)
+        .copied()
+        .unwrap_or("unknown");
+    format!("generation-{}", numeral)
+}
+
+/// The generation of a PokeAPI version group, such as 4 for "heartgold-soulsilver"
+fn version_group_generation(name: &str) -> Option<u8> {
+    Some(match name {
+        "red-blue" | "yellow" => 1,
+        "gold-silver" | "crystal" => 2,
+        "ruby-sapphire" | "emerald" | "firered-leafgreen" | "colosseum" | "xd" => 3,
+        "diamond-pearl" | "platinum" | "heartgold-soulsilver" => 4,
+        "black-white" | "black-2-white-2" => 5,
+        "x-y" | "omega-ruby-alpha-sapphire" => 6,
+        "sun-moon" | "ultra-sun-ultra-moon" | "lets-go-pikachu-lets-go-eevee" => 7,
+        "sword-shield"
+        | "the-isle-of-armor"
+        | "the-crown-tundra"
+        | "brilliant-diamond-and-shining-pearl"
+        | "legends-arceus" => 8,
+        "scarlet-violet" | "the-teal-mask" | "the-indigo-disk" => 9,
+        _ => return None,
+    })
+}
+
+/// 4 for "generation-iv"
+fn generation_number(name: &str) -> Option<u8> {
+    let numeral = name.strip_prefix("generation-")?;
+    let index = NUMERALS.iter().position(|n| *n == numeral)?;
+    Some(index as u8 + 1)
+}
+
+/// The English name, or the slug title-cased when there is none
+fn english_name(names: &[LocalizedName], slug: &str) -> String {
+    names
+        .iter()
+        .find(|n| n.language.name == "en")
+        .map(|n| n.name.clone())
+        .unwrap_or_else(|| formes::showdown_name(slug))
+}
+
+/// The English short effect
+fn english_effect(entries: &[AbilityEffectEntry]) -> Option<String> {
+    entries
+        .iter()
+        .find(|e| e.language.name == "en")
+        .map(|e| e.short_effect.clone())
+}
+
+impl MoveDetails {
+    pub fn english_name(&self) -> String {
+        english_name(&self.names, &self.name)
+    }
+
+    /// The English short effect with its chance filled in
+    pub fn effect(&self) -> Option<String> {
+        let chance = self
+            .effect_chance
+            .map(|c| c.to_string())
+            .unwrap_or_default();
+        english_effect(&self.effect_entries).map(|e| e.replace("$effect_chance", &chance))
+    }
+
+    /// Generation the move was introduced in
+    pub fn introduced(&self) -> Option<u8> {
+        generation_number(&self.generation.name)
+    }
+}
+
+impl ItemDetails {
+    pub fn english_name(&self) -> String {
+        english_name(&self.names, &self.name)
+    }
+
+    pub fn effect(&self) -> Option<String> {
+        english_effect(&self.effect_entries)
+    }
+
+    /// The first generation the item appears in
+    pub fn introduced(&self) -> Option<u8> {
+        self.game_indices
+            .iter()
+            .filter_map(|g| generation_number(&g.generation.name))
+            .min()
+    }
+}
+
+impl AbilityDetails {
+    pub fn english_name(&self) -> String {
+        english_name(&self.names, &self.name)
+    }
+
+    pub fn effect(&self) -> Option<String> {
+        english_effect(&self.effect_entries)
+    }
+
+    /// Generation the ability was introduced in
+    pub fn introduced(&self) -> Option<u8> {
+        generation_number(&self.generation.as_ref()?.name)
+    }
+}
+
+/// Environment variable overriding the PokeAPI base URL, e.g. to point at a local mock server
+pub const POKEAPI_URL_ENV: &str = "POKEBRAINS_POKEAPI_URL";
+/// Environment variable naming the directory PokeAPI responses are cached in
+pub const CACHE_DIR_ENV: &str = "POKEBRAINS_CACHE_DIR";
+pub const DEFAULT_BASE_URL: &str = "https://pokeapi.co/api/v2";
+
+/// HTTP client for PokeAPI with an on-disk response cache and retries. Set the public fields
+/// after `new` to change the defaults.
+pub struct PokeApiClient {
+    http: reqwest::Client,
+    pub base_url: String,
+    /// Responses are stored here as JSON files; `None` disables the cache
+    pub cache_dir: Option<PathBuf>,
+    /// How long a cached response stays fresh
+    pub ttl: Duration,
+    /// Extra attempts after a network error, 429 or 5xx
+    pub retries: u32,
+    /// Wait before the first retry, doubled for each one after
+    pub backoff: Duration,
+}
+
+impl PokeApiClient {
+    pub fn new(base_url: &str) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        PokeApiClient {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            cache_dir: None,
+            ttl: Duration::from_secs(7 * 24 * 60 * 60),
+            retries: 3,
+            backoff: Duration::from_millis(250),
         }
+    }
 
-        // Fetch ability details for each ability
-        for ability_slot in &mut pokemon.abilities {
-            let ability_url = format!(
-                "https://pokeapi.co/api/v2/ability/{}",
-                ability_slot.ability.name
+    /// The shared client: `POKEBRAINS_POKEAPI_URL` or pokeapi.co, caching under
+    /// `POKEBRAINS_CACHE_DIR` or the system temp directory
+    pub fn get() -> &'static PokeApiClient {
+        static CLIENT: OnceLock<PokeApiClient> = OnceLock::new();
+        CLIENT.get_or_init(|| {
+            let base_url =
+                std::env::var(POKEAPI_URL_ENV).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+            let mut client = PokeApiClient::new(&base_url);
+            client.cache_dir = Some(
+                std::env::var(CACHE_DIR_ENV)
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| std::env::temp_dir().join("pokebrains-pokeapi")),
             );
-            let response_ability = reqwest::get(&ability_url).await;
-            if let Ok(resp) = response_ability
-                && resp.status().is_success()
-                && let Ok(ability_details) = resp.json::<AbilityDetails>().await
+            client
+        })
+    }
+
+    /// A Pokémon as it is now, with its generation and ability effects filled in
+    pub async fn pokemon(&self, name: &str) -> Result<PokemonInfo> {
+        self.pokemon_in(name, LATEST_GEN).await
+    }
+
+    /// A Pokémon as it was in `gen`, failing if it hadn't been introduced yet. The species and
+    /// ability requests run concurrently.
+    pub async fn pokemon_in(&self, name: &str, r#gen: u8) -> Result<PokemonInfo> {
+        let mut pokemon: PokemonInfo = self
+            .get_json(&format!("pokemon/{}", formes::pokeapi_slug(name)))
+            .await
+            .with_context(|| format!("Failed to fetch data for {}", name))?;
+        // The slug loses spaces and punctuation ("great-tusk"), so the dex spells it when it can
+        pokemon.name = match Dex::get().resolve_species(&pokemon.name) {
+            Some(species) => species.name.clone(),
+            None => formes::showdown_name(&pokemon.name),
+        };
+        pokemon.rewind(r#gen);
+
+        let species = self.get_json::<PokemonSpecies>(&pokemon.species.url);
+        let ability_paths: Vec<String> = pokemon
+            .abilities
+            .iter()
+            .map(|slot| format!("ability/{}", slot.ability.name))
+            .collect();
+        let abilities = join_all(
+            ability_paths
+                .iter()
+                .map(|path| self.get_json::<AbilityDetails>(path)),
+        );
+        let (species, abilities) = join(species, abilities).await;
+
+        if let Ok(species) = species {
+            if let Some(introduced) = generation_number(&species.generation.name)
+                && introduced > r#gen
             {
-                // Find the English effect entry
-                if let Some(entry) = ability_details
-                    .effect_entries
-                    .iter()
-                    .find(|e| e.language.name == "en")
-                {
-                    ability_slot.effect = Some(entry.short_effect.clone());
-                }
+                bail!(
+                    "{} does not exist in Gen {}; it was introduced in Gen {}",
+                    pokemon.name,
+                    r#gen,
+                    introduced
+                );
             }
+            pokemon.generation = Some(species.generation);
+        }
+        for (slot, details) in pokemon.abilities.iter_mut().zip(abilities) {
+            slot.effect = details.ok().and_then(|details| details.effect());
         }
         Ok(pokemon)
-    } else {
-        Err(anyhow::anyhow!(
-            "Failed to fetch data for {}: {}",
-            pokemon_name,
-            response.status()
-        ))
     }
-}
 
-pub async fn pretty_display(pokemon_name: &str) -> Result<()> {
-    let data = fetch_pokemon_info(pokemon_name).await;
-    match data {
-        Ok(info) => {
-            println!();
-            println!(
-                "{} {}",
-                "Pokemon:".cyan().bold(),
-                info.name.to_uppercase().bright_white().bold()
-            );
+    /// A move by its Showdown name, for moves the bundled data lacks
+    pub async fn move_details(&self, name: &str) -> Result<MoveDetails> {
+        self.get_json(&format!("move/{}", formes::slugify(name)))
+            .await
+            .with_context(|| format!("Failed to fetch data for {}", name))
+    }
 
-            println!(
-                "{} {}",
-                "Generation:".cyan().bold(),
-                info.r#gen
-                    .first()
-                    .map(|g| g.generation.name.to_uppercase().yellow().to_string())
-                    .unwrap_or_else(|| "Unknown".yellow().to_string())
-            );
+    /// An item by its Showdown name, for items the bundled data lacks
+    pub async fn item_details(&self, name: &str) -> Result<ItemDetails> {
+        self.get_json(&format!("item/{}", formes::slugify(name)))
+            .await
+            .with_context(|| format!("Failed to fetch data for {}", name))
+    }
 
-            print!("{}", "Types:   ".cyan().bold());
-            let types: Vec<String> = info
-                .types
-                .iter()
-                .map(|t| t.r#type.name.yellow().to_string())
-                .collect();
-            println!("{}", types.join(", "));
+    /// An ability by its Showdown name, for abilities the bundled data lacks
+    pub async fn ability_details(&self, name: &str) -> Result<AbilityDetails> {
+        self.get_json(&format!("ability/{}", formes::slugify(name)))
+            .await
+            .with_context(|| format!("Failed to fetch data for {}", name))
+    }
 
-            println!(
-                "{} {} | {} {}",
-                "Height:".cyan().bold(),
-                info.height.to_string().green(),
-                "Weight:".cyan().bold(),
-                info.weight.to_string().green()
-            );
+    /// Fetches `path` (relative to the base URL, or a full URL from a previous response),
+    /// answering from the cache while it is fresh
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+        };
+        let cache_file = self
+            .cache_dir
+            .as_ref()
+            .map(|dir| dir.join(cache_name(&url)));
 
-            println!();
-            println!("{}", "Stats:".cyan().bold());
-            for stat in info.stats {
-                println!(
-                    "  {:>15} : {}",
-                    stat.stat.name.white(),
-                    stat.base_stat.to_string().green().bold()
-                );
+        if let Some(file) = &cache_file
+            && let Some(text) = self.read_fresh(file)
+            && let Ok(value) = serde_json::from_str(&text)
+        {
+            return Ok(value);
+        }
+
+        let text = self.fetch(&url).await?;
+        let value = serde_json::from_str(&text)
+            .with_context(|| format!("Unexpected response from {}", url))?;
+        if let Some(file) = &cache_file {
+            let written = file
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::write(file, &text));
+            if let Err(e) = written {
+                eprintln!("Failed to cache {}: {}", url, e);
             }
+        }
+        Ok(value)
+    }
 
-            println!();
-            println!("{}", "Abilities:".cyan().bold());
-            for ability in info.abilities {
-                let hidden_text = if ability.is_hidden {
-                    "(Hidden)".red().italic()
-                } else {
-                    "".normal()
-                };
-                if let Some(effect) = &ability.effect {
-                    println!(
-                        " {:>15} - {} {}",
-                        ability.ability.name.white(),
-                        effect.bright_blue(),
-                        hidden_text
-                    );
-                } else {
-                    println!("  {:>15} {}", ability.ability.name.white(), hidden_text);
+    fn read_fresh(&self, file: &Path) -> Option<String> {
+        let age = std::fs::metadata(file)
+            .ok()?
+            .modified()
+            .ok()?
+            .elapsed()
+            .ok()?;
+        if age < self.ttl {
+            std::fs::read_to_string(file).ok()
+        } else {
+            None
+        }
+    }
+
+    /// GETs `url`, retrying with exponential backoff on network errors, 429 and 5xx
+    async fn fetch(&self, url: &str) -> Result<String> {
+        let mut attempt = 0;
+        loop {
+            let error = match self.http.get(url).send().await {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(response.text().await?);
                 }
+                Ok(response) => {
+                    let status = response.status();
+                    let error = anyhow!("{} returned {}", url, status);
+                    if !(status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS) {
+                        return Err(error);
+                    }
+                    error
+                }
+                Err(e) => anyhow::Error::from(e).context(format!("Request to {} failed", url)),
+            };
+            if attempt >= self.retries {
+                return Err(error);
             }
+            tokio::time::sleep(self.backoff * 2u32.pow(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
 
-            println!();
-            println!(
-                "{} (Total: {})",
-                "Moves:".cyan().bold(),
-                info.moves.len().to_string().green()
-            );
-            for mv in info.moves.iter().take(4) {
-                println!("  - {}", mv.r#move.name.white());
-            }
-            if info.moves.len() > 4 {
-                println!("  ... and {} more", info.moves.len() - 4);
+/// Cache file name for a URL: "pokemon/16" under pokeapi.co is "pokeapi.co_api_v2_pokemon_16.json"
+fn cache_name(url: &str) -> String {
+    let path = url.split("://").last().unwrap_or(url);
+    let name: String = path
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
             }
+        })
+        .collect();
+    format!("{}.json", name)
+}
+
+/// Details of a Pokémon as it is now, from the bundled data when it has the species and from
+/// PokeAPI otherwise
+pub async fn fetch_pokemon_info(pokemon_name: &str) -> Result<PokemonInfo> {
+    fetch_pokemon_info_in(pokemon_name, LATEST_GEN).await
+}
+
+/// Details of a Pokémon as it was in `gen`: past types, stats and abilities, and an error if it
+/// didn't exist yet
+pub async fn fetch_pokemon_info_in(pokemon_name: &str, r#gen: u8) -> Result<PokemonInfo> {
+    // Only exact and forme spellings are looked up: the bundle is a subset, and a species it
+    // lacks (Latias) must reach the API rather than its closest bundled neighbour (Latios)
+    if let Some(species) = Dex::get().resolve_species(pokemon_name) {
+        if species.r#gen > r#gen {
+            bail!(
+                "{} does not exist in Gen {}; it was introduced in Gen {}",
+                species.name,
+                r#gen,
+                species.r#gen
+            );
         }
-        Err(e) => {
-            eprintln!("{} {}", "Error fetching data:".red().bold(), e);
-        }
+        return Ok(PokemonInfo::from_dex(species, r#gen));
+    }
+    PokeApiClient::get().pokemon_in(pokemon_name, r#gen).await
+}
+
+/// Prints a Pokémon's details in color, or the error fetching them
+pub async fn pretty_display(pokemon_name: &str) -> Result<()> {
+    match fetch_pokemon_info(pokemon_name).await {
+        Ok(info) => print_pokemon(&info),
+        Err(e) => eprintln!("{} {}", "Error fetching data:".red().bold(), e),
     }
     Ok(())
 }
+
+/// Prints fetched details in color
+pub fn print_pokemon(info: &PokemonInfo) {
+    println!();
+    println!(
+        "{} {}",
+        "Pokemon:".cyan().bold(),
+        info.name.to_uppercase().bright_white().bold()
+    );
+
+    println!(
+        "{} {}",
+        "Generation:".cyan().bold(),
+        info.generation
+            .as_ref()
+            .map(|g| g.name.to_uppercase().yellow().to_string())
+            .unwrap_or_else(|| "Unknown".yellow().to_string())
+    );
+    if info.as_of_gen > 0 && info.as_of_gen < LATEST_GEN {
+        println!(
+            "{} {}",
+            "Data as of:".cyan().bold(),
+            format!("Gen {}", info.as_of_gen).yellow()
+        );
+    }
+    if let Some(reason) = &info.unavailable {
+        println!(
+            "{} {}",
+            "Availability:".cyan().bold(),
+            format!(
+                "not obtainable in Gen {} games ({})",
+                info.as_of_gen, reason
+            )
+            .red()
+        );
+    }
+
+    print!("{}", "Types:   ".cyan().bold());
+    let types: Vec<String> = info
+        .types
+        .iter()
+        .map(|t| t.r#type.name.yellow().to_string())
+        .collect();
+    println!("{}", types.join(", "));
+
+    if info.height > 0 {
+        print!(
+            "{} {} | ",
+            "Height:".cyan().bold(),
+            info.height.to_string().green()
+        );
+    }
+    println!(
+        "{} {}",
+        "Weight:".cyan().bold(),
+        info.weight.to_string().green()
+    );
+
+    println!();
+    println!("{}", "Stats:".cyan().bold());
+    for stat in &info.stats {
+        println!(
+            "  {:>15} : {}",
+            stat.stat.name.white(),
+            stat.base_stat.to_string().green().bold()
+        );
+    }
+
+    println!();
+    println!("{}", "Abilities:".cyan().bold());
+    for ability in &info.abilities {
+        let hidden_text = if ability.is_hidden {
+            "(Hidden)".red().italic()
+        } else {
+            "".normal()
+        };
+        if let Some(effect) = &ability.effect {
+            println!(
+                " {:>15} - {} {}",
+                ability.ability.name.white(),
+                effect.bright_blue(),
+                hidden_text
+            );
+        } else {
+            println!("  {:>15} {}", ability.ability.name.white(), hidden_text);
+        }
+    }
+
+    println!();
+    println!(
+        "{} (Total: {})",
+        "Moves:".cyan().bold(),
+        info.moves.len().to_string().green()
+    );
+    if info.moves_partial {
+        println!(
+            "  {}",
+            "(Partial: only the moves in the bundled data are listed)".yellow()
+        );
+    }
+    for mv in info.moves.iter().take(4) {
+        println!("  - {}", mv.r#move.name.white());
+    }
+    if info.moves.len() > 4 {
+        println!("  ... and {} more", info.moves.len() - 4);
+    }
+}
@@ -0,0 +1,431 @@
+//! Searching the bundled dex for Pokémon by type, ability, base stats, learnable moves, format
+//! legality and tier, for finding teammates.
+
+use crate::data::{Dex, LATEST_GEN, Species};
+use crate::format::Format;
+use crate::id::{did_you_mean, to_id};
+use crate::pokeapi::PokemonInfo;
+use crate::stats::{BaseStats, Stat};
+use crate::types::Type;
+use anyhow::{Result, bail};
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// A base stat, or the base stat total
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatKey {
+    Stat(Stat),
+    Total,
+}
+
+impl StatKey {
+    pub fn of(&self, stats: &BaseStats) -> u16 {
+        match self {
+            StatKey::Stat(stat) => stats.get(*stat),
+            StatKey::Total => stats.total(),
+        }
+    }
+}
+
+impl fmt::Display for StatKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatKey::Stat(stat) => write!(f, "{}", stat),
+            StatKey::Total => write!(f, "BST"),
+        }
+    }
+}
+
+impl FromStr for StatKey {
+    type Err = anyhow::Error;
+
+    /// Stat names as [`Stat`] reads them, plus "bst" or "total"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "bst" | "total" => Ok(StatKey::Total),
+            other => Ok(StatKey::Stat(other.parse()?)),
+        }
+    }
+}
+
+/// Filters for [`search`]; every given filter has to match
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpeciesQuery {
+    /// Generation to search in; the format's when one is given, otherwise the latest
+    pub r#gen: Option<u8>,
+    pub types: Vec<Type>,
+    pub abilities: Vec<String>,
+    /// Moves that all have to be learnable
+    pub moves: Vec<String>,
+    /// Inclusive lower bounds on base stats
+    pub min_stats: Vec<(StatKey, u16)>,
+    /// Inclusive upper bounds on base stats
+    pub max_stats: Vec<(StatKey, u16)>,
+    /// Format id such as "gen6ou" the Pokémon has to be allowed in
+    pub format: Option<String>,
+    /// Tiers such as "OU" or "Uber"; any of them matches
+    pub tiers: Vec<String>,
+    /// Highest first; by name when unset
+    pub sort: Option<StatKey>,
+    pub limit: Option<usize>,
+}
+
+impl SpeciesQuery {
+    /// Reads a query like "steel gen6 ou spe>=90 u-turn sort:atk": type names, "gen6",
+    /// format ids ("gen6ou"), tiers, stat bounds ("spe >= 90", "bst<500"), move and ability
+    /// names (up to two words, like "Close Combat"), and "move:", "ability:", "tier:",
+    /// "format:", "sort:" and "limit:" prefixes
+    pub fn parse(query: &str) -> Result<SpeciesQuery> {
+        const FILLER: [&str; 14] = [
+            "type", "types", "in", "with", "base", "that", "learn", "learns", "and", "has",
+            "pokemon", "pokémon", "the", "of",
+        ];
+        let dex = Dex::get();
+        let words: Vec<&str> = query.split_whitespace().collect();
+        let mut parsed = SpeciesQuery::default();
+        let mut i = 0;
+
+        while i < words.len() {
+            let word = words[i];
+            let lower = word.to_lowercase();
+            // "spe>=90" or "spe >= 90"
+            let spaced = words.get(i..i + 3).map(|w| w.concat());
+            if let Some(bound) = parse_bound(word) {
+                parsed.push_bound(bound);
+            } else if let Some(bound) = spaced.as_deref().and_then(parse_bound) {
+                parsed.push_bound(bound);
+                i += 2;
+            } else if let Some((key, value)) = lower.split_once(':') {
+                match key {
+                    "move" => parsed.moves.push(value.to_string()),
+                    "ability" => parsed.abilities.push(value.to_string()),
+                    "tier" => parsed.tiers.push(tier_name(value)),
+                    "format" => parsed.format = Some(to_id(value)),
+                    "sort" => parsed.sort = Some(value.parse()?),
+                    "limit" => parsed.limit = Some(value.parse()?),
+                    _ => bail!("Unknown filter: {}", word),
+                }
+            } else if i + 1 < words.len()
+                && let Some(name) = move_or_ability(dex, &format!("{} {}", word, words[i + 1]))
+            {
+                parsed.push_name(name);
+                i += 1;
+            } else if let Ok(r#type) = word.parse::<Type>() {
+                parsed.types.push(r#type);
+            } else if let Some(r#gen) = lower
+                .strip_prefix("gen")
+                .and_then(|rest| rest.parse::<u8>().ok())
+            {
+                parsed.r#gen = Some(r#gen.clamp(1, LATEST_GEN));
+            } else if lower.starts_with("gen") {
+                parsed.format = Some(to_id(word));
+            } else if TIERS.contains(&to_id(word).as_str()) {
+                parsed.tiers.push(tier_name(word));
+            } else if let Some(name) = move_or_ability(dex, word) {
+                parsed.push_name(name);
+            } else if !FILLER.contains(&lower.as_str()) {
+                if dex.is_complete() {
+                    bail!("Don't know how to search for '{}'", word);
+                }
+                bail!(
+                    "Don't know how to search for '{}': it isn't a type, tier or stat, nor part \
+                     of a move or ability in the offline data, which has only some of them",
+                    word
+                );
+            }
+            i += 1;
+        }
+        Ok(parsed)
+    }
+
+    fn push_bound(&mut self, (key, min, max): (StatKey, Option<u16>, Option<u16>)) {
+        if let Some(min) = min {
+            self.min_stats.push((key, min));
+        }
+        if let Some(max) = max {
+            self.max_stats.push((key, max));
+        }
+    }
+
+    fn push_name(&mut self, name: Name) {
+        match name {
+            Name::Move(name) => self.moves.push(name),
+            Name::Ability(name) => self.abilities.push(name),
+        }
+    }
+}
+
+/// A Pokémon found by [`search`], as it is in the searched generation
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeciesMatch {
+    pub name: String,
+    pub types: Vec<String>,
+    pub abilities: Vec<String>,
+    pub base_stats: BaseStats,
+    pub tier: Option<String>,
+}
+
+impl SpeciesMatch {
+    /// A species as it was in `gen`, with the abilities it could have then
+    pub fn new(species: &Species, r#gen: u8) -> SpeciesMatch {
+        let current = species.in_gen(r#gen);
+        let tier = Dex::get()
+            .formats_entry(r#gen, &species.name)
+            .and_then(|e| e.tier.as_deref())
+            .map(|t| t.trim_start_matches('(').trim_end_matches(')').to_string());
+        SpeciesMatch {
+            abilities: current
+                .abilities
+                .iter()
+                // Abilities start in Gen 3, hidden abilities in Gen 5
+                .filter(|(slot, _)| r#gen >= 5 || (r#gen >= 3 && slot.as_str() != "H"))
+                .map(|(_, ability)| ability.clone())
+                .collect(),
+            name: current.name,
+            types: current.types,
+            base_stats: current.base_stats,
+            tier,
+        }
+    }
+
+    /// A Pokémon fetched from PokeAPI, already rewound to the generation it was requested for.
+    /// PokeAPI has no tiers.
+    pub fn from_info(info: &PokemonInfo) -> SpeciesMatch {
+        let dex = Dex::get();
+        SpeciesMatch {
+            name: info.name.clone(),
+            types: info
+                .types
+                .iter()
+                .filter_map(|t| t.r#type.name.parse::<Type>().ok())
+                .map(|t| t.to_string())
+                .collect(),
+            abilities: info
+                .abilities
+                .iter()
+                .map(|a| match dex.get_ability(&a.ability.name) {
+                    Some(ability) => ability.name.clone(),
+                    // "magic-guard" -> "Magic Guard"
+                    None => a
+                        .ability
+                        .name
+                        .split('-')
+                        .map(capitalize)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                })
+                .collect(),
+            base_stats: info.base_stats(),
+            tier: None,
+        }
+    }
+}
+
+impl fmt::Display for SpeciesMatch {
+    /// "Excadrill (Ground/Steel) OU - 110/135/60/50/65/88 (508) - Sand Rush, Sand Force"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = &self.base_stats;
+        write!(f, "{} ({})", self.name, self.types.join("/"))?;
+        if let Some(tier) = &self.tier {
+            write!(f, " {}", tier)?;
+        }
+        write!(
+            f,
+            " - {}/{}/{}/{}/{}/{} ({})",
+            stats.hp,
+            stats.atk,
+            stats.def,
+            stats.spa,
+            stats.spd,
+            stats.spe,
+            stats.total()
+        )?;
+        if !self.abilities.is_empty() {
+            write!(f, " - {}", self.abilities.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Pokémon in the bundled dex matching every filter of `query`. Pokémon that can't be
+/// obtained in the searched generation are left out.
+pub fn search(query: &SpeciesQuery) -> Result<Vec<SpeciesMatch>> {
+    let dex = Dex::get();
+    let format = query.format.as_deref().map(Format::from_id);
+    let r#gen = match &format {
+        Some(format) => format.r#gen,
+        None => query.r#gen.unwrap_or(LATEST_GEN),
+    };
+
+    let mut moves = Vec::new();
+    for name in &query.moves {
+        let Some(mv) = dex.get_move(name) else {
+            let suggestion = dex.suggest_move(name).map(|m| m.name.as_str());
+            if dex.is_complete() {
+                bail!("Unknown move: {}{}", name, did_you_mean(suggestion));
+            }
+            bail!(
+                "Can't search by {}: it isn't a move in the offline data{}",
+                name,
+                did_you_mean(suggestion)
+            );
+        };
+        moves.push(to_id(&mv.name));
+    }
+    let mut abilities = Vec::new();
+    for name in &query.abilities {
+        let Some(ability) = dex.get_ability(name) else {
+            let suggestion = dex.suggest_ability(name).map(|a| a.name.as_str());
+            if dex.is_complete() {
+                bail!("Unknown ability: {}{}", name, did_you_mean(suggestion));
+            }
+            bail!(
+                "Can't search by {}: it isn't an ability in the offline data{}",
+                name,
+                did_you_mean(suggestion)
+            );
+        };
+        abilities.push(ability.name.clone());
+    }
+
+    let mut matches: Vec<SpeciesMatch> = dex
+        .all_species()
+        // Battle-only formes like Aegislash-Blade can't be brought to a team
+        .filter(|species| species.r#gen <= r#gen && species.battle_only.is_none())
+        .filter_map(|species| {
+            let entry = dex.formats_entry(r#gen, &species.name);
+            if entry.is_some_and(|e| e.is_nonstandard.is_some()) {
+                return None;
+            }
+            let summary = SpeciesMatch::new(species, r#gen);
+            let tier = summary.tier.as_deref();
+            if tier == Some("Illegal") {
+                return None;
+            }
+
+            let types_ok = query
+                .types
+                .iter()
+                .all(|t| summary.types.iter().any(|own| own == t.name()));
+            let abilities_ok = abilities.iter().all(|a| summary.abilities.contains(a));
+            let stats = &summary.base_stats;
+            let stats_ok = query.min_stats.iter().all(|(k, min)| k.of(stats) >= *min)
+                && query.max_stats.iter().all(|(k, max)| k.of(stats) <= *max);
+            let moves_ok = moves.iter().all(|id| dex.can_learn(species, id, r#gen));
+            let tiers_ok = query.tiers.is_empty()
+                || tier.is_some_and(|t| query.tiers.iter().any(|q| to_id(q) == to_id(t)));
+            let format_ok = format
+                .as_ref()
+                .is_none_or(|f| allowed_in(f, species, tier, &summary.abilities));
+
+            (types_ok && abilities_ok && stats_ok && moves_ok && tiers_ok && format_ok)
+                .then_some(summary)
+        })
+        .collect();
+
+    matches.sort_by(|a, b| a.name.cmp(&b.name));
+    if let Some(key) = query.sort {
+        matches.sort_by_key(|m| std::cmp::Reverse(key.of(&m.base_stats)));
+    }
+    if let Some(limit) = query.limit {
+        matches.truncate(limit);
+    }
+    Ok(matches)
+}
+
+/// One match per line, or a note that nothing matched. Without a complete dex, a note says the
+/// search only covered the offline data, and that learnable moves come from partial learnsets.
+pub fn report(query: &SpeciesQuery, matches: &[SpeciesMatch]) -> String {
+    let dex = Dex::get();
+    let mut s = if matches.is_empty() {
+        "No Pokémon match the search\n".to_string()
+    } else {
+        matches.iter().map(|m| format!("{}\n", m)).collect()
+    };
+    if !dex.is_complete() {
+        s.push_str(&format!(
+            "(Limited to the {} Pokémon in the offline data",
+            dex.all_species().count()
+        ));
+        if !query.moves.is_empty() {
+            s.push_str(
+                ", whose learnsets are partial, so Pokémon that learn the moves may be missing",
+            );
+        }
+        s.push_str(")\n");
+    }
+    s
+}
+
+/// Showdown tier ids, without the parentheses of "(OU)"
+const TIERS: [&str; 18] = [
+    "ag", "uber", "ubers", "ou", "uubl", "uu", "rubl", "ru", "nubl", "nu", "publ", "pu", "zu",
+    "nfe", "lc", "dou", "duber", "duu",
+];
+
+/// "ubers" -> "Uber", "ou" -> "OU"
+fn tier_name(tier: &str) -> String {
+    match to_id(tier).as_str() {
+        "uber" | "ubers" => "Uber".to_string(),
+        "duber" => "DUber".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+/// "spe>=90", "atk≥100", "bst<500" or "spe=110", as a stat with inclusive bounds
+fn parse_bound(text: &str) -> Option<(StatKey, Option<u16>, Option<u16>)> {
+    let text = text.replace('≥', ">=").replace('≤', "<=");
+    let (stat, op, value) = [">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|op| text.split_once(op).map(|(s, v)| (s, op, v)))?;
+    let key: StatKey = stat.parse().ok()?;
+    let value: u16 = value.trim().parse().ok()?;
+    Some(match op {
+        ">=" => (key, Some(value), None),
+        ">" => (key, Some(value.saturating_add(1)), None),
+        "<=" => (key, None, Some(value)),
+        "<" => (key, None, Some(value.checked_sub(1)?)),
+        _ => (key, Some(value), Some(value)),
+    })
+}
+
+enum Name {
+    Move(String),
+    Ability(String),
+}
+
+/// An exact move or ability name, such as "U-turn" or "Sand Rush"
+fn move_or_ability(dex: &Dex, name: &str) -> Option<Name> {
+    if let Some(mv) = dex.get_move(name) {
+        Some(Name::Move(mv.name.clone()))
+    } else {
+        dex.get_ability(name)
+            .map(|ability| Name::Ability(ability.name.clone()))
+    }
+}
+
+/// Whether a format allows a species: neither its tier nor its name is banned, and it has an
+/// ability that isn't
+fn allowed_in(
+    format: &Format,
+    species: &Species,
+    tier: Option<&str>,
+    abilities: &[String],
+) -> bool {
+    let unbanned = format
+        .unbanlist
+        .iter()
+        .any(|u| to_id(u) == to_id(&species.name));
+    let tier_ok = tier.is_none_or(|t| !format.is_banned(t) || unbanned);
+    let abilities_ok = abilities.is_empty() || abilities.iter().any(|a| !format.is_banned(a));
+    tier_ok && !format.is_banned(&species.name) && abilities_ok
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,264 @@
+//! Common competitive sets per format, used to fill in what we don't know about an
+//! opponent's Pokémon.
+//!
+//! Files follow the shape of Smogon's sets dumps (`<format>.json`, e.g. `gen9ou.json`):
+//! species name -> set name -> set, where any field may list several options and each move
+//! slot is either a move or a list of alternatives. A few formats are bundled; a directory of
+//! full dumps can be pointed to with `POKEBRAINS_SETS_DIR`.
+
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::{EVs, Pokemon};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Environment variable pointing at a directory of `<format>.json` set dumps
+pub const SETS_DIR_ENV: &str = "POKEBRAINS_SETS_DIR";
+
+const BUNDLED: [(&str, &str); 2] = [
+    ("gen5ou", include_str!("../data/sets/gen5ou.json")),
+    ("gen9ou", include_str!("../data/sets/gen9ou.json")),
+];
+
+/// Dump fields are either a single value or a list of options
+fn one_or_many<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match Option::<OneOrMany<T>>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(OneOrMany::One(value)) => vec![value],
+        Some(OneOrMany::Many(values)) => values,
+    })
+}
+
+/// A set as it appears in the dump, with every listed option kept in order
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SmogonSet {
+    /// Set name, e.g. "Bulky Spinner"
+    #[serde(skip)]
+    pub name: String,
+    #[serde(skip)]
+    pub species: String,
+    /// One entry per move slot, each with its alternatives
+    #[serde(default, deserialize_with = "move_slots")]
+    pub moves: Vec<Vec<String>>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub ability: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub item: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub nature: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub evs: Vec<BTreeMap<String, u16>>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub ivs: Vec<BTreeMap<String, u16>>,
+    #[serde(default, rename = "teratypes", deserialize_with = "one_or_many")]
+    pub tera_types: Vec<String>,
+    pub level: Option<u8>,
+}
+
+/// A JSON object with its key order kept, since dumps list the most common sets first
+struct Ordered<T>(Vec<(String, T)>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Ordered<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct Visitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> serde::de::Visitor<'de> for Visitor<T> {
+            type Value = Ordered<T>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Ordered(entries))
+            }
+        }
+
+        deserializer.deserialize_map(Visitor(std::marker::PhantomData))
+    }
+}
+
+fn move_slots<'de, D>(deserializer: D) -> std::result::Result<Vec<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Slot(#[serde(deserialize_with = "one_or_many")] Vec<String>);
+
+    let slots: Vec<Slot> = Vec::deserialize(deserializer)?;
+    Ok(slots.into_iter().map(|slot| slot.0).collect())
+}
+
+/// "Hidden Power Ice" in dumps is "Hidden Power [Ice]" in Showdown text
+fn showdown_move_name(name: &str) -> String {
+    match name.strip_prefix("Hidden Power ") {
+        Some(r#type) if !r#type.starts_with('[') => format!("Hidden Power [{}]", r#type),
+        _ => name.to_string(),
+    }
+}
+
+fn stats(values: &BTreeMap<String, u16>, default: u16) -> EVs {
+    let get = |stat: &str| values.get(stat).copied().unwrap_or(default);
+    EVs {
+        hp: get("hp"),
+        atk: get("atk"),
+        def: get("def"),
+        spa: get("spa"),
+        spd: get("spd"),
+        spe: get("spe"),
+    }
+}
+
+impl SmogonSet {
+    /// The set with the first listed option everywhere
+    pub fn to_pokemon(&self) -> Pokemon {
+        Pokemon {
+            name: self.species.clone(),
+            species: Some(self.species.clone()),
+            item: self.item.first().cloned(),
+            ability: self.ability.first().cloned(),
+            nature: self.nature.first().cloned(),
+            evs: self.evs.first().map(|e| stats(e, 0)).unwrap_or_default(),
+            ivs: self.ivs.first().map(|i| stats(i, 31)),
+            level: self.level,
+            moves: self
+                .moves
+                .iter()
+                .filter_map(|slot| slot.first())
+                .map(|m| showdown_move_name(m))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Every move the set may run, across all slots
+    pub fn possible_moves(&self) -> impl Iterator<Item = &str> {
+        self.moves.iter().flatten().map(String::as_str)
+    }
+}
+
+type FormatSets = HashMap<String, Vec<SmogonSet>>;
+
+/// Sets by format id, then by species id
+#[derive(Debug, Default)]
+pub struct SetsDex {
+    formats: HashMap<String, FormatSets>,
+}
+
+impl SetsDex {
+    /// The shared database: the bundled formats, with files from `POKEBRAINS_SETS_DIR` added
+    /// on top when it is set
+    pub fn get() -> &'static SetsDex {
+        static SETS: OnceLock<SetsDex> = OnceLock::new();
+        SETS.get_or_init(|| {
+            let mut sets = SetsDex::bundled();
+            if let Ok(dir) = std::env::var(SETS_DIR_ENV)
+                && let Err(e) = sets.load_dir(Path::new(&dir))
+            {
+                eprintln!("Using bundled sets only: {:#}", e);
+            }
+            sets
+        })
+    }
+
+    pub fn bundled() -> SetsDex {
+        let mut sets = SetsDex::default();
+        for (format, json) in BUNDLED {
+            sets.add_json(format, json).expect("bundled sets are valid");
+        }
+        sets
+    }
+
+    /// Adds every `<format>.json` in `dir`, replacing formats already loaded. Nothing is added
+    /// unless every file parses.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<()> {
+        let mut loaded = SetsDex::default();
+        let files =
+            std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for file in files {
+            let path = file?.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let Some(format) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            loaded
+                .add_json(format, &text)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+        }
+        self.formats.extend(loaded.formats);
+        Ok(())
+    }
+
+    /// Adds the sets of one format from a dump's JSON text
+    pub fn add_json(&mut self, format: &str, json: &str) -> Result<()> {
+        let dump: Ordered<Ordered<SmogonSet>> = serde_json::from_str(json)?;
+        let mut by_species = FormatSets::new();
+        for (species, Ordered(sets)) in dump.0 {
+            let entry: &mut Vec<SmogonSet> = by_species.entry(to_id(&species)).or_default();
+            for (name, mut set) in sets {
+                set.name = name;
+                set.species = species.clone();
+                entry.push(set);
+            }
+        }
+        self.formats.insert(to_id(format), by_species);
+        Ok(())
+    }
+
+    /// Format ids with sets, sorted
+    pub fn formats(&self) -> Vec<&str> {
+        let mut formats: Vec<&str> = self.formats.keys().map(String::as_str).collect();
+        formats.sort();
+        formats
+    }
+
+    /// Candidate sets for a species in a format, most common first. A forme without sets of its own, like
+    /// Charizard-Mega-X, gets the sets of its base species.
+    pub fn sets(&self, format: &str, species: &str) -> &[SmogonSet] {
+        let Some(by_species) = self.formats.get(&to_id(format)) else {
+            return &[];
+        };
+        if let Some(sets) = by_species.get(&to_id(species)) {
+            return sets;
+        }
+        Dex::get()
+            .get_species(species)
+            .and_then(|s| s.base_species.as_deref())
+            .and_then(|base| by_species.get(&to_id(base)))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// The candidate sets converted to team members
+    pub fn candidates(&self, format: &str, species: &str) -> Vec<Pokemon> {
+        self.sets(format, species)
+            .iter()
+            .map(SmogonSet::to_pokemon)
+            .collect()
+    }
+}
@@ -0,0 +1,472 @@
+use crate::parser::team::{EVs, Pokemon};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stat {
+    HP,
+    Atk,
+    Def,
+    SpA,
+    SpD,
+    Spe,
+}
+
+impl Stat {
+    pub const ALL: [Stat; 6] = [
+        Stat::HP,
+        Stat::Atk,
+        Stat::Def,
+        Stat::SpA,
+        Stat::SpD,
+        Stat::Spe,
+    ];
+
+    /// Short name as used in Showdown team text (e.g. "SpA")
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Stat::HP => "HP",
+            Stat::Atk => "Atk",
+            Stat::Def => "Def",
+            Stat::SpA => "SpA",
+            Stat::SpD => "SpD",
+            Stat::Spe => "Spe",
+        }
+    }
+}
+
+impl fmt::Display for Stat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.short_name())
+    }
+}
+
+impl FromStr for Stat {
+    type Err = anyhow::Error;
+
+    /// Accepts Showdown short names, battle log ids ("spa") and PokeAPI names ("special-attack")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "hp" => Ok(Stat::HP),
+            "atk" | "attack" => Ok(Stat::Atk),
+            "def" | "defense" => Ok(Stat::Def),
+            "spa" | "special-attack" | "spatk" => Ok(Stat::SpA),
+            "spd" | "special-defense" | "spdef" => Ok(Stat::SpD),
+            "spe" | "speed" => Ok(Stat::Spe),
+            other => Err(anyhow::anyhow!("Unknown stat: {}", other)),
+        }
+    }
+}
+
+impl EVs {
+    pub fn get(&self, stat: Stat) -> u16 {
+        match stat {
+            Stat::HP => self.hp,
+            Stat::Atk => self.atk,
+            Stat::Def => self.def,
+            Stat::SpA => self.spa,
+            Stat::SpD => self.spd,
+            Stat::Spe => self.spe,
+        }
+    }
+
+    pub fn set(&mut self, stat: Stat, value: u16) {
+        match stat {
+            Stat::HP => self.hp = value,
+            Stat::Atk => self.atk = value,
+            Stat::Def => self.def = value,
+            Stat::SpA => self.spa = value,
+            Stat::SpD => self.spd = value,
+            Stat::Spe => self.spe = value,
+        }
+    }
+
+    pub fn total(&self) -> u16 {
+        Stat::ALL.iter().map(|s| self.get(*s)).sum()
+    }
+
+    /// Showdown's default IV spread (31 in every stat)
+    pub fn max_ivs() -> Self {
+        EVs {
+            hp: 31,
+            atk: 31,
+            def: 31,
+            spa: 31,
+            spd: 31,
+            spe: 31,
+        }
+    }
+}
+
+/// Species base stats. In Gen 1 the Special stat is stored in `spa` (and mirrored in `spd`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaseStats {
+    pub hp: u16,
+    pub atk: u16,
+    pub def: u16,
+    pub spa: u16,
+    pub spd: u16,
+    pub spe: u16,
+}
+
+impl BaseStats {
+    pub fn new(hp: u16, atk: u16, def: u16, spa: u16, spd: u16, spe: u16) -> Self {
+        BaseStats {
+            hp,
+            atk,
+            def,
+            spa,
+            spd,
+            spe,
+        }
+    }
+
+    pub fn get(&self, stat: Stat) -> u16 {
+        match stat {
+            Stat::HP => self.hp,
+            Stat::Atk => self.atk,
+            Stat::Def => self.def,
+            Stat::SpA => self.spa,
+            Stat::SpD => self.spd,
+            Stat::Spe => self.spe,
+        }
+    }
+
+    pub fn total(&self) -> u16 {
+        Stat::ALL.iter().map(|s| self.get(*s)).sum()
+    }
+}
+
+/// Final in-battle stats of a Pokémon
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hp: u16,
+    pub atk: u16,
+    pub def: u16,
+    pub spa: u16,
+    pub spd: u16,
+    pub spe: u16,
+}
+
+impl Stats {
+    pub fn get(&self, stat: Stat) -> u16 {
+        match stat {
+            Stat::HP => self.hp,
+            Stat::Atk => self.atk,
+            Stat::Def => self.def,
+            Stat::SpA => self.spa,
+            Stat::SpD => self.spd,
+            Stat::Spe => self.spe,
+        }
+    }
+
+    fn set(&mut self, stat: Stat, value: u16) {
+        match stat {
+            Stat::HP => self.hp = value,
+            Stat::Atk => self.atk = value,
+            Stat::Def => self.def = value,
+            Stat::SpA => self.spa = value,
+            Stat::SpD => self.spd = value,
+            Stat::Spe => self.spe = value,
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} HP / {} Atk / {} Def / {} SpA / {} SpD / {} Spe",
+            self.hp, self.atk, self.def, self.spa, self.spd, self.spe
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Nature {
+    Hardy,
+    Lonely,
+    Brave,
+    Adamant,
+    Naughty,
+    Bold,
+    Docile,
+    Relaxed,
+    Impish,
+    Lax,
+    Timid,
+    Hasty,
+    Serious,
+    Jolly,
+    Naive,
+    Modest,
+    Mild,
+    Quiet,
+    Bashful,
+    Rash,
+    Calm,
+    Gentle,
+    Sassy,
+    Careful,
+    Quirky,
+}
+
+impl Nature {
+    pub const ALL: [Nature; 25] = [
+        Nature::Hardy,
+        Nature::Lonely,
+        Nature::Brave,
+        Nature::Adamant,
+        Nature::Naughty,
+        Nature::Bold,
+        Nature::Docile,
+        Nature::Relaxed,
+        Nature::Impish,
+        Nature::Lax,
+        Nature::Timid,
+        Nature::Hasty,
+        Nature::Serious,
+        Nature::Jolly,
+        Nature::Naive,
+        Nature::Modest,
+        Nature::Mild,
+        Nature::Quiet,
+        Nature::Bashful,
+        Nature::Rash,
+        Nature::Calm,
+        Nature::Gentle,
+        Nature::Sassy,
+        Nature::Careful,
+        Nature::Quirky,
+    ];
+
+    /// (boosted stat, lowered stat), or None for neutral natures
+    pub fn modifiers(&self) -> Option<(Stat, Stat)> {
+        use Stat::*;
+        match self {
+            Nature::Lonely => Some((Atk, Def)),
+            Nature::Brave => Some((Atk, Spe)),
+            Nature::Adamant => Some((Atk, SpA)),
+            Nature::Naughty => Some((Atk, SpD)),
+            Nature::Bold => Some((Def, Atk)),
+            Nature::Relaxed => Some((Def, Spe)),
+            Nature::Impish => Some((Def, SpA)),
+            Nature::Lax => Some((Def, SpD)),
+            Nature::Timid => Some((Spe, Atk)),
+            Nature::Hasty => Some((Spe, Def)),
+            Nature::Jolly => Some((Spe, SpA)),
+            Nature::Naive => Some((Spe, SpD)),
+            Nature::Modest => Some((SpA, Atk)),
+            Nature::Mild => Some((SpA, Def)),
+            Nature::Quiet => Some((SpA, Spe)),
+            Nature::Rash => Some((SpA, SpD)),
+            Nature::Calm => Some((SpD, Atk)),
+            Nature::Gentle => Some((SpD, Def)),
+            Nature::Sassy => Some((SpD, Spe)),
+            Nature::Careful => Some((SpD, SpA)),
+            Nature::Hardy | Nature::Docile | Nature::Serious | Nature::Bashful | Nature::Quirky => {
+                None
+            }
+        }
+    }
+
+    pub fn plus(&self) -> Option<Stat> {
+        self.modifiers().map(|(plus, _)| plus)
+    }
+
+    pub fn minus(&self) -> Option<Stat> {
+        self.modifiers().map(|(_, minus)| minus)
+    }
+
+    /// Nature multiplier for a stat in percent (110, 100 or 90)
+    pub fn percent(&self, stat: Stat) -> u32 {
+        match self.modifiers() {
+            Some((plus, _)) if plus == stat => 110,
+            Some((_, minus)) if minus == stat => 90,
+            _ => 100,
+        }
+    }
+}
+
+impl fmt::Display for Nature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Nature {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wanted = s.trim().trim_end_matches("Nature").trim();
+        Nature::ALL
+            .iter()
+            .find(|n| n.to_string().eq_ignore_ascii_case(wanted))
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unknown nature: {}", s))
+    }
+}
+
+/// Gen 3+ stat formula
+pub fn calc_stat(
+    stat: Stat,
+    base: u16,
+    iv: u16,
+    ev: u16,
+    level: u8,
+    nature: Option<Nature>,
+) -> u16 {
+    let level = level as u32;
+    let core = (2 * base as u32 + iv as u32 + ev as u32 / 4) * level / 100;
+
+    if stat == Stat::HP {
+        // Shedinja is the only species with base 1 HP and is always fixed at 1
+        if base == 1 {
+            return 1;
+        }
+        return (core + level + 10) as u16;
+    }
+
+    let percent = nature.map(|n| n.percent(stat)).unwrap_or(100);
+    ((core + 5) * percent / 100) as u16
+}
+
+/// Gen 1-2 stat formula using DVs (0-15) and stat experience (0-65535)
+pub fn calc_stat_gb(stat: Stat, base: u16, dv: u8, stat_exp: u16, level: u8) -> u16 {
+    let level = level as u32;
+    let exp_bonus = (stat_exp as f64).sqrt().ceil() as u32 / 4;
+    let core = ((base as u32 + dv.min(15) as u32) * 2 + exp_bonus) * level / 100;
+
+    if stat == Stat::HP {
+        (core + level + 10) as u16
+    } else {
+        (core + 5) as u16
+    }
+}
+
+/// In Gen 1-2 the HP DV is derived from the low bit of the other DVs
+pub fn hp_dv(atk: u8, def: u8, spe: u8, spc: u8) -> u8 {
+    ((atk & 1) << 3) | ((def & 1) << 2) | ((spe & 1) << 1) | (spc & 1)
+}
+
+/// Showdown stores Gen 1-2 DVs as even IVs (DV * 2)
+pub fn dv_from_iv(iv: u16) -> u8 {
+    (iv.min(31) / 2) as u8
+}
+
+/// Inverse of Showdown's EV representation of stat experience (252 EVs ≈ max stat exp)
+pub fn stat_exp_from_ev(ev: u16) -> u16 {
+    (ev as u32 * ev as u32).min(65535) as u16
+}
+
+/// Computes all six final stats for a generation
+pub fn calc_stats(
+    r#gen: u8,
+    base: &BaseStats,
+    evs: &EVs,
+    ivs: &EVs,
+    level: u8,
+    nature: Option<Nature>,
+) -> Stats {
+    let mut stats = Stats::default();
+
+    if r#gen <= 2 {
+        // Gen 1 has a single Special stat, Gen 2 split it but kept a single Special DV
+        let spc_dv = dv_from_iv(ivs.spa);
+        let dvs = [
+            dv_from_iv(ivs.atk),
+            dv_from_iv(ivs.def),
+            dv_from_iv(ivs.spe),
+            spc_dv,
+        ];
+        for stat in Stat::ALL {
+            let dv = match stat {
+                Stat::HP => hp_dv(dvs[0], dvs[1], dvs[2], dvs[3]),
+                Stat::Atk => dvs[0],
+                Stat::Def => dvs[1],
+                Stat::Spe => dvs[2],
+                Stat::SpA | Stat::SpD => spc_dv,
+            };
+            let (base_value, ev) = match stat {
+                Stat::SpD if r#gen == 1 => (base.spa, evs.spa),
+                _ => (base.get(stat), evs.get(stat)),
+            };
+            stats.set(
+                stat,
+                calc_stat_gb(stat, base_value, dv, stat_exp_from_ev(ev), level),
+            );
+        }
+        return stats;
+    }
+
+    for stat in Stat::ALL {
+        stats.set(
+            stat,
+            calc_stat(
+                stat,
+                base.get(stat),
+                ivs.get(stat),
+                evs.get(stat),
+                level,
+                nature,
+            ),
+        );
+    }
+    stats
+}
+
+/// Stat stage multiplier as a fraction (numerator, denominator)
+pub fn stage_multiplier(r#gen: u8, stage: i8) -> (u32, u32) {
+    let stage = stage.clamp(-6, 6);
+    if r#gen <= 2 {
+        // Gen 1-2 use a percentage table instead of (2 + n) / 2
+        const TABLE: [u32; 13] = [25, 28, 33, 40, 50, 66, 100, 150, 200, 250, 300, 350, 400];
+        return (TABLE[(stage + 6) as usize], 100);
+    }
+    if stage >= 0 {
+        (2 + stage as u32, 2)
+    } else {
+        (2, 2 + (-stage) as u32)
+    }
+}
+
+/// Accuracy/evasion stage multiplier as a fraction (numerator, denominator)
+pub fn accuracy_stage_multiplier(stage: i8) -> (u32, u32) {
+    let stage = stage.clamp(-6, 6);
+    if stage >= 0 {
+        (3 + stage as u32, 3)
+    } else {
+        (3, 3 + (-stage) as u32)
+    }
+}
+
+/// Applies a stat stage to a computed stat (floored, minimum 1)
+pub fn apply_stage(r#gen: u8, value: u16, stage: i8) -> u16 {
+    let (num, den) = stage_multiplier(r#gen, stage);
+    ((value as u32 * num / den).max(1)).min(u16::MAX as u32) as u16
+}
+
+impl Pokemon {
+    pub fn nature(&self) -> Option<Nature> {
+        self.nature.as_deref().and_then(|n| n.parse().ok())
+    }
+
+    /// Level as used by the stat formula (Showdown defaults to 100)
+    pub fn effective_level(&self) -> u8 {
+        self.level.unwrap_or(100)
+    }
+
+    /// Computes this Pokémon's final stats for the given generation and species base stats
+    pub fn stats(&self, r#gen: u8, base: &BaseStats) -> Stats {
+        let ivs = self.ivs.clone().unwrap_or_else(EVs::max_ivs);
+        calc_stats(
+            r#gen,
+            base,
+            &self.evs,
+            &ivs,
+            self.effective_level(),
+            self.nature(),
+        )
+    }
+}
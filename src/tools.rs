@@ -1,10 +1,21 @@
+use crate::analysis::{analyze_team, classify_team, diff_teams};
+use crate::data::{Dex, LATEST_GEN};
+use crate::format::Format;
+use crate::id::did_you_mean;
+use crate::library::{TeamLibrary, TeamQuery};
+use crate::lookup::{
+    can_learn, fetch_ability_info, fetch_item_info, fetch_learnset, fetch_move_info,
+};
 use crate::parser::team::{EVs, Pokemon};
+use crate::search::{self, SpeciesQuery};
+use crate::types::{Matchup, Type, effectiveness_text};
+use crate::validator::{validate, verdict};
 use crate::{PokemonInfo, ShowdownClient, Team};
 use anyhow::anyhow;
 use forge::api::tools_registry::Tool;
 use serde_json::Value;
 
-/// A tool to fetch Pokémon details from the PokeAPI
+/// A tool to fetch Pokémon details, offline when the species is bundled
 pub struct PokeAPITool;
 
 #[async_trait::async_trait]
@@ -18,13 +29,17 @@ impl Tool for PokeAPITool {
             "type": "function",
             "function": {
               "name": self.name(),
-              "description": "Fetches a pokemon details from the PokeAPI",
+              "description": "Fetches a pokemon's types, base stats, abilities and learnable moves from the bundled Pokedex, falling back to the PokeAPI for species it lacks",
               "parameters": {
                 "type": "object",
                 "properties": {
                   "pokemon": {
                     "type": "string",
                     "description": "Exact Pokemon Name"
+                  },
+                  "gen": {
+                    "type": "integer",
+                    "description": format!("Generation to describe the Pokemon as it was in (types, base stats, abilities, moves), {} by default", LATEST_GEN)
                   }
                 },
                 "required": ["pokemon"]
@@ -43,7 +58,12 @@ impl Tool for PokeAPITool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow!("Missing 'pokemon' argument"))?;
 
-        let pokemon_data = crate::pokeapi::fetch_pokemon_info(pokemon).await?;
+        let r#gen = args
+            .get("gen")
+            .and_then(|v| v.as_u64())
+            .map_or(LATEST_GEN, |g| g.clamp(1, LATEST_GEN as u64) as u8);
+
+        let pokemon_data = crate::pokeapi::fetch_pokemon_info_in(pokemon, r#gen).await?;
 
         let str = PokemonInfo::to_readable_form(&pokemon_data);
 
@@ -51,6 +71,248 @@ impl Tool for PokeAPITool {
     }
 }
 
+/// A tool to look up a move's type, power and effects
+pub struct MoveDetailsTool;
+
+#[async_trait::async_trait]
+impl Tool for MoveDetailsTool {
+    fn name(&self) -> &str {
+        "get_move_details"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Looks up a move's type, category, base power, accuracy, PP, priority, target, flags (contact, sound, punch...) and effects such as secondary chances, stat changes, recoil and switching. Check a move here before recommending it.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "move": {
+                            "type": "string",
+                            "description": "Move name, e.g. 'Knock Off'"
+                        },
+                        "gen": {
+                            "type": "integer",
+                            "description": format!("Generation to check the move exists in, {} by default", LATEST_GEN)
+                        }
+                    },
+                    "required": ["move"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let name = args
+            .get("move")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'move' argument"))?;
+        let r#gen = args
+            .get("gen")
+            .and_then(|v| v.as_u64())
+            .map_or(LATEST_GEN, |g| g.clamp(1, LATEST_GEN as u64) as u8);
+
+        Ok(fetch_move_info(name, r#gen).await?.to_readable_form())
+    }
+}
+
+/// A tool to look up what an item does
+pub struct ItemDetailsTool;
+
+#[async_trait::async_trait]
+impl Tool for ItemDetailsTool {
+    fn name(&self) -> &str {
+        "get_item_details"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Looks up a held item's effect, the generation it was introduced in, whether it is a Mega Stone or Z Crystal (and for which Pokemon), and its Fling power. Check an item here before putting it on a set.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "item": {
+                            "type": "string",
+                            "description": "Item name, e.g. 'Heavy-Duty Boots'"
+                        },
+                        "gen": {
+                            "type": "integer",
+                            "description": format!("Generation to check the item exists in, {} by default", LATEST_GEN)
+                        }
+                    },
+                    "required": ["item"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let name = args
+            .get("item")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'item' argument"))?;
+        let r#gen = args
+            .get("gen")
+            .and_then(|v| v.as_u64())
+            .map_or(LATEST_GEN, |g| g.clamp(1, LATEST_GEN as u64) as u8);
+
+        Ok(fetch_item_info(name, r#gen).await?.to_readable_form())
+    }
+}
+
+/// A tool to look up what an ability does and who can have it
+pub struct AbilityDetailsTool;
+
+#[async_trait::async_trait]
+impl Tool for AbilityDetailsTool {
+    fn name(&self) -> &str {
+        "get_ability_details"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Looks up an ability's effect and the generation it was introduced in. Give a Pokemon as well to check that it can have the ability. Check an ability here before putting it on a set.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "ability": {
+                            "type": "string",
+                            "description": "Ability name, e.g. 'Good as Gold'"
+                        },
+                        "pokemon": {
+                            "type": "string",
+                            "description": "Optional Pokemon that should have the ability"
+                        },
+                        "gen": {
+                            "type": "integer",
+                            "description": format!("Generation to check in, {} by default", LATEST_GEN)
+                        }
+                    },
+                    "required": ["ability"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let name = args
+            .get("ability")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'ability' argument"))?;
+        let r#gen = args
+            .get("gen")
+            .and_then(|v| v.as_u64())
+            .map_or(LATEST_GEN, |g| g.clamp(1, LATEST_GEN as u64) as u8);
+
+        let info = fetch_ability_info(name, r#gen).await?;
+        let mut text = info.to_readable_form();
+        if let Some(pokemon) = args.get("pokemon").and_then(|v| v.as_str()) {
+            let dex = Dex::get();
+            let Some(species) = dex.resolve_species(pokemon) else {
+                let suggestion = dex.suggest_species(pokemon).map(|s| s.name.as_str());
+                return Err(anyhow!(
+                    "Unknown Pokemon: {}{}",
+                    pokemon,
+                    did_you_mean(suggestion)
+                ));
+            };
+            let species = species.in_gen(r#gen);
+            let abilities: Vec<&str> = species.abilities.values().map(String::as_str).collect();
+            let verdict = if abilities.contains(&info.name.as_str()) {
+                "can"
+            } else {
+                "cannot"
+            };
+            text.push_str(&format!(
+                "{} {} have {} (its abilities: {})\n",
+                species.name,
+                verdict,
+                info.name,
+                abilities.join(", ")
+            ));
+        }
+        Ok(text)
+    }
+}
+
+/// A tool to check which moves a Pokemon can learn
+pub struct LearnsetTool;
+
+#[async_trait::async_trait]
+impl Tool for LearnsetTool {
+    fn name(&self) -> &str {
+        "check_learnset"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Checks whether a Pokemon can learn a move in a generation and how (level-up, TM, tutor, egg move, event). Without a move, lists every move the Pokemon can learn, grouped by method. The offline learnsets are partial, so a move they don't list is reported as unknown rather than unlearnable. Check every move here before putting it on a set.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "pokemon": {
+                            "type": "string",
+                            "description": "Pokemon name, e.g. 'Ferrothorn'"
+                        },
+                        "move": {
+                            "type": "string",
+                            "description": "Optional move to check, e.g. 'Sludge Bomb'"
+                        },
+                        "gen": {
+                            "type": "integer",
+                            "description": format!("Generation to check in, {} by default", LATEST_GEN)
+                        }
+                    },
+                    "required": ["pokemon"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let pokemon = args
+            .get("pokemon")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'pokemon' argument"))?;
+        let r#gen = args
+            .get("gen")
+            .and_then(|v| v.as_u64())
+            .map_or(LATEST_GEN, |g| g.clamp(1, LATEST_GEN as u64) as u8);
+
+        match args.get("move").and_then(|v| v.as_str()) {
+            Some(name) => Ok(can_learn(pokemon, name, r#gen)?.to_readable_form()),
+            None => Ok(fetch_learnset(pokemon, r#gen)?.to_readable_form()),
+        }
+    }
+}
+
 /// A tool to generate a Pokemon Showdown team text from structured data
 pub struct PokemonShowdownTeamGeneratorTool;
 
@@ -202,7 +464,8 @@ impl Tool for PokemonShowdownTeamGeneratorTool {
             });
         }
 
-        let team = Team { pokemon: mons };
+        let mut team = Team { pokemon: mons };
+        team.correct_names();
         let showdown_text = team.serialize();
         Ok(showdown_text)
     }
@@ -222,13 +485,21 @@ impl Tool for TeamValidatorTool {
             "type": "function",
             "function": {
                 "name": self.name(),
-                "description": "Validates a given Pokemon Showdown team text for correctness.",
+                "description": "Validates a given Pokemon Showdown team text for correctness: EVs, IVs, levels, clauses, and whether each Pokemon can legally have its ability, item and moves in the format. Names are normalized to their Showdown spelling first, and unknown names come with a 'did you mean' suggestion. The answer is valid, invalid, or unverified when the offline data can't settle a name or move; online validation settles those.",
                 "parameters": {
                     "type": "object",
                     "properties": {
                         "team_text": {
                             "type": "string",
                             "description": "The Pokemon Showdown format team text to validate."
+                        },
+                        "format": {
+                            "type": "string",
+                            "description": "Showdown format id, e.g. 'gen5ou' (defaults to gen5ou)"
+                        },
+                        "online": {
+                            "type": "boolean",
+                            "description": "Also ask the Showdown server to validate the team (defaults to false)"
                         }
                     },
                     "required": ["team_text"]
@@ -247,11 +518,492 @@ impl Tool for TeamValidatorTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow!("Missing 'team_text' argument"))?;
 
+        let format = args
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("gen5ou");
+
+        let online = args
+            .get("online")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let mut team = Team::deserialize(team_text).await;
+        let corrections = team.correct_names();
+        let corrected = if corrections.is_empty() {
+            String::new()
+        } else {
+            format!("Names: {}\n", corrections.join("; "))
+        };
+
+        let problems = validate(&team, &Format::from_id(format));
+        // Only the server can settle what the offline data couldn't check
+        let invalid = problems.iter().any(|p| !p.unverified);
+        if invalid || !online {
+            return Ok(format!("{}{}", corrected, verdict(&problems)));
+        }
+
         let mut client = ShowdownClient::new("test", "test".to_string(), 5);
 
-        match client.validate_team(team_text, "gen5ou").await {
-            Ok(_) => Ok("The team is valid.".to_string()),
-            Err(e) => Ok(format!("The team is invalid: {}", e)),
+        match client.validate_team(&team.serialize(), format).await {
+            Ok(_) => Ok(format!("{}The team is valid.", corrected)),
+            Err(e) => Ok(format!("{}The team is invalid: {}", corrected, e)),
+        }
+    }
+}
+
+/// A tool to analyze a team's type matchups and move coverage
+pub struct TeamAnalysisTool;
+
+#[async_trait::async_trait]
+impl Tool for TeamAnalysisTool {
+    fn name(&self) -> &str {
+        "analyze_pokemon_showdown_team"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Analyzes a Pokemon Showdown team text: each member's weaknesses, resistances and immunities (including abilities like Levitate), weaknesses shared by the team, offensive coverage of its moves and type combinations that wall it, plus the team's archetype (rain, sun, sand, hyper offense, balance, stall) and each member's role (hazard setter, spinner/defogger, pivot, cleric, setup sweeper, wallbreaker). Use it to critique a team.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "team_text": {
+                            "type": "string",
+                            "description": "The Pokemon Showdown format team text to analyze."
+                        },
+                        "format": {
+                            "type": "string",
+                            "description": "Showdown format id, e.g. 'gen5ou', whose generation's typings and type chart are used (defaults to the latest generation)"
+                        }
+                    },
+                    "required": ["team_text"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let team_text = args
+            .get("team_text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'team_text' argument"))?;
+
+        let r#gen = args
+            .get("format")
+            .and_then(|v| v.as_str())
+            .map_or(LATEST_GEN, |f| Format::from_id(f).r#gen);
+
+        let mut team = Team::deserialize(team_text).await;
+        team.correct_names();
+        if team.pokemon.is_empty() {
+            return Err(anyhow!("The team text contains no Pokemon"));
+        }
+
+        Ok(format!(
+            "{}\n\n{}",
+            classify_team(&team),
+            analyze_team(&team, r#gen)
+        ))
+    }
+}
+
+/// A tool to show what changed between two versions of a team
+pub struct TeamDiffTool;
+
+#[async_trait::async_trait]
+impl Tool for TeamDiffTool {
+    fn name(&self) -> &str {
+        "diff_pokemon_showdown_teams"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Compares two Pokemon Showdown team texts and lists added and removed Pokemon and, per Pokemon, changed item, ability, nature, EVs, IVs and moves. Use it after fixing a team to tell the user exactly what changed.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "old_team_text": {
+                            "type": "string",
+                            "description": "The team before the changes."
+                        },
+                        "new_team_text": {
+                            "type": "string",
+                            "description": "The team after the changes."
+                        }
+                    },
+                    "required": ["old_team_text", "new_team_text"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let team_arg = |key: &str| {
+            args.get(key)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Missing '{}' argument", key))
+        };
+        let old = Team::deserialize(team_arg("old_team_text")?).await;
+        let new = Team::deserialize(team_arg("new_team_text")?).await;
+
+        Ok(diff_teams(&old, &new).to_string())
+    }
+}
+
+/// A tool to store a team in the local team library
+pub struct TeamLibrarySaveTool;
+
+#[async_trait::async_trait]
+impl Tool for TeamLibrarySaveTool {
+    fn name(&self) -> &str {
+        "save_team_to_library"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Saves a Pokemon Showdown team text to the user's local team library with a name, format and tags, and returns its library id.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "description": "A short name for the team, e.g. 'Gen 5 Rain'"
+                        },
+                        "team_text": {
+                            "type": "string",
+                            "description": "The Pokemon Showdown format team text to save."
+                        },
+                        "format": {
+                            "type": "string",
+                            "description": "Showdown format id, e.g. 'gen5ou'"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Tags such as 'rain' or 'stall'"
+                        }
+                    },
+                    "required": ["name", "team_text"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'name' argument"))?;
+        let team_text = args
+            .get("team_text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'team_text' argument"))?;
+        let format = args.get("format").and_then(|v| v.as_str());
+        let tags: Vec<String> = args
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let team = Team::deserialize(team_text).await;
+        if team.pokemon.is_empty() {
+            return Err(anyhow!("The team text contains no Pokemon"));
+        }
+        let library = TeamLibrary::open(TeamLibrary::default_dir())?;
+        let entry = library.save(name, &team, format, &tags)?;
+        Ok(format!("Saved as {}", entry))
+    }
+}
+
+/// A tool to search the local team library
+pub struct TeamLibrarySearchTool;
+
+#[async_trait::async_trait]
+impl Tool for TeamLibrarySearchTool {
+    fn name(&self) -> &str {
+        "search_team_library"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Searches the user's local team library and returns matching teams with their metadata and team text. The query can name Pokemon, a format id and #tags, e.g. 'Ferrothorn gen5ou #rain'. An empty query lists every team.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Pokemon names, a format id like 'gen5ou', #tags and words from the team name"
+                        }
+                    },
+                    "required": ["query"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+
+        let library = TeamLibrary::open(TeamLibrary::default_dir())?;
+        let entries = library.search(&TeamQuery::parse(query)).await?;
+        if entries.is_empty() {
+            return Ok("No teams found.".to_string());
+        }
+
+        let mut output = Vec::new();
+        for entry in entries {
+            let team = library.load(&entry.id).await?;
+            output.push(format!("{}\n{}", entry, team.serialize().trim()));
         }
+        Ok(output.join("\n\n"))
+    }
+}
+
+/// A tool to find Pokemon matching teambuilding criteria
+pub struct PokemonSearchTool;
+
+#[async_trait::async_trait]
+impl Tool for PokemonSearchTool {
+    fn name(&self) -> &str {
+        "search_pokemon"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Finds Pokemon by type, ability, base stats, learnable moves, format legality and tier, with their types, tier, base stats and abilities. Use it to find teammates instead of guessing, e.g. 'steel gen6 ou spe>=90 U-turn sort:atk'.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Types, 'gen6', a format id like 'gen6ou' (legal there), tiers like 'OU', stat bounds like 'spe>=90' or 'bst<500', move and ability names, 'sort:<stat>' and 'limit:<n>'"
+                        }
+                    },
+                    "required": ["query"]
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'query' argument"))?;
+
+        let mut query = SpeciesQuery::parse(query)?;
+        query.limit = query.limit.or(Some(25));
+        Ok(search::report(&query, &search::search(&query)?))
+    }
+}
+
+/// A tool to look up type effectiveness, generation by generation
+pub struct TypeMatchupTool;
+
+#[async_trait::async_trait]
+impl Tool for TypeMatchupTool {
+    fn name(&self) -> &str {
+        "get_type_matchup"
+    }
+
+    fn description(&self) -> Value {
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": self.name(),
+                "description": "Looks up type effectiveness with the type chart of a given generation, counting ability and item immunities (Levitate, Flash Fire, Air Balloon...), moves with special rules (Freeze-Dry, Thousand Arrows, Flying Press), Scrappy and Tera types. Give a move or an attacking type for a single multiplier, or leave both out for every type's multiplier against the defender.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "defender": {
+                            "type": "string",
+                            "description": "Defending Pokemon, whose types are looked up. Not needed with defending_types."
+                        },
+                        "defending_types": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Defending types, e.g. [\"Water\", \"Ground\"]"
+                        },
+                        "move": {
+                            "type": "string",
+                            "description": "Attacking move; its type is used"
+                        },
+                        "attacking_type": {
+                            "type": "string",
+                            "description": "Attacking type when no move is given"
+                        },
+                        "ability": {
+                            "type": "string",
+                            "description": "The defender's ability"
+                        },
+                        "item": {
+                            "type": "string",
+                            "description": "The defender's item"
+                        },
+                        "tera_type": {
+                            "type": "string",
+                            "description": "The type the defender Terastallized into"
+                        },
+                        "attacker_ability": {
+                            "type": "string",
+                            "description": "The attacker's ability, e.g. Scrappy or Mold Breaker"
+                        },
+                        "gen": {
+                            "type": "integer",
+                            "description": format!("Generation of the type chart, {} by default", LATEST_GEN)
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn tool_callback(&self) -> bool {
+        true
+    }
+
+    async fn execute_tool(&self, args: Value) -> anyhow::Result<String> {
+        let text = |key: &str| args.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let r#gen = args
+            .get("gen")
+            .and_then(|v| v.as_u64())
+            .map_or(LATEST_GEN, |g| g.clamp(1, LATEST_GEN as u64) as u8);
+        let dex = Dex::get();
+
+        let (defender, types): (String, Vec<Type>) =
+            match args.get("defending_types").and_then(|v| v.as_array()) {
+                Some(types) => {
+                    let types = types
+                        .iter()
+                        .filter_map(|t| t.as_str())
+                        .map(str::parse)
+                        .collect::<anyhow::Result<Vec<Type>>>()?;
+                    let names: Vec<&str> = types.iter().map(|t| t.name()).collect();
+                    (names.join("/"), types)
+                }
+                None => {
+                    let name = text("defender").ok_or_else(|| {
+                        anyhow!("Missing 'defender' or 'defending_types' argument")
+                    })?;
+                    let species = dex
+                        .get_species(&name)
+                        .ok_or_else(|| anyhow!("Unknown Pokemon: {}", name))?
+                        .in_gen(r#gen);
+                    let types = species
+                        .types
+                        .iter()
+                        .filter_map(|t| t.parse().ok())
+                        .collect();
+                    (
+                        format!("{} ({})", species.name, species.types.join("/")),
+                        types,
+                    )
+                }
+            };
+        let matchup = Matchup {
+            r#gen,
+            move_name: text("move"),
+            attacker_ability: text("attacker_ability"),
+            defender_ability: text("ability"),
+            defender_item: text("item"),
+            tera_type: text("tera_type").map(|t| t.parse()).transpose()?,
+        };
+        let defender = match matchup.tera_type {
+            Some(tera) => format!("{} Tera {}", defender, tera),
+            None => defender,
+        };
+
+        let attack = match (&matchup.move_name, text("attacking_type")) {
+            (Some(name), _) => {
+                let mv = dex
+                    .get_move(name)
+                    .ok_or_else(|| anyhow!("Unknown move: {}", name))?;
+                Some((format!("{} ({})", mv.name, mv.r#type), mv.r#type.parse()?))
+            }
+            (None, Some(r#type)) => {
+                let r#type: Type = r#type.parse()?;
+                Some((r#type.to_string(), r#type))
+            }
+            (None, None) => None,
+        };
+        if let Some((name, r#type)) = attack {
+            let multiplier = matchup.multiplier(r#type, &types);
+            let reason = matchup
+                .immunity(r#type, &types)
+                .map(|source| format!(" ({})", source))
+                .unwrap_or_default();
+            return Ok(format!(
+                "{} vs {} in Gen {}: {}x, {}{}",
+                name,
+                defender,
+                r#gen,
+                multiplier,
+                effectiveness_text(multiplier),
+                reason
+            ));
+        }
+
+        let chart = matchup.chart(&types);
+        let group = |label: &str, keep: &dyn Fn(f32) -> bool| {
+            let members: Vec<String> = chart
+                .iter()
+                .filter(|(_, m)| keep(*m))
+                .map(|(t, m)| format!("{} ({}x)", t, m))
+                .collect();
+            (!members.is_empty()).then(|| format!("{}: {}", label, members.join(", ")))
+        };
+        let lines: Vec<String> = [
+            group("Weak to", &|m| m > 1.0),
+            group("Resists", &|m| m > 0.0 && m < 1.0),
+            group("Immune to", &|m| m == 0.0),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Ok(format!(
+            "{} in Gen {}\n{}",
+            defender,
+            r#gen,
+            lines.join("\n")
+        ))
     }
 }
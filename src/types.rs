@@ -0,0 +1,367 @@
+//! The type chart, by generation, and what else decides a move's effectiveness.
+//!
+//! Gen 1 had Bug and Poison super effective on each other, Ghost unable to touch Psychic and Ice
+//! neutral on Fire. Gens 2 to 5 added Dark and Steel, with Steel resisting Ghost and Dark, and
+//! Gen 6 added Fairy and dropped those two resistances. [`Matchup`] layers abilities, items,
+//! move-specific rules and Terastallization on top.
+
+use crate::data::Dex;
+use crate::id::to_id;
+use std::fmt;
+use std::str::FromStr;
+
+/// The 18 Pokémon types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Type {
+    Normal,
+    Fire,
+    Water,
+    Electric,
+    Grass,
+    Ice,
+    Fighting,
+    Poison,
+    Ground,
+    Flying,
+    Psychic,
+    Bug,
+    Rock,
+    Ghost,
+    Dragon,
+    Dark,
+    Steel,
+    Fairy,
+}
+
+use Type::*;
+
+impl Type {
+    pub const ALL: [Type; 18] = [
+        Normal, Fire, Water, Electric, Grass, Ice, Fighting, Poison, Ground, Flying, Psychic, Bug,
+        Rock, Ghost, Dragon, Dark, Steel, Fairy,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Normal => "Normal",
+            Fire => "Fire",
+            Water => "Water",
+            Electric => "Electric",
+            Grass => "Grass",
+            Ice => "Ice",
+            Fighting => "Fighting",
+            Poison => "Poison",
+            Ground => "Ground",
+            Flying => "Flying",
+            Psychic => "Psychic",
+            Bug => "Bug",
+            Rock => "Rock",
+            Ghost => "Ghost",
+            Dragon => "Dragon",
+            Dark => "Dark",
+            Steel => "Steel",
+            Fairy => "Fairy",
+        }
+    }
+
+    /// Dark and Steel arrived in Gen 2, Fairy in Gen 6
+    pub fn exists_in(&self, r#gen: u8) -> bool {
+        match self {
+            Dark | Steel => r#gen >= 2,
+            Fairy => r#gen >= 6,
+            _ => true,
+        }
+    }
+
+    /// Hex color the games and Showdown use for the type
+    pub fn color(&self) -> &'static str {
+        match self {
+            Normal => "#A8A878",
+            Fire => "#F08030",
+            Water => "#6890F0",
+            Electric => "#F8D030",
+            Grass => "#78C850",
+            Ice => "#98D8D8",
+            Fighting => "#C03028",
+            Poison => "#A040A0",
+            Ground => "#E0C068",
+            Flying => "#A890F0",
+            Psychic => "#F85888",
+            Bug => "#A8B820",
+            Rock => "#B8A038",
+            Ghost => "#705898",
+            Dragon => "#7038F8",
+            Dark => "#705848",
+            Steel => "#B8B8D0",
+            Fairy => "#EE99AC",
+        }
+    }
+
+    /// (super effective against, not very effective against, no effect on), current generation
+    fn matchups(&self) -> (&'static [Type], &'static [Type], &'static [Type]) {
+        match self {
+            Normal => (&[], &[Rock, Steel], &[Ghost]),
+            Fire => (&[Grass, Ice, Bug, Steel], &[Fire, Water, Rock, Dragon], &[]),
+            Water => (&[Fire, Ground, Rock], &[Water, Grass, Dragon], &[]),
+            Electric => (&[Water, Flying], &[Electric, Grass, Dragon], &[Ground]),
+            Grass => (
+                &[Water, Ground, Rock],
+                &[Fire, Grass, Poison, Flying, Bug, Dragon, Steel],
+                &[],
+            ),
+            Ice => (
+                &[Grass, Ground, Flying, Dragon],
+                &[Fire, Water, Ice, Steel],
+                &[],
+            ),
+            Fighting => (
+                &[Normal, Ice, Rock, Dark, Steel],
+                &[Poison, Flying, Psychic, Bug, Fairy],
+                &[Ghost],
+            ),
+            Poison => (&[Grass, Fairy], &[Poison, Ground, Rock, Ghost], &[Steel]),
+            Ground => (
+                &[Fire, Electric, Poison, Rock, Steel],
+                &[Grass, Bug],
+                &[Flying],
+            ),
+            Flying => (&[Grass, Fighting, Bug], &[Electric, Rock, Steel], &[]),
+            Psychic => (&[Fighting, Poison], &[Psychic, Steel], &[Dark]),
+            Bug => (
+                &[Grass, Psychic, Dark],
+                &[Fire, Fighting, Poison, Flying, Ghost, Steel, Fairy],
+                &[],
+            ),
+            Rock => (&[Fire, Ice, Flying, Bug], &[Fighting, Ground, Steel], &[]),
+            Ghost => (&[Psychic, Ghost], &[Dark], &[Normal]),
+            Dragon => (&[Dragon], &[Steel], &[Fairy]),
+            Dark => (&[Psychic, Ghost], &[Fighting, Dark, Fairy], &[]),
+            Steel => (&[Ice, Rock, Fairy], &[Fire, Water, Electric, Steel], &[]),
+            Fairy => (&[Fighting, Dragon, Dark], &[Fire, Poison, Steel], &[]),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Type {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Type::ALL
+            .into_iter()
+            .find(|t| t.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| anyhow::anyhow!("Unknown type: {}", s))
+    }
+}
+
+/// Damage multiplier of an `attack` type move against a single defending type
+pub fn effectiveness(attack: Type, defend: Type) -> f32 {
+    let (strong, weak, immune) = attack.matchups();
+    if immune.contains(&defend) {
+        0.0
+    } else if strong.contains(&defend) {
+        2.0
+    } else if weak.contains(&defend) {
+        0.5
+    } else {
+        1.0
+    }
+}
+
+/// Damage multiplier of an `attack` type move against a Pokémon with the given types
+pub fn type_effectiveness(attack: Type, defenders: &[Type]) -> f32 {
+    defenders
+        .iter()
+        .map(|&defend| effectiveness(attack, defend))
+        .product()
+}
+
+/// Damage multiplier of an `attack` type move against a single defending type in `gen`. Types
+/// that don't exist yet are neutral both ways.
+pub fn effectiveness_in(r#gen: u8, attack: Type, defend: Type) -> f32 {
+    if !attack.exists_in(r#gen) || !defend.exists_in(r#gen) {
+        return 1.0;
+    }
+    match (r#gen, attack, defend) {
+        (1, Bug, Poison) | (1, Poison, Bug) => 2.0,
+        (1, Ghost, Psychic) => 0.0,
+        (1, Ice, Fire) => 1.0,
+        (2..=5, Ghost | Dark, Steel) => 0.5,
+        _ => effectiveness(attack, defend),
+    }
+}
+
+/// [`type_effectiveness`] with the type chart of `gen`
+pub fn type_effectiveness_in(r#gen: u8, attack: Type, defenders: &[Type]) -> f32 {
+    defenders
+        .iter()
+        .map(|&defend| effectiveness_in(r#gen, attack, defend))
+        .product()
+}
+
+/// Everything besides the two sides' types that decides how effective a move is
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Matchup {
+    /// Generation of the type chart, the current one when 0
+    pub r#gen: u8,
+    /// For moves with rules of their own: Freeze-Dry, Thousand Arrows, Flying Press, and sound
+    /// and ball moves against Soundproof and Bulletproof
+    pub move_name: Option<String>,
+    /// Scrappy and Mind's Eye hit Ghosts; Mold Breaker ignores the defender's ability
+    pub attacker_ability: Option<String>,
+    pub defender_ability: Option<String>,
+    /// Air Balloon, Ring Target and Iron Ball
+    pub defender_item: Option<String>,
+    /// The defender Terastallized into this type
+    pub tera_type: Option<Type>,
+}
+
+impl Matchup {
+    pub fn new(r#gen: u8) -> Matchup {
+        Matchup {
+            r#gen,
+            ..Default::default()
+        }
+    }
+
+    fn chart_gen(&self) -> u8 {
+        if self.r#gen == 0 { 9 } else { self.r#gen }
+    }
+
+    fn move_id(&self) -> String {
+        self.move_name.as_deref().map(to_id).unwrap_or_default()
+    }
+
+    fn attacker_ability(&self) -> String {
+        self.attacker_ability
+            .as_deref()
+            .map(to_id)
+            .unwrap_or_default()
+    }
+
+    /// The defender's ability, unless the attacker's ignores it
+    fn defender_ability(&self) -> String {
+        if matches!(
+            self.attacker_ability().as_str(),
+            "moldbreaker" | "teravolt" | "turboblaze"
+        ) {
+            return String::new();
+        }
+        self.defender_ability
+            .as_deref()
+            .map(to_id)
+            .unwrap_or_default()
+    }
+
+    fn defender_item(&self) -> String {
+        self.defender_item.as_deref().map(to_id).unwrap_or_default()
+    }
+
+    /// Multiplier against one defending type, with the move's and the attacker's special rules
+    /// and the defender's item lifting type immunities
+    pub fn against_type(&self, attack: Type, defend: Type) -> f32 {
+        let r#gen = self.chart_gen();
+        let move_id = self.move_id();
+        let mut multiplier = effectiveness_in(r#gen, attack, defend);
+        if move_id == "flyingpress" {
+            multiplier *= effectiveness_in(r#gen, Flying, defend);
+        }
+        match (move_id.as_str(), attack, defend) {
+            ("freezedry", _, Water) => return 2.0,
+            ("thousandarrows", Ground, Flying) => return 1.0,
+            _ => {}
+        }
+        if multiplier == 0.0 {
+            let scrappy = matches!(self.attacker_ability().as_str(), "scrappy" | "mindseye")
+                && matches!(attack, Normal | Fighting)
+                && defend == Ghost;
+            let item = self.defender_item();
+            let grounded = item == "ironball" && attack == Ground && defend == Flying;
+            if scrappy || grounded || item == "ringtarget" {
+                return 1.0;
+            }
+        }
+        multiplier
+    }
+
+    /// Multiplier from the type chart alone against `types`, or the Tera type when there is one
+    pub fn type_multiplier(&self, attack: Type, types: &[Type]) -> f32 {
+        match self.tera_type {
+            Some(tera) => self.against_type(attack, tera),
+            None => types
+                .iter()
+                .map(|t| self.against_type(attack, *t))
+                .product(),
+        }
+    }
+
+    /// The ability or item that makes the defender immune to the move when its types don't:
+    /// "Levitate", "Air Balloon", "Wonder Guard"...
+    pub fn immunity(&self, attack: Type, types: &[Type]) -> Option<&'static str> {
+        let r#gen = self.chart_gen();
+        let move_id = self.move_id();
+        let ignores_floating = move_id == "thousandarrows";
+        let flags = |flag: &str| {
+            Dex::get()
+                .get_move(&move_id)
+                .is_some_and(|m| m.has_flag(flag))
+        };
+        let ability = match (self.defender_ability().as_str(), attack) {
+            ("levitate", Ground) if !ignores_floating => Some("Levitate"),
+            ("eartheater", Ground) => Some("Earth Eater"),
+            ("flashfire", Fire) => Some("Flash Fire"),
+            ("wellbakedbody", Fire) => Some("Well-Baked Body"),
+            ("waterabsorb", Water) => Some("Water Absorb"),
+            ("dryskin", Water) => Some("Dry Skin"),
+            ("stormdrain", Water) if r#gen >= 5 => Some("Storm Drain"),
+            ("voltabsorb", Electric) => Some("Volt Absorb"),
+            ("motordrive", Electric) => Some("Motor Drive"),
+            ("lightningrod", Electric) if r#gen >= 5 => Some("Lightning Rod"),
+            ("sapsipper", Grass) => Some("Sap Sipper"),
+            ("soundproof", _) if flags("sound") => Some("Soundproof"),
+            ("bulletproof", _) if flags("bullet") => Some("Bulletproof"),
+            ("wonderguard", _) if self.type_multiplier(attack, types) <= 1.0 => {
+                Some("Wonder Guard")
+            }
+            _ => None,
+        };
+        ability.or_else(|| {
+            (attack == Ground && self.defender_item() == "airballoon" && !ignores_floating)
+                .then_some("Air Balloon")
+        })
+    }
+
+    /// Damage multiplier of an `attack` type move against a defender with `types`
+    pub fn multiplier(&self, attack: Type, types: &[Type]) -> f32 {
+        if self.immunity(attack, types).is_some() {
+            0.0
+        } else {
+            self.type_multiplier(attack, types)
+        }
+    }
+
+    /// The multiplier from each attacking type that exists in the generation
+    pub fn chart(&self, types: &[Type]) -> Vec<(Type, f32)> {
+        Type::ALL
+            .into_iter()
+            .filter(|t| t.exists_in(self.chart_gen()))
+            .map(|attack| (attack, self.multiplier(attack, types)))
+            .collect()
+    }
+}
+
+/// "immune", "not very effective", "neutral" or "super effective"
+pub fn effectiveness_text(multiplier: f32) -> &'static str {
+    match multiplier {
+        0.0 => "immune",
+        m if m < 1.0 => "not very effective",
+        m if m > 1.0 => "super effective",
+        _ => "neutral",
+    }
+}
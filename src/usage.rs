@@ -0,0 +1,331 @@
+//! Metagame usage statistics from Smogon's monthly `chaos` JSON files.
+//!
+//! A chaos file (`chaos/gen9ou-1695.json`) has an `info` header and a `data` map from species
+//! name to weighted counts of abilities, items, spreads, moves, tera types and teammates, plus
+//! checks-and-counters matchups. Counts are turned into shares of the species' total here,
+//! sorted most common first, and ids are replaced by display names when the dex knows them.
+
+use crate::data::Dex;
+use crate::id::to_id;
+use crate::parser::team::EVs;
+use crate::types::Type;
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Environment variable pointing at a directory of chaos files
+pub const USAGE_DIR_ENV: &str = "POKEBRAINS_USAGE_DIR";
+
+#[derive(Debug, Deserialize)]
+struct ChaosFile {
+    info: ChaosInfo,
+    data: HashMap<String, ChaosSpecies>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChaosInfo {
+    metagame: String,
+    cutoff: f64,
+    #[serde(rename = "number of battles")]
+    battles: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChaosSpecies {
+    #[serde(rename = "Raw count", default)]
+    raw_count: u64,
+    #[serde(default)]
+    usage: f64,
+    #[serde(rename = "Abilities", default)]
+    abilities: HashMap<String, f64>,
+    #[serde(rename = "Items", default)]
+    items: HashMap<String, f64>,
+    #[serde(rename = "Spreads", default)]
+    spreads: HashMap<String, f64>,
+    #[serde(rename = "Moves", default)]
+    moves: HashMap<String, f64>,
+    #[serde(rename = "Tera Types", default)]
+    tera_types: HashMap<String, f64>,
+    #[serde(rename = "Teammates", default)]
+    teammates: HashMap<String, f64>,
+    /// `[matchups, KO-or-switch probability, standard deviation]`
+    #[serde(rename = "Checks and Counters", default)]
+    checks_and_counters: HashMap<String, Vec<f64>>,
+}
+
+/// A nature and EV spread with how often it is used
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spread {
+    pub nature: String,
+    pub evs: EVs,
+    pub share: f64,
+}
+
+/// A Pokémon that beats the species, from the checks-and-counters table
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counter {
+    pub species: String,
+    pub matchups: f64,
+    /// Chance the species gets KOed or forced out in the matchup
+    pub ko_or_switch: f64,
+    pub deviation: f64,
+}
+
+impl Counter {
+    /// Smogon's ranking score: the probability minus four standard deviations
+    pub fn score(&self) -> f64 {
+        self.ko_or_switch - 4.0 * self.deviation
+    }
+}
+
+/// Everything known about how one species is used in a metagame. Shares are fractions of the
+/// species' own (weighted) appearances, so move shares add up to about 4.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeciesUsage {
+    pub name: String,
+    /// Weighted fraction of teams using the species
+    pub usage: f64,
+    pub raw_count: u64,
+    pub abilities: Vec<(String, f64)>,
+    pub items: Vec<(String, f64)>,
+    pub spreads: Vec<Spread>,
+    pub moves: Vec<(String, f64)>,
+    pub tera_types: Vec<(String, f64)>,
+    pub teammates: Vec<(String, f64)>,
+    /// Sorted by [`Counter::score`], best counter first
+    pub counters: Vec<Counter>,
+}
+
+fn share_of(name: &str, options: &[(String, f64)]) -> f64 {
+    options
+        .iter()
+        .find(|(option, _)| to_id(option) == to_id(name))
+        .map_or(0.0, |(_, share)| *share)
+}
+
+impl SpeciesUsage {
+    pub fn ability_share(&self, ability: &str) -> f64 {
+        share_of(ability, &self.abilities)
+    }
+
+    pub fn item_share(&self, item: &str) -> f64 {
+        share_of(item, &self.items)
+    }
+
+    pub fn move_share(&self, move_name: &str) -> f64 {
+        share_of(move_name, &self.moves)
+    }
+
+    pub fn teammate_share(&self, species: &str) -> f64 {
+        share_of(species, &self.teammates)
+    }
+}
+
+/// Counts as shares of `total`, most common first. Empty ids (Showdown's "nothing") are dropped.
+fn shares(
+    counts: HashMap<String, f64>,
+    total: f64,
+    name: impl Fn(&str) -> String,
+) -> Vec<(String, f64)> {
+    let mut shares: Vec<(String, f64)> = counts
+        .into_iter()
+        .filter(|(id, _)| !id.is_empty() && id != "nothing")
+        .map(|(id, count)| (name(&id), if total > 0.0 { count / total } else { 0.0 }))
+        .collect();
+    shares.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    shares
+}
+
+/// "Jolly:0/252/4/0/0/252"
+fn parse_spread(spread: &str) -> Option<(String, EVs)> {
+    let (nature, evs) = spread.split_once(':')?;
+    let values: Vec<u16> = evs
+        .split('/')
+        .map(|v| v.parse().ok())
+        .collect::<Option<_>>()?;
+    let [hp, atk, def, spa, spd, spe] = values[..] else {
+        return None;
+    };
+    Some((
+        nature.to_string(),
+        EVs {
+            hp,
+            atk,
+            def,
+            spa,
+            spd,
+            spe,
+        },
+    ))
+}
+
+impl SpeciesUsage {
+    fn from_chaos(name: String, chaos: ChaosSpecies) -> SpeciesUsage {
+        let dex = Dex::get();
+        // Every appearance has exactly one ability, so their counts add up to the total
+        let total: f64 = chaos.abilities.values().sum();
+
+        let mut spreads: Vec<Spread> = chaos
+            .spreads
+            .into_iter()
+            .filter_map(|(spread, count)| {
+                let (nature, evs) = parse_spread(&spread)?;
+                Some(Spread {
+                    nature,
+                    evs,
+                    share: if total > 0.0 { count / total } else { 0.0 },
+                })
+            })
+            .collect();
+        spreads.sort_by(|a, b| b.share.total_cmp(&a.share));
+
+        let mut counters: Vec<Counter> = chaos
+            .checks_and_counters
+            .into_iter()
+            .filter_map(|(species, stats)| match stats[..] {
+                [matchups, ko_or_switch, deviation, ..] => Some(Counter {
+                    species,
+                    matchups,
+                    ko_or_switch,
+                    deviation,
+                }),
+                _ => None,
+            })
+            .collect();
+        counters.sort_by(|a, b| b.score().total_cmp(&a.score()));
+
+        SpeciesUsage {
+            usage: chaos.usage,
+            raw_count: chaos.raw_count,
+            abilities: shares(chaos.abilities, total, |id| {
+                dex.get_ability(id)
+                    .map_or(id.to_string(), |a| a.name.clone())
+            }),
+            items: shares(chaos.items, total, |id| {
+                dex.get_item(id).map_or(id.to_string(), |i| i.name.clone())
+            }),
+            spreads,
+            moves: shares(chaos.moves, total, |id| {
+                dex.get_move(id).map_or(id.to_string(), |m| m.name.clone())
+            }),
+            tera_types: shares(chaos.tera_types, total, |id| {
+                id.parse::<Type>()
+                    .map_or(id.to_string(), |t| t.name().to_string())
+            }),
+            teammates: shares(chaos.teammates, total, str::to_string),
+            counters,
+            name,
+        }
+    }
+}
+
+/// Usage statistics of one metagame at one rating cutoff
+#[derive(Debug, Clone)]
+pub struct UsageStats {
+    pub format: String,
+    pub cutoff: u32,
+    pub battles: u64,
+    species: HashMap<String, SpeciesUsage>,
+}
+
+impl UsageStats {
+    pub fn from_json(json: &str) -> Result<UsageStats> {
+        let chaos: ChaosFile = serde_json::from_str(json)?;
+        Ok(UsageStats {
+            format: to_id(&chaos.info.metagame),
+            cutoff: chaos.info.cutoff.round() as u32,
+            battles: chaos.info.battles,
+            species: chaos
+                .data
+                .into_iter()
+                .map(|(name, data)| (to_id(&name), SpeciesUsage::from_chaos(name, data)))
+                .collect(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<UsageStats> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        UsageStats::from_json(&text).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn get(&self, species: &str) -> Option<&SpeciesUsage> {
+        self.species.get(&to_id(species))
+    }
+
+    /// Species ordered by usage, most used first
+    pub fn ranked(&self) -> Vec<&SpeciesUsage> {
+        let mut ranked: Vec<&SpeciesUsage> = self.species.values().collect();
+        ranked.sort_by(|a, b| {
+            b.usage
+                .total_cmp(&a.usage)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        ranked
+    }
+}
+
+/// Loaded chaos files by format id. When a format has several cutoffs, the highest is kept.
+#[derive(Debug, Default)]
+pub struct UsageDex {
+    formats: HashMap<String, UsageStats>,
+}
+
+impl UsageDex {
+    /// The shared statistics from `POKEBRAINS_USAGE_DIR`, or `usage/`; empty if neither exists
+    pub fn get() -> &'static UsageDex {
+        static USAGE: OnceLock<UsageDex> = OnceLock::new();
+        USAGE.get_or_init(|| {
+            let dir = std::env::var(USAGE_DIR_ENV).unwrap_or_else(|_| "usage".to_string());
+            let mut usage = UsageDex::default();
+            if Path::new(&dir).is_dir()
+                && let Err(e) = usage.load_dir(Path::new(&dir))
+            {
+                eprintln!("Failed to load usage stats: {:#}", e);
+            }
+            usage
+        })
+    }
+
+    /// Adds every `.json` chaos file in `dir`. Files that fail to load are skipped with a
+    /// warning, so one bad download doesn't hide the other formats.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<()> {
+        let files =
+            std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for file in files {
+            let path = file?.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            match UsageStats::load(&path) {
+                Ok(stats) => self.insert(stats),
+                Err(e) => eprintln!("Skipping usage stats: {:#}", e),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn insert(&mut self, stats: UsageStats) {
+        match self.formats.get(&stats.format) {
+            Some(existing) if existing.cutoff > stats.cutoff => {}
+            _ => {
+                self.formats.insert(stats.format.clone(), stats);
+            }
+        }
+    }
+
+    pub fn format(&self, format: &str) -> Option<&UsageStats> {
+        self.formats.get(&to_id(format))
+    }
+
+    pub fn species(&self, format: &str, species: &str) -> Result<&SpeciesUsage> {
+        let stats = self
+            .format(format)
+            .ok_or_else(|| anyhow!("No usage stats loaded for {}", format))?;
+        stats
+            .get(species)
+            .ok_or_else(|| anyhow!("{} has no usage in {}", species, format))
+    }
+}
@@ -0,0 +1,304 @@
+use super::Problem;
+use crate::data::{Dex, Species};
+use crate::format::{Clause, Format};
+use crate::id::{did_you_mean, to_id};
+use crate::parser::team::{Pokemon, Team};
+use std::collections::HashMap;
+
+const EVASION_ITEMS: [&str; 2] = ["brightpowder", "laxincense"];
+const EVASION_ABILITIES: [&str; 2] = ["sandveil", "snowcloak"];
+
+/// Checks species, abilities, items and moves against the bundled dex for the format's
+/// generation, its banlist and its clauses. Every problem found is reported, not just the first.
+/// When the dex is a subset, names and learnset entries it lacks are reported as unverified
+/// rather than illegal.
+pub fn validate_legality(team: &Team, format: &Format) -> Vec<Problem> {
+    let dex = Dex::get();
+    let mut problems = Vec::new();
+
+    for pkmn in &team.pokemon {
+        problems.extend(validate_pokemon(dex, pkmn, format));
+    }
+
+    // Species Clause goes by Pokédex number, so Charizard and Charizard-Mega-X conflict.
+    // Exact duplicates are already reported by the structural check.
+    if format.has_clause(Clause::Species) {
+        let mut by_num: HashMap<i32, Vec<&str>> = HashMap::new();
+        for pkmn in &team.pokemon {
+            if let Some(species) = dex.get_species(species_of(pkmn)) {
+                by_num.entry(species.num).or_default().push(&species.name);
+            }
+        }
+        let mut clashes: Vec<_> = by_num
+            .into_values()
+            .filter(|names| names.len() > 1 && names.iter().any(|n| *n != names[0]))
+            .collect();
+        clashes.sort();
+        for names in clashes {
+            problems.push(Problem::team(format!(
+                "{}: {} are the same species",
+                Clause::Species,
+                names.join(" and ")
+            )));
+        }
+    }
+
+    if format.has_clause(Clause::BatonPass) {
+        let passers = team
+            .pokemon
+            .iter()
+            .filter(|p| p.moves.iter().any(|m| to_id(m) == "batonpass"))
+            .count();
+        if passers > 1 {
+            problems.push(Problem::team(format!(
+                "{}: {} Pokémon know Baton Pass, only one is allowed",
+                Clause::BatonPass,
+                passers
+            )));
+        }
+    }
+
+    for combo in format.banned_combinations() {
+        let on_team = |part: &String| {
+            let id = to_id(part);
+            team.pokemon.iter().any(|p| {
+                let names = [Some(species_of(p)), p.ability.as_deref(), p.item.as_deref()];
+                names.into_iter().flatten().any(|n| to_id(n) == id)
+                    || p.moves.iter().any(|m| to_id(m) == id)
+            })
+        };
+        if combo.iter().all(on_team) {
+            problems.push(Problem::team(format!(
+                "{} can't be used together in {}",
+                combo.join(" and "),
+                format
+            )));
+        }
+    }
+
+    problems
+}
+
+fn validate_pokemon(dex: &Dex, pkmn: &Pokemon, format: &Format) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let name = species_of(pkmn);
+    let mut push = |message: String| problems.push(Problem::pokemon(name, message));
+    // Names and learnset entries the bundled subset lacks may still be legal
+    let mut unchecked = Vec::new();
+    let mut unknown = |message: String| {
+        unchecked.push(if dex.is_complete() {
+            Problem::pokemon(name, message)
+        } else {
+            Problem::unverified(name, message)
+        })
+    };
+    let r#gen = format.r#gen;
+
+    let Some(species) = dex.get_species(name) else {
+        // LLMs sometimes put the held item or a move where the species goes
+        let kind = if dex.get_item(name).is_some() {
+            Some("an item")
+        } else if dex.get_move(name).is_some() {
+            Some("a move")
+        } else {
+            None
+        };
+        match kind {
+            Some(kind) => push(format!("{} is {}, not a Pokémon", name, kind)),
+            None => unknown(format!(
+                "{} is not a known Pokémon{}",
+                name,
+                did_you_mean(dex.suggest_species(name).map(|s| s.name.as_str()))
+            )),
+        }
+        problems.extend(unchecked);
+        return problems;
+    };
+    if species.r#gen > r#gen {
+        push(format!("{} does not exist in Gen {}", species.name, r#gen));
+        return problems;
+    }
+    let species = species.in_gen(r#gen);
+    // Formes that change in battle (Megas, Keldeo-Resolute) are built as their base species
+    let base = species
+        .changes_from
+        .as_deref()
+        .and_then(|b| dex.get_species(b))
+        .map(|b| b.in_gen(r#gen));
+    let out_of_battle = base.as_ref().unwrap_or(&species);
+
+    if let Some(ref battle_only) = species.battle_only {
+        push(format!(
+            "{} only exists in battle, use {}",
+            species.name, battle_only
+        ));
+    }
+
+    // Availability and tier bans, also for the Mega the held stone turns it into
+    let mut forms = vec![species.clone()];
+    if let Some(mega) = pkmn
+        .item
+        .as_deref()
+        .and_then(|i| dex.get_item(i))
+        .filter(|i| i.mega_evolves.as_deref() == Some(&out_of_battle.name))
+        .and_then(|i| dex.get_species(i.mega_stone.as_deref()?))
+        .filter(|mega| mega.name != species.name)
+    {
+        forms.push(mega.in_gen(r#gen));
+    }
+    for form in &forms {
+        if let Some(entry) = dex.formats_entry(r#gen, &form.name) {
+            if entry.is_nonstandard.as_deref() == Some("Past") {
+                push(format!("{} is not obtainable in Gen {}", form.name, r#gen));
+                continue;
+            }
+            let tier = entry.tier.as_deref().unwrap_or_default();
+            let tier = tier.trim_start_matches('(').trim_end_matches(')');
+            if format.is_banned(tier)
+                && !format
+                    .unbanlist
+                    .iter()
+                    .any(|u| to_id(u) == to_id(&form.name))
+            {
+                push(format!("{} is banned in {} ({})", form.name, format, tier));
+            }
+        }
+        if format.is_banned(&form.name) {
+            push(format!("{} is banned in {}", form.name, format));
+        }
+    }
+
+    if let Some(ref required) = species.required_item
+        && pkmn.item.as_deref().map(to_id) != Some(to_id(required))
+    {
+        push(format!("{} must hold {}", species.name, required));
+    }
+    if let Some(ref required) = species.required_move
+        && !pkmn.moves.iter().any(|m| to_id(m) == to_id(required))
+    {
+        push(format!("{} must know {}", species.name, required));
+    }
+
+    // Ability
+    if let Some(ref ability_name) = pkmn.ability
+        && r#gen >= 3
+    {
+        match dex.get_ability(ability_name) {
+            None => unknown(format!(
+                "{} is not a known ability{}",
+                ability_name,
+                did_you_mean(dex.suggest_ability(ability_name).map(|a| a.name.as_str()))
+            )),
+            Some(ability) if ability.r#gen > r#gen => {
+                push(format!("{} does not exist in Gen {}", ability.name, r#gen))
+            }
+            Some(ability) => {
+                // Hidden abilities arrived in Gen 5
+                let allowed = |s: &Species| {
+                    s.abilities.iter().any(|(slot, a)| {
+                        (slot != "H" || r#gen >= 5) && to_id(a) == to_id(&ability.name)
+                    })
+                };
+                if !allowed(&species) && !base.as_ref().is_some_and(allowed) {
+                    push(format!("{} can't have {}", species.name, ability.name));
+                }
+                if format.is_banned(&ability.name) {
+                    push(format!("{} is banned in {}", ability.name, format));
+                }
+                if format.has_clause(Clause::EvasionAbilities)
+                    && EVASION_ABILITIES.contains(&to_id(&ability.name).as_str())
+                {
+                    push(format!(
+                        "{} is banned by {}",
+                        ability.name,
+                        Clause::EvasionAbilities
+                    ));
+                }
+            }
+        }
+    }
+
+    // Item
+    if let Some(ref item_name) = pkmn.item
+        && r#gen >= 2
+    {
+        match dex.get_item(item_name) {
+            None => unknown(format!(
+                "{} is not a known item{}",
+                item_name,
+                did_you_mean(dex.suggest_item(item_name).map(|i| i.name.as_str()))
+            )),
+            Some(item) if item.r#gen > r#gen => {
+                push(format!("{} does not exist in Gen {}", item.name, r#gen))
+            }
+            Some(item) => {
+                if format.is_banned(&item.name) {
+                    push(format!("{} is banned in {}", item.name, format));
+                }
+                if format.has_clause(Clause::EvasionItems)
+                    && EVASION_ITEMS.contains(&to_id(&item.name).as_str())
+                {
+                    push(format!(
+                        "{} is banned by {}",
+                        item.name,
+                        Clause::EvasionItems
+                    ));
+                }
+                if format.has_clause(Clause::Gems) && item.is_gem {
+                    push(format!("{} is banned by {}", item.name, Clause::Gems));
+                }
+            }
+        }
+    }
+
+    // Moves
+    for move_name in &pkmn.moves {
+        let Some(mv) = dex.get_move(move_name) else {
+            unknown(format!(
+                "{} is not a known move{}",
+                move_name,
+                did_you_mean(dex.suggest_move(move_name).map(|m| m.name.as_str()))
+            ));
+            continue;
+        };
+        if !mv.exists_in(r#gen) {
+            push(format!("{} does not exist in Gen {}", mv.name, r#gen));
+            continue;
+        }
+        let id = to_id(&mv.name);
+        // A species missing from the games is already rejected, its moves needn't be too
+        if dex.obtainable_in(&species, r#gen) && !dex.can_learn(&species, &id, r#gen) {
+            let verb = if dex.is_complete() {
+                "can't learn"
+            } else {
+                "has no known way to learn"
+            };
+            unknown(format!("{} {} {}", species.name, verb, mv.name));
+        }
+        if format.is_banned(&mv.name) {
+            push(format!("{} is banned in {}", mv.name, format));
+        }
+
+        let clause = if mv.ohko.is_some() {
+            Some(Clause::Ohko)
+        } else if mv.target == "self" && mv.boosts.get("evasion").is_some_and(|b| *b > 0) {
+            Some(Clause::EvasionMoves)
+        } else if mv.status.as_deref() == Some("slp") || id == "yawn" {
+            Some(Clause::SleepMoves)
+        } else if id == "swagger" {
+            Some(Clause::Swagger)
+        } else {
+            None
+        };
+        if let Some(clause) = clause.filter(|c| format.has_clause(*c)) {
+            push(format!("{} is banned by {}", mv.name, clause));
+        }
+    }
+
+    problems.extend(unchecked);
+    problems
+}
+
+fn species_of(pkmn: &Pokemon) -> &str {
+    pkmn.species.as_deref().unwrap_or(&pkmn.name)
+}
@@ -0,0 +1,88 @@
+pub mod legality;
+pub mod structure;
+
+use std::fmt;
+
+/// A single reason a team is rejected, attributed to a Pokémon when possible
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub pokemon: Option<String>,
+    pub message: String,
+    /// Something the bundled data can't confirm either way, like a move missing from a partial
+    /// learnset. Reported, but doesn't make the team invalid.
+    pub unverified: bool,
+}
+
+impl Problem {
+    pub fn team(message: impl Into<String>) -> Self {
+        Problem {
+            pokemon: None,
+            message: message.into(),
+            unverified: false,
+        }
+    }
+
+    pub fn pokemon(pokemon: &str, message: impl Into<String>) -> Self {
+        Problem {
+            pokemon: Some(pokemon.to_string()),
+            message: message.into(),
+            unverified: false,
+        }
+    }
+
+    pub fn unverified(pokemon: &str, message: impl Into<String>) -> Self {
+        Problem {
+            unverified: true,
+            ..Problem::pokemon(pokemon, message)
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.pokemon {
+            Some(pokemon) => write!(f, "{}: {}", pokemon, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Joins problems into the newline separated report shown to users and the agent
+pub fn report(problems: &[Problem]) -> String {
+    problems
+        .iter()
+        .map(|p| format!("- {}", p))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The offline verdict on a team: invalid when any problem is confirmed, unverified when the
+/// only problems are ones the data can't settle, valid otherwise
+pub fn verdict(problems: &[Problem]) -> String {
+    let (unverified, invalid): (Vec<Problem>, Vec<Problem>) =
+        problems.iter().cloned().partition(|p| p.unverified);
+    let unchecked = format!(
+        "Not in the offline data, so not checked (use online validation to confirm):\n{}",
+        report(&unverified)
+    );
+    match (invalid.is_empty(), unverified.is_empty()) {
+        (true, true) => "The team is valid.".to_string(),
+        (true, false) => format!("The team is unverified. {}", unchecked),
+        (false, true) => format!("The team is invalid:\n{}", report(&invalid)),
+        (false, false) => format!("The team is invalid:\n{}\n{}", report(&invalid), unchecked),
+    }
+}
+
+pub use legality::validate_legality;
+pub use structure::validate_structure;
+
+use crate::format::Format;
+use crate::parser::team::Team;
+
+/// Runs every offline check: structure (EVs, IVs, levels, clauses) and legality against the
+/// bundled dex (species, abilities, items, learnsets and the format's banlist)
+pub fn validate(team: &Team, format: &Format) -> Vec<Problem> {
+    let mut problems = validate_structure(team, format);
+    problems.extend(validate_legality(team, format));
+    problems
+}
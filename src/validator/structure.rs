@@ -0,0 +1,169 @@
+use super::Problem;
+use crate::format::{Clause, Format};
+use crate::id::to_id;
+use crate::parser::team::{Pokemon, Team};
+use crate::stats::{Nature, Stat};
+use std::collections::HashMap;
+
+const MAX_EV: u16 = 252;
+const MAX_EV_TOTAL: u16 = 510;
+// Gen 1-2 have no EV total, Showdown represents max stat exp as 255
+const MAX_EV_GB: u16 = 255;
+const MAX_IV: u16 = 31;
+const MAX_MOVES: usize = 4;
+
+/// Checks EVs, IVs, natures, levels, moves and team clauses without touching the network.
+/// Every problem found is reported, not just the first.
+pub fn validate_structure(team: &Team, format: &Format) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    if team.pokemon.is_empty() {
+        problems.push(Problem::team("Team has no Pokémon"));
+    }
+    if team.pokemon.len() > format.max_team_size {
+        problems.push(Problem::team(format!(
+            "Team has {} Pokémon, {} allows at most {}",
+            team.pokemon.len(),
+            format,
+            format.max_team_size
+        )));
+    }
+
+    for pkmn in &team.pokemon {
+        problems.extend(validate_pokemon(pkmn, format));
+    }
+
+    if format.has_clause(Clause::Species) {
+        for (species, count) in duplicates(team.pokemon.iter().map(species_of)) {
+            problems.push(Problem::team(format!(
+                "{}: {} appears {} times, only one is allowed",
+                Clause::Species,
+                species,
+                count
+            )));
+        }
+    }
+
+    if format.has_clause(Clause::Item) {
+        let items = team.pokemon.iter().filter_map(|p| p.item.as_deref());
+        for (item, count) in duplicates(items) {
+            problems.push(Problem::team(format!(
+                "{}: {} is held {} times, only one is allowed",
+                Clause::Item,
+                item,
+                count
+            )));
+        }
+    }
+
+    problems
+}
+
+/// Per-Pokémon checks, independent of the rest of the team
+pub fn validate_pokemon(pkmn: &Pokemon, format: &Format) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let name = species_of(pkmn);
+    let mut push = |message: String| problems.push(Problem::pokemon(name, message));
+
+    // EVs
+    let max_ev = if format.r#gen <= 2 { MAX_EV_GB } else { MAX_EV };
+    for stat in Stat::ALL {
+        let ev = pkmn.evs.get(stat);
+        if ev > max_ev {
+            push(format!(
+                "{} EVs in {} exceed the maximum of {}",
+                ev, stat, max_ev
+            ));
+        }
+    }
+    if format.r#gen >= 3 && pkmn.evs.total() > MAX_EV_TOTAL {
+        push(format!(
+            "has {} total EVs, the maximum is {}",
+            pkmn.evs.total(),
+            MAX_EV_TOTAL
+        ));
+    }
+
+    // IVs
+    if let Some(ref ivs) = pkmn.ivs {
+        for stat in Stat::ALL {
+            let iv = ivs.get(stat);
+            if iv > MAX_IV {
+                push(format!(
+                    "{} IVs in {} exceed the maximum of {}",
+                    iv, stat, MAX_IV
+                ));
+            }
+        }
+    }
+
+    // Nature
+    if let Some(ref nature) = pkmn.nature {
+        if format.r#gen <= 2 {
+            push(format!("natures don't exist in Gen {}", format.r#gen));
+        } else if nature.parse::<Nature>().is_err() {
+            push(format!("{} is not a valid nature", nature));
+        }
+    }
+
+    // Gen 1-2 predate abilities, Gen 1 predates held items
+    if format.r#gen <= 2 && pkmn.ability.is_some() {
+        push(format!("abilities don't exist in Gen {}", format.r#gen));
+    }
+    if format.r#gen == 1 && pkmn.item.is_some() {
+        push("held items don't exist in Gen 1".to_string());
+    }
+
+    // Level
+    let level = pkmn.effective_level();
+    if level == 0 || level > format.max_level {
+        push(format!(
+            "level {} is outside the allowed range 1-{}",
+            level, format.max_level
+        ));
+    }
+
+    // Moves
+    if pkmn.moves.is_empty() {
+        push("has no moves".to_string());
+    }
+    if pkmn.moves.len() > MAX_MOVES {
+        push(format!(
+            "has {} moves, at most {} are allowed",
+            pkmn.moves.len(),
+            MAX_MOVES
+        ));
+    }
+    for (mv, count) in duplicates(pkmn.moves.iter().map(String::as_str)) {
+        push(format!("has {} {} times", mv, count));
+    }
+
+    problems
+}
+
+fn species_of(pkmn: &Pokemon) -> &str {
+    pkmn.species.as_deref().unwrap_or(&pkmn.name)
+}
+
+/// Returns names that occur more than once (compared by id), in first-seen order
+fn duplicates<'a>(names: impl Iterator<Item = &'a str>) -> Vec<(&'a str, usize)> {
+    let mut counts: Vec<(&str, String, usize)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for name in names {
+        let key = to_id(name);
+        match index.get(&key) {
+            Some(&i) => counts[i].2 += 1,
+            None => {
+                index.insert(key.clone(), counts.len());
+                counts.push((name, key, 1));
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, _, count)| *count > 1)
+        .map(|(name, _, count)| (name, count))
+        .collect()
+}
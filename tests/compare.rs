@@ -0,0 +1,70 @@
+use pokebrains::{compare, type_matchups};
+
+#[tokio::test]
+async fn type_matchups_of_types_and_pokemon() {
+    let ferrothorn = type_matchups("Ferrothorn", 9).await.unwrap();
+    assert_eq!(ferrothorn.types, ["Grass", "Steel"]);
+    assert_eq!(ferrothorn.defense["Fire"], 4.0);
+    assert_eq!(ferrothorn.defense["Poison"], 0.0);
+    assert_eq!(
+        ferrothorn.super_effective_against,
+        ["Water", "Ice", "Ground", "Rock", "Fairy"]
+    );
+    let text = ferrothorn.to_readable_form();
+    assert!(
+        text.starts_with("Ferrothorn (Grass/Steel) in Gen 9\nWeak to: Fire (4x), Fighting (2x)\n")
+    );
+    assert!(text.contains("Immune to: Poison (0x)\n"));
+
+    let swampert = type_matchups("water/ground", 5).await.unwrap();
+    assert_eq!(swampert.name, "Water/Ground");
+    assert!(
+        swampert
+            .to_readable_form()
+            .starts_with("Water/Ground in Gen 5\nWeak to: Grass (4x)\n")
+    );
+    assert_eq!(
+        type_matchups("water ground", 5).await.unwrap().defense,
+        swampert.defense
+    );
+
+    // Steel resisted Ghost and Dark before Gen 6; Fairy didn't exist
+    let steel = type_matchups("Steel", 5).await.unwrap();
+    assert_eq!(steel.defense["Ghost"], 0.5);
+    assert!(!steel.defense.contains_key("Fairy"));
+    assert!(type_matchups("Fairy", 5).await.is_err());
+    assert!(type_matchups("Starmask", 9).await.is_err());
+}
+
+#[tokio::test]
+async fn compares_side_by_side() {
+    let comparison = compare("Excadrill", "ferrothorn", 9).await.unwrap();
+    let [excadrill, ferrothorn] = &comparison.pokemon;
+    assert_eq!(
+        (excadrill.name.as_str(), ferrothorn.name.as_str()),
+        ("Excadrill", "Ferrothorn")
+    );
+    assert_eq!(comparison.stab[0]["Ground"], 1.0);
+    assert_eq!(comparison.stab[1]["Grass"], 1.0);
+
+    let text = comparison.to_readable_form();
+    assert!(text.starts_with(
+        "Gen 9         Excadrill     Ferrothorn\n\
+         Types         Ground/Steel  Grass/Steel\n"
+    ));
+    assert!(text.contains("\nDef           60            131*\n"));
+    assert!(text.contains("Excadrill's STAB on Ferrothorn: Ground 1x, Steel 0.5x\n"));
+    assert!(text.ends_with("Excadrill outspeeds Ferrothorn (88 vs 20)\n"));
+
+    // Clefable was Normal type in Gen 5
+    let gen5 = compare("Clefable", "Gengar", 5).await.unwrap();
+    assert_eq!(gen5.stab[0]["Normal"], 0.0);
+    assert!(compare("Gholdengo", "Gengar", 8).await.is_err());
+
+    // Near misses of bundled names are suggested, not swapped in
+    let error = compare("Garchmop", "Gengar", 9).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unknown Pokemon: Garchmop, did you mean Garchomp?"
+    );
+}
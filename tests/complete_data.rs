@@ -0,0 +1,131 @@
+//! With a full export, something missing from the data is an answer rather than a gap. These
+//! tests mark the bundled files complete through a data directory holding only a manifest.
+
+use pokebrains::data::DATA_DIR_ENV;
+use pokebrains::format::Format;
+use pokebrains::search::{SpeciesQuery, report, search};
+use pokebrains::validator::{Problem, validate, verdict};
+use pokebrains::{Team, can_learn, fetch_move_info};
+use std::sync::Once;
+
+// The README team's mistakes that only a complete learnset can rule out
+static README_TEAM: &str = "#\
+Ferrothorn @ Leftovers
+Ability: Iron Barbs
+EVs: 252 HP / 252 Def / 4 SpD
+Relaxed Nature
+- Leech Seed
+- Spiky Shield
+- Sludge Bomb
+- Swords Dance
+
+Togekiss @ Choice Band
+Ability: Serene Grace
+EVs: 252 HP / 252 Atk / 4 Spe
+Adamant Nature
+- Bullet Seed
+- Close Combat
+- Swagger
+- Play Rough
+
+Gligar @ Life Orb
+Ability: Sand Force
+EVs: 252 HP / 4 Def / 252 SpD
+Sassy Nature
+- Swords Dance
+- Earthquake
+- Rock Slide
+- Slack Off
+";
+
+fn complete_data() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("pokebrains-complete-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("manifest.json"), r#"{"complete": true}"#).unwrap();
+        // Every test calls this before anything reads the shared dex
+        unsafe { std::env::set_var(DATA_DIR_ENV, &dir) };
+    });
+}
+
+fn rejected(problems: &[Problem], message: &str) {
+    let problem = problems.iter().find(|p| p.to_string().contains(message));
+    assert!(
+        problem.is_some_and(|p| !p.unverified),
+        "no rejection containing '{}' in {:#?}",
+        message,
+        problems
+    );
+}
+
+#[tokio::test]
+async fn readme_team_is_invalid_in_gen5() {
+    complete_data();
+    let team = Team::deserialize(README_TEAM).await;
+    let problems = validate(&team, &Format::from_id("gen5ou"));
+
+    rejected(&problems, "Ferrothorn can't learn Sludge Bomb");
+    rejected(&problems, "Togekiss can't learn Close Combat");
+    rejected(&problems, "Gligar can't learn Slack Off");
+    assert!(problems.iter().all(|p| !p.unverified), "{:#?}", problems);
+    assert!(verdict(&problems).starts_with("The team is invalid:\n"));
+}
+
+#[test]
+fn missing_learnset_entries_are_answers() {
+    complete_data();
+    for (species, r#move) in [("Ferrothorn", "Sludge Bomb"), ("Togekiss", "Close Combat")] {
+        let check = can_learn(species, r#move, 5).unwrap();
+        assert_eq!(check.learnable, Some(false), "{} {}", species, r#move);
+        assert_eq!(
+            check.to_readable_form(),
+            format!("{} cannot learn {} in Gen 5\n", species, r#move)
+        );
+    }
+}
+
+#[test]
+fn searches_cover_everything() {
+    complete_data();
+    let query = SpeciesQuery::parse("gen9 ground steel close combat").unwrap();
+    assert!(!report(&query, &search(&query).unwrap()).contains("offline data"));
+}
+
+#[tokio::test]
+async fn readme_team_is_invalid_in_gen6() {
+    complete_data();
+    let team = Team::deserialize(README_TEAM).await;
+    let problems = validate(&team, &Format::from_id("gen6ou"));
+
+    rejected(&problems, "Ferrothorn can't learn Spiky Shield");
+    rejected(&problems, "Ferrothorn can't learn Sludge Bomb");
+    rejected(&problems, "Togekiss can't learn Play Rough");
+    rejected(&problems, "Togekiss can't learn Bullet Seed");
+}
+
+#[tokio::test]
+async fn unknown_names_are_rejected() {
+    complete_data();
+    let team = Team::deserialize(
+        "#\
+Garchmop @ Leftovers
+Ability: Rough Skin
+- Earthquake
+",
+    )
+    .await;
+    let problems = validate(&team, &Format::from_id("gen9ou"));
+    rejected(
+        &problems,
+        "Garchmop is not a known Pokémon, did you mean Garchomp?",
+    );
+    // Without asking PokeAPI
+    assert_eq!(
+        fetch_move_info("Knock Of", 9)
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Unknown move: Knock Of, did you mean Knock Off?"
+    );
+}
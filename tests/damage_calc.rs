@@ -0,0 +1,165 @@
+use pokebrains::damage::{Combatant, Field, Screens, calculate};
+use pokebrains::types::Type;
+use pokebrains::{BattleEvents, Pokemon, Team};
+
+fn pokemon(json: &str) -> Pokemon {
+    Team::from_json(&format!(r#"{{"pokemon": [{}]}}"#, json))
+        .unwrap()
+        .pokemon
+        .remove(0)
+}
+
+fn dragonite(item: &str) -> Combatant {
+    Combatant::from(pokemon(&format!(
+        r#"{{"name": "Dragonite", "item": "{}", "ability": "Multiscale",
+            "evs": {{"atk": 252, "spe": 252}}, "nature": "Adamant"}}"#,
+        item
+    )))
+}
+
+fn tyranitar() -> Combatant {
+    Combatant::from(pokemon(
+        r#"{"name": "Tyranitar", "evs": {"hp": 252}, "nature": "Careful"}"#,
+    ))
+}
+
+#[test]
+fn rolls_and_description() {
+    let band = calculate(
+        9,
+        &dragonite("Choice Band"),
+        &tyranitar(),
+        "Outrage",
+        &Field::default(),
+    )
+    .unwrap();
+    assert_eq!(band.rolls.len(), 16);
+    assert_eq!((band.min(), band.max()), (304, 358));
+    assert_eq!(
+        band.to_string(),
+        "252+ Atk Choice Band Dragonite Outrage vs. 252 HP / 0 Def Tyranitar: \
+         304-358 (75.2 - 88.6%) -- guaranteed 2HKO"
+    );
+
+    // Gen 4 floors at each step instead of chaining, and lands on the same numbers here
+    let gen4 = calculate(
+        4,
+        &dragonite("Choice Band"),
+        &tyranitar(),
+        "Outrage",
+        &Field::default(),
+    )
+    .unwrap();
+    assert_eq!((gen4.min(), gen4.max()), (304, 358));
+
+    assert!(
+        calculate(
+            2,
+            &dragonite("Choice Band"),
+            &tyranitar(),
+            "Outrage",
+            &Field::default()
+        )
+        .is_err()
+    );
+    assert!(calculate(9, &dragonite(""), &tyranitar(), "Roost", &Field::default()).is_err());
+}
+
+#[test]
+fn field_crits_and_ko_chances() {
+    let reflect = Field {
+        screens: Screens {
+            reflect: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let screened = calculate(
+        9,
+        &dragonite("Choice Band"),
+        &tyranitar(),
+        "Outrage",
+        &reflect,
+    )
+    .unwrap();
+    assert_eq!((screened.min(), screened.max()), (152, 179));
+    assert!(screened.description.ends_with("through Reflect"));
+    // Crits go through screens at 1.5x
+    assert_eq!(screened.crit_rolls.last(), Some(&537));
+
+    // 204-240 unboosted; at 222 HP, half the rolls KO
+    let mut hurt = tyranitar();
+    hurt.hp = Some(0.55);
+    let plain = calculate(
+        9,
+        &dragonite("Leftovers"),
+        &hurt,
+        "Outrage",
+        &Field::default(),
+    )
+    .unwrap();
+    assert_eq!((plain.min(), plain.max()), (204, 240));
+    assert_eq!(plain.current_hp, 222);
+    assert_eq!(plain.ko_chance(1), 0.5);
+    assert_eq!(plain.ko_text(), "50.0% chance to OHKO");
+
+    let mut tera = dragonite("Leftovers");
+    tera.tera_type = Some(Type::Dragon);
+    let tera = calculate(9, &tera, &tyranitar(), "Outrage", &Field::default()).unwrap();
+    assert_eq!(tera.max(), 320);
+    assert!(tera.description.contains("Tera Dragon Dragonite"));
+
+    let latios = Combatant::from(pokemon(r#"{"name": "Latios", "ability": "Levitate"}"#));
+    let immune = calculate(9, &dragonite(""), &latios, "Earthquake", &Field::default()).unwrap();
+    assert_eq!(immune.max(), 0);
+    assert_eq!(immune.ko(), None);
+    assert_eq!(immune.ko_text(), "no damage");
+}
+
+static BATTLE: &str = "\
+|player|p1|me|1|
+|player|p2|foe|1|
+|gen|9
+|tier|[Gen 9] OU
+|poke|p1|Dragonite, M|
+|poke|p2|Tyranitar, M|
+|start
+|switch|p1a: Dragonite|Dragonite, M|100/100
+|switch|p2a: Tyranitar|Tyranitar, M|100/100
+|-weather|Sandstorm|[from] ability: Sand Stream|[of] p2a: Tyranitar
+|turn|1
+|move|p1a: Dragonite|Dragon Dance|p1a: Dragonite
+|-boost|p1a: Dragonite|atk|1
+|-boost|p1a: Dragonite|spe|1
+|move|p2a: Tyranitar|Stealth Rock|p1a: Dragonite
+|turn|2";
+
+#[test]
+fn calculations_from_the_battle_state() {
+    let team = Team::from_json(
+        r#"{"pokemon": [{"name": "Dragonite", "item": "Heavy-Duty Boots",
+            "ability": "Multiscale", "evs": {"atk": 252, "spe": 252}, "nature": "Adamant",
+            "moves": ["Dragon Dance", "Outrage", "Earthquake", "Roost"]}]}"#,
+    )
+    .unwrap();
+    let mut events = BattleEvents::new("me".to_string());
+    events.opponents.set_own_team(&team);
+    for line in BATTLE.lines() {
+        events.add_event(line);
+    }
+
+    let outrage = events.opponents.damage_to_opponent("Outrage").unwrap();
+    assert!(
+        outrage
+            .description
+            .starts_with("+1 252+ Atk Dragonite Outrage vs.")
+    );
+    assert!(outrage.description.ends_with("Tyranitar in Sand"));
+
+    // Damaging moves only, strongest first: super effective Earthquake beats STAB Outrage
+    let all = events.opponents.active_damage();
+    assert_eq!(all.len(), 2);
+    assert!(all[0].description.contains("Earthquake"));
+    assert!(all[1].description.contains("Outrage"));
+    assert!(events.opponents.damage_from_opponent("Crunch").is_ok());
+}
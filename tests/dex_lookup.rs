@@ -0,0 +1,132 @@
+use pokebrains::data::Dex;
+use pokebrains::stats::BaseStats;
+use pokebrains::{fetch_pokemon_info, fetch_pokemon_info_in};
+
+#[test]
+fn lookups_by_generation() {
+    let dex = Dex::get();
+    // Clefable was Normal before Fairy existed, with 85 base SpA before Gen 7
+    let gen5 = dex.species_in("Clefable", 5).unwrap();
+    assert_eq!(gen5.types, ["Normal"]);
+    assert_eq!(gen5.base_stats.spa, 85);
+    let gen9 = dex.species_in("clefable", 9).unwrap();
+    assert_eq!(gen9.types, ["Fairy"]);
+    assert_eq!(gen9.base_stats.spa, 95);
+    assert!(dex.species_in("Gholdengo", 8).is_none());
+
+    assert!(dex.move_in("U-turn", 3).is_none());
+    assert_eq!(dex.move_in("uturn", 4).unwrap().base_power, 70);
+    assert!(dex.item_in("Choice Band", 1).is_none());
+    assert!(dex.item_in("Choice Band", 3).is_some());
+    assert!(dex.ability_in("Levitate", 2).is_none());
+    assert_eq!(dex.ability_in("Levitate", 3).unwrap().name, "Levitate");
+}
+
+#[test]
+fn bundled_data_is_a_subset() {
+    let dex = Dex::bundled();
+    assert!(!dex.is_complete());
+    // Latias isn't bundled, and isn't mistaken for Latios
+    assert!(dex.resolve_species("Latias").is_none());
+
+    let dir = std::env::temp_dir().join(format!("pokebrains-dex-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("pokedex.json"), "{}").unwrap();
+    assert!(!Dex::load(&dir).unwrap().is_complete());
+    for name in [
+        "moves.json",
+        "items.json",
+        "abilities.json",
+        "learnsets.json",
+    ] {
+        std::fs::write(dir.join(name), "{}").unwrap();
+    }
+    let full = Dex::load(&dir).unwrap();
+    assert!(full.is_complete());
+    assert!(full.get_species("Clefable").is_none());
+    // A manifest has the last word
+    std::fs::write(dir.join("manifest.json"), r#"{"complete": false}"#).unwrap();
+    assert!(!Dex::load(&dir).unwrap().is_complete());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn learnable_moves_follow_generation() {
+    let dex = Dex::get();
+    let clefable = dex.get_species("Clefable").unwrap();
+    let names = |r#gen| -> Vec<String> {
+        dex.learnable_moves(clefable, r#gen)
+            .iter()
+            .map(|m| m.name.clone())
+            .collect()
+    };
+    let gen9 = names(9);
+    assert!(gen9.contains(&"Calm Mind".to_string()));
+    let mut sorted = gen9.clone();
+    sorted.sort();
+    assert_eq!(gen9, sorted);
+    // Dazzling Gleam is a Gen 6 TM
+    assert!(!names(5).contains(&"Dazzling Gleam".to_string()));
+    assert!(names(6).contains(&"Dazzling Gleam".to_string()));
+}
+
+#[tokio::test]
+async fn pokemon_details_come_from_bundled_data() {
+    // No network: bundled species never reach PokeAPI
+    let info = fetch_pokemon_info("Alolan Muk").await.unwrap();
+    assert_eq!(info.name, "Muk-Alola");
+    assert_eq!(info.base_stats(), BaseStats::new(105, 105, 75, 65, 100, 50));
+    let text = info.to_readable_form();
+    assert!(text.contains("Types:   poison, dark"));
+    assert!(text.contains("Generation: GENERATION-VII"));
+    assert!(text.contains("Poison Touch"));
+
+    let gengar = fetch_pokemon_info("Gengar").await.unwrap();
+    let abilities: Vec<&str> = gengar
+        .abilities
+        .iter()
+        .map(|a| a.ability.name.as_str())
+        .collect();
+    assert_eq!(abilities, ["Cursed Body"]);
+    assert!(gengar.abilities[0].effect.is_some());
+}
+
+#[tokio::test]
+async fn pokemon_details_by_generation() {
+    let gen5 = fetch_pokemon_info_in("Clefable", 5).await.unwrap();
+    let text = gen5.to_readable_form();
+    assert!(text.contains("Types:   normal\n"));
+    assert!(text.contains("Data as of: Gen 5\n"));
+    // The bundled learnsets are a subset, so the list says it may be missing moves
+    assert!(text.contains("(Partial: only the moves in the bundled data are listed)\n"));
+    assert_eq!(gen5.base_stats().spa, 85);
+    assert!(gen5.moves.iter().all(|m| m.r#move.name != "Dazzling Gleam"));
+    let gen9 = fetch_pokemon_info_in("Clefable", 9).await.unwrap();
+    assert!(gen9.to_readable_form().contains("Types:   fairy\n"));
+
+    // Gengar had Levitate before Gen 7, and no abilities at all in Gen 1
+    let gengar = fetch_pokemon_info_in("Gengar", 6).await.unwrap();
+    assert_eq!(gengar.abilities[0].ability.name, "Levitate");
+    assert!(
+        fetch_pokemon_info_in("Gengar", 1)
+            .await
+            .unwrap()
+            .abilities
+            .is_empty()
+    );
+    // Hidden abilities start in Gen 5
+    let clefable = fetch_pokemon_info_in("Clefable", 4).await.unwrap();
+    assert!(clefable.abilities.iter().all(|a| !a.is_hidden));
+
+    let error = fetch_pokemon_info_in("Gholdengo", 8).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Gholdengo does not exist in Gen 8; it was introduced in Gen 9"
+    );
+    // Megas exist in Gen 9 data but can't be used
+    let mega = fetch_pokemon_info_in("Charizard-Mega-X", 9).await.unwrap();
+    assert!(
+        mega.to_readable_form()
+            .contains("Availability: not obtainable in Gen 9 games (Past)\n")
+    );
+}
@@ -0,0 +1,84 @@
+use pokebrains::Team;
+use pokebrains::data::Dex;
+use pokebrains::data::formes::{pokeapi_slug, showdown_name};
+
+#[test]
+fn showdown_names_to_pokeapi_slugs() {
+    let cases = [
+        ("Charizard-Mega-X", "charizard-mega-x"),
+        ("Keldeo", "keldeo-ordinary"),
+        ("Keldeo-Resolute", "keldeo-resolute"),
+        ("Thundurus", "thundurus-incarnate"),
+        ("Thundurus-Therian", "thundurus-therian"),
+        ("Landorus-Therian", "landorus-therian"),
+        ("Rotom-Wash", "rotom-wash"),
+        ("Aegislash", "aegislash-shield"),
+        ("Necrozma-Dusk-Mane", "necrozma-dusk"),
+        ("Tauros-Paldea-Blaze", "tauros-paldea-blaze-breed"),
+        // Cosmetic and item-driven formes share one entry
+        ("Gastrodon-East", "gastrodon"),
+        ("Arceus-Fire", "arceus"),
+        ("Mr. Mime", "mr-mime"),
+        ("Type: Null", "type-null"),
+        ("Flabébé", "flabebe"),
+        ("Zygarde-10%", "zygarde-10"),
+        ("Ho-Oh", "ho-oh"),
+        ("Maushold", "maushold-family-of-three"),
+        ("Maushold-Four", "maushold-family-of-four"),
+        ("Pikachu-Original", "pikachu-original-cap"),
+        ("Pikachu-Alola", "pikachu-alola-cap"),
+        ("Pikachu", "pikachu"),
+        ("Cherrim-Sunshine", "cherrim"),
+    ];
+    for (name, slug) in cases {
+        assert_eq!(pokeapi_slug(name), slug, "{}", name);
+    }
+
+    assert_eq!(showdown_name("thundurus-incarnate"), "Thundurus");
+    assert_eq!(showdown_name("necrozma-dusk"), "Necrozma-Dusk-Mane");
+    assert_eq!(showdown_name("charizard-mega-x"), "Charizard-Mega-X");
+    assert_eq!(showdown_name("maushold-family-of-four"), "Maushold-Four");
+    assert_eq!(showdown_name("pikachu-world-cap"), "Pikachu-World");
+}
+
+#[tokio::test]
+async fn team_species_round_trip() {
+    let dex = Dex::get();
+    for file in ["./teams/GEN_V.txt", "./teams/GEN_VI.txt"] {
+        let team = Team::deserialize_from_file(file).await.unwrap();
+        for pkmn in &team.pokemon {
+            let name = pkmn.species.as_deref().unwrap_or(&pkmn.name);
+            let species = dex.get_species(name).unwrap();
+            let back = dex.resolve_species(&pokeapi_slug(name)).unwrap();
+            assert_eq!(back.name, species.name, "{}", name);
+        }
+    }
+    assert_eq!(
+        dex.resolve_species("landorus-incarnate").unwrap().name,
+        "Landorus"
+    );
+    assert_eq!(
+        dex.resolve_species("keldeo-ordinary").unwrap().name,
+        "Keldeo"
+    );
+}
+
+#[test]
+fn cosmetic_formes_share_their_base_entry() {
+    let dir = std::env::temp_dir().join(format!("pokebrains-formes-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("pokedex.json"),
+        r#"{"gastrodon": {"num": 423, "name": "Gastrodon", "types": ["Water", "Ground"],
+            "baseStats": {"hp": 111, "atk": 83, "def": 68, "spa": 92, "spd": 82, "spe": 39},
+            "abilities": {"0": "Sticky Hold", "1": "Storm Drain", "H": "Sand Force"},
+            "weightkg": 29.9, "gen": 4, "cosmeticFormes": ["Gastrodon-East"]}}"#,
+    )
+    .unwrap();
+    let dex = Dex::load(&dir).unwrap();
+
+    assert_eq!(dex.get_species("Gastrodon-East").unwrap().name, "Gastrodon");
+    assert_eq!(dex.species_name("gastrodon east"), Some("Gastrodon-East"));
+    assert_eq!(dex.species_name("Gastrodon"), Some("Gastrodon"));
+    assert!(dex.get_species("Gastrodon-West").is_none());
+}
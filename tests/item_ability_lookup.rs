@@ -0,0 +1,50 @@
+use pokebrains::{fetch_ability_info, fetch_item_info};
+
+#[tokio::test]
+async fn item_details() {
+    let leftovers = fetch_item_info("Leftovers", 9).await.unwrap();
+    assert_eq!(leftovers.r#gen, 2);
+    assert_eq!(leftovers.fling_power, Some(10));
+    assert!(leftovers.effect.contains("1/16"));
+    assert!(leftovers.mega_stone.is_none() && leftovers.z_crystal.is_none());
+
+    let stone = fetch_item_info("Charizardite X", 6).await.unwrap();
+    assert_eq!(stone.mega_stone.as_deref(), Some("Charizard-Mega-X"));
+    assert_eq!(stone.users, ["Charizard"]);
+    assert_eq!(stone.fling_power, Some(80));
+    let text = stone.to_readable_form();
+    assert!(text.contains("Mega Stone: evolves into Charizard-Mega-X\n"));
+    assert!(text.contains("Only works for: Charizard\n"));
+
+    let z = fetch_item_info("Fairium Z", 7).await.unwrap();
+    assert_eq!(z.z_crystal.as_deref(), Some("Fairy"));
+    assert!(fetch_item_info("Sitrus Berry", 9).await.unwrap().is_berry);
+
+    // Held items start in Gen 2; typos get a suggestion
+    assert!(fetch_item_info("Leftovers", 1).await.is_err());
+    assert_eq!(
+        fetch_item_info("Leftover", 9)
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Unknown item: Leftover, did you mean Leftovers?"
+    );
+    assert!(fetch_item_info("Charizardite X", 5).await.is_err());
+}
+
+#[tokio::test]
+async fn ability_details() {
+    let levitate = fetch_ability_info("levitate", 9).await.unwrap();
+    assert_eq!(levitate.r#gen, 3);
+    assert_eq!(
+        levitate.to_readable_form(),
+        "Ability: Levitate\nGeneration: 3\nEffect: This Pokemon is immune to Ground; \
+         Gravity/Ingrain/Smack Down/Iron Ball nullify it.\n"
+    );
+    assert!(fetch_ability_info("Levitate", 2).await.is_err());
+    assert!(fetch_ability_info("Cursed Body", 4).await.is_err());
+
+    // Not bundled, so PokeAPI is asked, and with no close bundled name the error is its own
+    let error = fetch_ability_info("Starmask", 9).await.unwrap_err();
+    assert_eq!(error.to_string(), "Failed to fetch data for Starmask");
+}
@@ -0,0 +1,102 @@
+use pokebrains::lookup::LearnMethod;
+use pokebrains::{can_learn, fetch_learnset};
+
+#[test]
+fn can_learn_by_generation() {
+    // The README team's mistakes, which the partial bundled learnsets can't rule out
+    let check = can_learn("Ferrothorn", "Sludge Bomb", 5).unwrap();
+    assert!(check.learnable.is_none() && check.sources.is_empty());
+    assert_eq!(
+        check.to_readable_form(),
+        "Unknown whether Ferrothorn can learn Sludge Bomb in Gen 5: the bundled learnset is \
+         partial and doesn't list it\n"
+    );
+    // Ferrothorn isn't in the Gen 9 games, so it learns nothing there
+    let check = can_learn("Ferrothorn", "Leech Seed", 9).unwrap();
+    assert_eq!(check.learnable, Some(false));
+    assert_eq!(
+        check.to_readable_form(),
+        "Ferrothorn cannot learn Leech Seed in Gen 9: Ferrothorn is not obtainable in Gen 9 \
+         games\n"
+    );
+    assert_eq!(
+        can_learn("Ferrothorn", "Leech Seed", 8).unwrap().learnable,
+        Some(true)
+    );
+    // Latios learns Energy Ball by TM in Gen 5, it just isn't bundled
+    assert_eq!(
+        can_learn("Latios", "Energy Ball", 5).unwrap().learnable,
+        None
+    );
+
+    let check = can_learn("gengar", "sludgebomb", 9).unwrap();
+    assert_eq!(check.learnable, Some(true));
+    assert_eq!(check.learnset_of, None);
+    assert_eq!(
+        (check.species.as_str(), check.r#move.as_str()),
+        ("Gengar", "Sludge Bomb")
+    );
+    assert_eq!(check.sources[0].r#gen, 9);
+    assert_eq!(check.sources[0].method, LearnMethod::Machine);
+    assert!(
+        check
+            .to_readable_form()
+            .starts_with("Gengar can learn Sludge Bomb in Gen 9 by:\n  - Gen 9 TM\n")
+    );
+
+    // Moonblast is a Gen 6 move Clefable learns by level-up
+    let moonblast = can_learn("Clefable", "Moonblast", 6).unwrap();
+    assert!(moonblast.sources.iter().all(|s| s.r#gen <= 6));
+    assert_eq!(moonblast.sources[0].method, LearnMethod::LevelUp);
+    assert!(can_learn("Clefable", "Moonblast", 5).is_err());
+
+    let error = can_learn("Gholdengo", "Make It Rain", 8).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Gholdengo does not exist in Gen 8; it was introduced in Gen 9"
+    );
+    assert!(can_learn("Gengar", "Starmask", 9).is_err());
+}
+
+#[test]
+fn formes_without_a_learnset_say_so() {
+    let check = can_learn("Muk-Alola", "Knock Off", 9).unwrap();
+    assert_eq!(check.learnable, Some(true));
+    assert_eq!(check.learnset_of.as_deref(), Some("Muk"));
+    assert!(
+        check
+            .to_readable_form()
+            .ends_with("(Using Muk's learnset, Muk-Alola has none of its own)\n")
+    );
+
+    let learnset = fetch_learnset("Muk-Alola", 9).unwrap();
+    assert!(learnset.partial);
+    assert_eq!(learnset.learnset_of.as_deref(), Some("Muk"));
+}
+
+#[test]
+fn learnsets_grouped_by_method() {
+    let learnset = fetch_learnset("Clefable", 9).unwrap();
+    assert_eq!(learnset.species, "Clefable");
+    assert!(learnset.level_up.contains(&"Moonblast".to_string()));
+    assert!(learnset.machine.contains(&"Calm Mind".to_string()));
+    assert!(!learnset.machine.contains(&"Moonblast".to_string()));
+
+    let text = learnset.to_readable_form();
+    assert!(text.starts_with(
+        "Gen 9 learnset of Clefable\n\
+         (Partial: only the moves in the bundled data are listed)\n\
+         Level-up: "
+    ));
+    assert!(text.contains("\nTM: "));
+
+    // Dazzling Gleam is a Gen 6 TM
+    let gen5 = fetch_learnset("Clefable", 5).unwrap();
+    assert!(
+        gen5.machine
+            .iter()
+            .chain(&gen5.level_up)
+            .chain(&gen5.tutor)
+            .all(|m| m != "Dazzling Gleam")
+    );
+}
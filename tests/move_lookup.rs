@@ -0,0 +1,63 @@
+use pokebrains::fetch_move_info;
+
+#[tokio::test]
+async fn move_details() {
+    let flare_blitz = fetch_move_info("Flare Blitz", 9).await.unwrap();
+    assert_eq!(flare_blitz.r#type, "Fire");
+    assert_eq!(flare_blitz.category, "Physical");
+    assert_eq!(
+        (flare_blitz.base_power, flare_blitz.accuracy),
+        (120, Some(100))
+    );
+    assert_eq!(flare_blitz.r#gen, 4);
+    assert!(flare_blitz.flags.contains(&"contact".to_string()));
+    assert_eq!(
+        flare_blitz.effects,
+        ["10% chance of burn", "Recoil: 33% of the damage dealt"]
+    );
+
+    let close_combat = fetch_move_info("closecombat", 9).await.unwrap();
+    assert_eq!(close_combat.effects, ["Lowers the user's Def and SpD by 1"]);
+    let fire_fang = fetch_move_info("Fire Fang", 9).await.unwrap();
+    assert_eq!(
+        fire_fang.effects,
+        ["10% chance of burn", "10% chance of flinching"]
+    );
+    let swords_dance = fetch_move_info("Swords Dance", 9).await.unwrap();
+    assert_eq!(swords_dance.accuracy, None);
+    assert_eq!(swords_dance.effects, ["Raises the user's Atk by 2"]);
+    assert_eq!(
+        fetch_move_info("Bullet Seed", 9).await.unwrap().effects,
+        ["Hits 2-5 times"]
+    );
+
+    // Typos get a suggestion, missing generations an explanation
+    assert_eq!(
+        fetch_move_info("Knock Of", 9)
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Unknown move: Knock Of, did you mean Knock Off?"
+    );
+    let error = fetch_move_info("U-turn", 3).await.unwrap_err();
+    assert_eq!(error.to_string(), "U-turn is not available in Gen 3");
+    assert!(fetch_move_info("Totally Real Move", 9).await.is_err());
+}
+
+#[tokio::test]
+async fn readable_move_details() {
+    let text = fetch_move_info("Scald", 9)
+        .await
+        .unwrap()
+        .to_readable_form();
+    assert!(text.starts_with("Move: Scald\nType: Water | Category: Special | Generation: 5\n"));
+    assert!(text.contains("Power: 80 | Accuracy: 100% | PP: 15 | Priority: +0\n"));
+    assert!(text.contains("  - 30% chance of burn\n"));
+
+    let text = fetch_move_info("Stealth Rock", 9)
+        .await
+        .unwrap()
+        .to_readable_form();
+    assert!(text.contains("Accuracy: never misses"));
+    assert!(text.contains("  - Sets up stealthrock\n"));
+}
@@ -0,0 +1,148 @@
+use pokebrains::Team;
+use pokebrains::data::Dex;
+use pokebrains::format::Format;
+use pokebrains::id::{closest, distance, to_id};
+use pokebrains::validator::validate_legality;
+
+#[test]
+fn showdown_ids() {
+    assert_eq!(to_id("Mr. Mime"), "mrmime");
+    assert_eq!(to_id("Charizard-Mega-X"), "charizardmegax");
+    assert_eq!(to_id("King's Rock"), "kingsrock");
+    assert_eq!(to_id("Hidden Power [Ice]"), "hiddenpowerice");
+}
+
+#[test]
+fn typo_distance() {
+    assert_eq!(distance("ferrothorn", "ferrothorn"), 0);
+    assert_eq!(distance("ferrothorne", "ferrothorn"), 1);
+    // Swapped neighbours count once
+    assert_eq!(distance("garchmop", "garchomp"), 1);
+    assert_eq!(distance("", "abc"), 3);
+
+    let known = ["leftovers", "lifeorb", "lumberry"];
+    assert_eq!(closest("Leftover", known), Some("leftovers"));
+    assert_eq!(closest("Lum Bery", known), Some("lumberry"));
+    assert_eq!(closest("Choice Scarf", known), None);
+}
+
+#[test]
+fn dex_resolves_loose_names() {
+    let dex = Dex::get();
+    let species = |name: &str| dex.resolve_species(name).map(|s| s.name.as_str());
+    assert_eq!(species("Alola Muk"), Some("Muk-Alola"));
+    assert_eq!(species("Alolan Muk"), Some("Muk-Alola"));
+    assert_eq!(species("Mega Charizard X"), Some("Charizard-Mega-X"));
+    assert_eq!(species("Totally Real Mon"), None);
+    // Typos are only suggestions: the closest bundled name may be another Pokémon
+    assert_eq!(species("Ferrothorne"), None);
+    assert_eq!(species("Latias"), None);
+
+    let suggest = |name: &str| dex.suggest_species(name).map(|s| s.name.as_str());
+    assert_eq!(suggest("Ferrothorne"), Some("Ferrothorn"));
+    assert_eq!(
+        dex.suggest_move("Stelth Rock").unwrap().name,
+        "Stealth Rock"
+    );
+    assert_eq!(
+        dex.suggest_item("Choice Scraf").unwrap().name,
+        "Choice Scarf"
+    );
+    assert_eq!(dex.suggest_ability("Drizle").unwrap().name, "Drizzle");
+}
+
+#[tokio::test]
+async fn team_names_are_corrected() {
+    let mut team = Team::deserialize(
+        "#\
+Alolan Muk @ Black Sluge
+Ability: Poison Touch
+- Knock Of
+- Poison Jab
+- Hidden Power [Ice]
+- Protect
+
+Politoed @ Leftovers
+Ability: Drizle
+- Scald
+- Toxic
+- Encore
+- Protect
+",
+    )
+    .await;
+    let notes = team.correct_names();
+    assert_eq!(
+        notes,
+        vec![
+            "\"Alolan Muk\" -> \"Muk-Alola\"",
+            "\"Black Sluge\" is unknown, did you mean \"Black Sludge\"?",
+            "\"Knock Of\" is unknown, did you mean \"Knock Off\"?",
+            "\"Drizle\" is unknown, did you mean \"Drizzle\"?",
+        ]
+    );
+    assert_eq!(team.pokemon[0].name, "Muk-Alola");
+    // Only the forme spelling is rewritten, typos are left for the user to fix
+    assert_eq!(team.pokemon[0].item.as_deref(), Some("Black Sluge"));
+    assert_eq!(team.pokemon[0].moves[0], "Knock Of");
+    assert_eq!(team.pokemon[0].moves[2], "Hidden Power [Ice]");
+    assert!(
+        team.serialize_packed()
+            .contains("|mukalola|blacksluge|poisontouch|")
+    );
+}
+
+#[tokio::test]
+async fn similar_species_are_not_swapped() {
+    let mut team = Team::deserialize(
+        "#\
+Latias @ Soul Dew
+Ability: Levitate
+- Draco Meteor
+
+Latios @ Choice Specs
+Ability: Levitate
+- Draco Meteor
+
+Magneton @ Choice Scarf
+Ability: Magnet Pull
+- Thunderbolt
+",
+    )
+    .await;
+    let notes = team.correct_names();
+    let names: Vec<&str> = team.pokemon.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["Latias", "Latios", "Magneton"]);
+    assert!(notes.iter().all(|n| !n.contains("->")), "{:#?}", notes);
+}
+
+#[tokio::test]
+async fn unknown_names_get_suggestions() {
+    let team = Team::deserialize(
+        "#\
+Charizardite X @ Choice Scarf
+Ability: Blaze
+- Flamethrower
+
+Garchmop @ Leftover
+Ability: Rough Skn
+- Earthqake
+",
+    )
+    .await;
+    let messages: Vec<String> = validate_legality(&team, &Format::from_id("gen6ou"))
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+    assert!(
+        messages.contains(&"Charizardite X: Charizardite X is an item, not a Pokémon".to_string())
+    );
+    assert!(messages.contains(
+        &"Garchmop: Garchmop is not a known Pokémon, did you mean Garchomp?".to_string()
+    ));
+
+    let mut team = team;
+    let notes = team.correct_names();
+    assert!(notes.contains(&"\"Garchmop\" is unknown, did you mean \"Garchomp\"?".to_string()));
+    assert_eq!(team.pokemon[1].name, "Garchmop");
+}
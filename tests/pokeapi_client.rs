@@ -0,0 +1,390 @@
+use pokebrains::pokeapi::{CACHE_DIR_ENV, POKEAPI_URL_ENV};
+use pokebrains::{
+    PokeApiClient, compare, fetch_ability_info, fetch_item_info, fetch_move_info,
+    fetch_pokemon_info, type_matchups,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const PIDGEY: &str = r#"{
+    "id": 16, "name": "pidgey", "height": 3, "weight": 18,
+    "types": [{"type": {"name": "normal", "url": ""}}, {"type": {"name": "flying", "url": ""}}],
+    "abilities": [
+        {"is_hidden": false, "ability": {"name": "keen-eye", "url": ""}},
+        {"is_hidden": true, "ability": {"name": "big-pecks", "url": ""}}
+    ],
+    "moves": [], "stats": [{"base_stat": 56, "stat": {"name": "speed", "url": ""}}],
+    "species": {"name": "pidgey", "url": "BASE/pokemon-species/16/"},
+    "past_abilities": []
+}"#;
+
+/// Normal through Gen 5, with its second ability slot empty through Gen 4
+const TOGEPI: &str = r#"{
+    "id": 175, "name": "togepi", "height": 3, "weight": 15,
+    "types": [{"type": {"name": "fairy", "url": ""}}],
+    "abilities": [
+        {"is_hidden": false, "slot": 1, "ability": {"name": "hustle", "url": ""}},
+        {"is_hidden": false, "slot": 2, "ability": {"name": "serene-grace", "url": ""}},
+        {"is_hidden": true, "slot": 3, "ability": {"name": "super-luck", "url": ""}}
+    ],
+    "moves": [], "stats": [],
+    "species": {"name": "togepi", "url": "BASE/pokemon-species/175/"},
+    "past_types": [
+        {"generation": {"name": "generation-v", "url": ""},
+         "types": [{"type": {"name": "normal", "url": ""}}]}
+    ],
+    "past_abilities": [
+        {"generation": {"name": "generation-iv", "url": ""},
+         "abilities": [{"is_hidden": false, "slot": 2, "ability": null}]}
+    ]
+}"#;
+
+/// Attack raised in Gen 6, with a move from Gen 1 games and one from Gen 6 games
+const BEEDRILL: &str = r#"{
+    "id": 15, "name": "beedrill", "height": 10, "weight": 295,
+    "types": [{"type": {"name": "bug", "url": ""}}, {"type": {"name": "poison", "url": ""}}],
+    "abilities": [],
+    "moves": [
+        {"move": {"name": "twineedle", "url": ""}, "version_group_details": [
+            {"level_learned_at": 1, "move_learn_method": {"name": "level-up", "url": ""},
+             "version_group": {"name": "red-blue", "url": ""}},
+            {"level_learned_at": 10, "move_learn_method": {"name": "level-up", "url": ""},
+             "version_group": {"name": "firered-leafgreen", "url": ""}}
+        ]},
+        {"move": {"name": "fell-stinger", "url": ""}, "version_group_details": [
+            {"level_learned_at": 1, "move_learn_method": {"name": "level-up", "url": ""},
+             "version_group": {"name": "x-y", "url": ""}}
+        ]},
+        {"move": {"name": "rage", "url": ""}, "version_group_details": [
+            {"level_learned_at": 20, "move_learn_method": {"name": "level-up", "url": ""},
+             "version_group": {"name": "red-blue", "url": ""}}
+        ]}
+    ],
+    "stats": [{"base_stat": 90, "stat": {"name": "attack", "url": ""}},
+        {"base_stat": 75, "stat": {"name": "speed", "url": ""}}],
+    "species": {"name": "beedrill", "url": "BASE/pokemon-species/15/"},
+    "past_stats": [
+        {"generation": {"name": "generation-v", "url": ""},
+         "stats": [{"base_stat": 80, "stat": {"name": "attack", "url": ""}}]}
+    ]
+}"#;
+
+/// Not in the bundled dex, one letter away from Latios which is
+const LATIAS: &str = r#"{
+    "id": 380, "name": "latias", "height": 14, "weight": 400,
+    "types": [{"type": {"name": "dragon", "url": ""}}, {"type": {"name": "psychic", "url": ""}}],
+    "abilities": [], "moves": [],
+    "stats": [{"base_stat": 110, "stat": {"name": "speed", "url": ""}}],
+    "species": {"name": "latias", "url": "BASE/pokemon-species/380/"}
+}"#;
+
+/// In the bundled dex, whose spelling has a space the slug doesn't
+const GREAT_TUSK: &str = r#"{
+    "id": 984, "name": "great-tusk", "height": 22, "weight": 3200,
+    "types": [{"type": {"name": "ground", "url": ""}}, {"type": {"name": "fighting", "url": ""}}],
+    "abilities": [], "moves": [], "stats": [],
+    "species": {"name": "great-tusk", "url": "BASE/pokemon-species/984/"}
+}"#;
+
+/// Moves, items and abilities the bundled dex lacks
+const VOLT_TACKLE: &str = r#"{
+    "name": "volt-tackle", "names": [{"name": "Volt Tackle", "language": {"name": "en", "url": ""}}],
+    "type": {"name": "electric", "url": ""}, "damage_class": {"name": "physical", "url": ""},
+    "power": 120, "accuracy": 100, "pp": 15, "priority": 0,
+    "target": {"name": "selected-pokemon", "url": ""}, "effect_chance": 10,
+    "effect_entries": [{"effect": "", "language": {"name": "en", "url": ""},
+        "short_effect": "User takes 1/3 the damage inflicted in recoil. Has a $effect_chance% chance to paralyze the target."}],
+    "generation": {"name": "generation-iii", "url": ""}
+}"#;
+
+const METRONOME: &str = r#"{
+    "name": "metronome", "names": [{"name": "Metronome", "language": {"name": "en", "url": ""}}],
+    "effect_entries": [{"effect": "", "language": {"name": "en", "url": ""},
+        "short_effect": "Held: Increases the power of moves used consecutively."}],
+    "fling_power": 30, "category": {"name": "held-items", "url": ""},
+    "game_indices": [{"game_index": 277, "generation": {"name": "generation-iv", "url": ""}},
+        {"game_index": 277, "generation": {"name": "generation-v", "url": ""}}]
+}"#;
+
+const ADAMANT_ORB: &str = r#"{
+    "name": "adamant-orb", "names": [{"name": "Adamant Orb", "language": {"name": "en", "url": ""}}],
+    "effect_entries": [{"effect": "", "language": {"name": "en", "url": ""},
+        "short_effect": "Held by Dialga: Dragon and Steel moves have 1.2x power."}],
+    "fling_power": 60, "category": {"name": "species-specific", "url": ""},
+    "game_indices": [{"game_index": 112, "generation": {"name": "generation-iv", "url": ""}}]
+}"#;
+
+const RUN_AWAY: &str = r#"{
+    "name": "run-away", "names": [{"name": "Run Away", "language": {"name": "en", "url": ""}}],
+    "effect_entries": [{"effect": "", "language": {"name": "en", "url": ""},
+        "short_effect": "Ensures success fleeing from wild battles."}],
+    "generation": {"name": "generation-iii", "url": ""}
+}"#;
+
+fn ability(effect: &str) -> String {
+    format!(
+        r#"{{"name": "x", "effect_entries": [{{"effect": "", "short_effect": "{}",
+            "language": {{"name": "en", "url": ""}}}}]}}"#,
+        effect
+    )
+}
+
+/// A PokeAPI stand-in that fails the first `failures` requests with a 503 and counts requests
+async fn mock_server(failures: usize) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let (count, url) = (requests.clone(), base.clone());
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let n = count.fetch_add(1, Ordering::SeqCst);
+            let url = url.clone();
+            tokio::spawn(async move {
+                let mut buf = vec![0; 4096];
+                let len = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..len]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let (status, body) = match path.as_str() {
+                    _ if n < failures => ("503 Service Unavailable", String::new()),
+                    "/pokemon/pidgey" => ("200 OK", PIDGEY.replace("BASE", &url)),
+                    "/pokemon/togepi" => ("200 OK", TOGEPI.replace("BASE", &url)),
+                    "/pokemon/beedrill" => ("200 OK", BEEDRILL.replace("BASE", &url)),
+                    "/pokemon/latias" => ("200 OK", LATIAS.replace("BASE", &url)),
+                    "/pokemon/great-tusk" => ("200 OK", GREAT_TUSK.replace("BASE", &url)),
+                    "/pokemon-species/984/" => (
+                        "200 OK",
+                        r#"{"generation": {"name": "generation-ix", "url": ""}}"#.to_string(),
+                    ),
+                    "/pokemon-species/380/" => (
+                        "200 OK",
+                        r#"{"generation": {"name": "generation-iii", "url": ""}}"#.to_string(),
+                    ),
+                    "/pokemon-species/175/" => (
+                        "200 OK",
+                        r#"{"generation": {"name": "generation-ii", "url": ""}}"#.to_string(),
+                    ),
+                    "/pokemon-species/15/" => (
+                        "200 OK",
+                        r#"{"generation": {"name": "generation-i", "url": ""}}"#.to_string(),
+                    ),
+                    "/pokemon-species/16/" => (
+                        "200 OK",
+                        r#"{"generation": {"name": "generation-i", "url": ""}}"#.to_string(),
+                    ),
+                    "/ability/keen-eye" => ("200 OK", ability("Prevents accuracy loss.")),
+                    "/ability/big-pecks" => ("200 OK", ability("Prevents Defense loss.")),
+                    "/move/volt-tackle" => ("200 OK", VOLT_TACKLE.to_string()),
+                    "/item/metronome" => ("200 OK", METRONOME.to_string()),
+                    "/item/adamant-orb" => ("200 OK", ADAMANT_ORB.to_string()),
+                    "/ability/run-away" => ("200 OK", RUN_AWAY.to_string()),
+                    _ => ("404 Not Found", String::new()),
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    (base, requests)
+}
+
+fn client(base: &str, cache: &str) -> PokeApiClient {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("pokebrains-{}-{}", cache, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut client = PokeApiClient::new(base);
+    client.cache_dir = Some(dir);
+    client.backoff = Duration::from_millis(1);
+    client
+}
+
+#[tokio::test]
+async fn fetches_and_caches_pokemon() {
+    let (base, requests) = mock_server(0).await;
+    let client = client(&base, "pokeapi-cache");
+
+    let pidgey = client.pokemon("Pidgey").await.unwrap();
+    assert_eq!(pidgey.generation.unwrap().name, "generation-i");
+    let effects: Vec<Option<&str>> = pidgey
+        .abilities
+        .iter()
+        .map(|a| a.effect.as_deref())
+        .collect();
+    assert_eq!(
+        effects,
+        [
+            Some("Prevents accuracy loss."),
+            Some("Prevents Defense loss.")
+        ]
+    );
+    // Pokémon, species and two abilities
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+    // Served from disk the second time
+    client.pokemon("pidgey").await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+    let mut stale = client;
+    stale.ttl = Duration::ZERO;
+    stale.pokemon("pidgey").await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 8);
+}
+
+#[tokio::test]
+async fn retries_server_errors_but_not_missing_pokemon() {
+    let (base, requests) = mock_server(2).await;
+    let client = client(&base, "pokeapi-retry");
+    assert_eq!(client.pokemon("pidgey").await.unwrap().id, 16);
+    assert_eq!(requests.load(Ordering::SeqCst), 6);
+
+    let error = client.pokemon("missingno").await.unwrap_err();
+    assert!(format!("{:#}", error).contains("404"));
+    assert_eq!(requests.load(Ordering::SeqCst), 7);
+
+    let mut impatient = PokeApiClient::new(&base);
+    impatient.retries = 0;
+    let (base, _) = mock_server(1).await;
+    impatient.base_url = base;
+    assert!(impatient.pokemon("pidgey").await.is_err());
+}
+
+#[tokio::test]
+async fn rewinds_to_past_generations() {
+    let (base, _) = mock_server(0).await;
+    let client = client(&base, "pokeapi-past");
+    let names = |info: &pokebrains::PokemonInfo| -> (Vec<String>, Vec<String>) {
+        (
+            info.types.iter().map(|t| t.r#type.name.clone()).collect(),
+            info.abilities
+                .iter()
+                .map(|a| a.ability.name.clone())
+                .collect(),
+        )
+    };
+
+    let now = client.pokemon("togepi").await.unwrap();
+    assert_eq!(
+        names(&now),
+        (
+            vec!["fairy".to_string()],
+            vec!["hustle".into(), "serene-grace".into(), "super-luck".into()]
+        )
+    );
+    let gen5 = client.pokemon_in("togepi", 5).await.unwrap();
+    assert_eq!(gen5.as_of_gen, 5);
+    assert_eq!(names(&gen5).0, ["normal"]);
+    // No second slot and no hidden abilities before Gen 5
+    let gen4 = client.pokemon_in("togepi", 4).await.unwrap();
+    assert_eq!(names(&gen4).1, ["hustle"]);
+    assert!(
+        client
+            .pokemon_in("togepi", 2)
+            .await
+            .unwrap()
+            .abilities
+            .is_empty()
+    );
+
+    let error = client.pokemon_in("togepi", 1).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Togepi does not exist in Gen 1; it was introduced in Gen 2"
+    );
+
+    // Base stats from before a change, and only the moves that could be known then
+    let moves = |info: &pokebrains::PokemonInfo| -> Vec<String> {
+        info.moves.iter().map(|m| m.r#move.name.clone()).collect()
+    };
+    let now = client.pokemon("beedrill").await.unwrap();
+    assert_eq!(now.base_stats().atk, 90);
+    assert_eq!(moves(&now), ["twineedle", "fell-stinger"]);
+    let gen5 = client.pokemon_in("beedrill", 5).await.unwrap();
+    assert_eq!((gen5.base_stats().atk, gen5.base_stats().spe), (80, 75));
+    assert_eq!(moves(&gen5), ["twineedle"]);
+    // Rage from Red and Blue doesn't carry past the GB games
+    let gen1 = client.pokemon_in("beedrill", 1).await.unwrap();
+    assert_eq!(moves(&gen1), ["twineedle", "rage"]);
+}
+
+#[tokio::test]
+async fn names_use_the_dex_spelling() {
+    let (base, _) = mock_server(0).await;
+    let client = client(&base, "pokeapi-names");
+    assert_eq!(
+        client.pokemon("Great Tusk").await.unwrap().name,
+        "Great Tusk"
+    );
+    // Not in the dex, so the slug is title-cased
+    assert_eq!(client.pokemon("pidgey").await.unwrap().name, "Pidgey");
+}
+
+#[tokio::test]
+async fn names_missing_from_the_bundle_come_from_the_api() {
+    let (base, _) = mock_server(0).await;
+    let cache = std::env::temp_dir().join(format!("pokebrains-fallback-{}", std::process::id()));
+    // The only test here using the shared client, so nothing has read these yet
+    unsafe {
+        std::env::set_var(POKEAPI_URL_ENV, &base);
+        std::env::set_var(CACHE_DIR_ENV, &cache);
+    }
+
+    let latias = fetch_pokemon_info("Latias").await.unwrap();
+    assert_eq!((latias.id, latias.name.as_str()), (380, "Latias"));
+    assert_eq!(latias.base_stats().spe, 110);
+
+    let comparison = compare("Latias", "Garchomp", 9).await.unwrap();
+    let names = comparison.pokemon.each_ref().map(|p| p.name.as_str());
+    assert_eq!(names, ["Latias", "Garchomp"]);
+    assert_eq!(comparison.pokemon[0].types, ["Dragon", "Psychic"]);
+    let matchups = type_matchups("Latias", 9).await.unwrap();
+    assert_eq!(matchups.defense["Dragon"], 2.0);
+
+    // Real moves, items and abilities outside the bundle aren't reported as unknown
+    let volt_tackle = fetch_move_info("Volt Tackle", 9).await.unwrap();
+    assert_eq!(volt_tackle.name, "Volt Tackle");
+    assert_eq!(
+        (volt_tackle.r#type.as_str(), volt_tackle.category.as_str()),
+        ("Electric", "Physical")
+    );
+    assert_eq!(
+        (
+            volt_tackle.base_power,
+            volt_tackle.accuracy,
+            volt_tackle.r#gen
+        ),
+        (120, Some(100), 3)
+    );
+    assert!(volt_tackle.effects[0].ends_with("Has a 10% chance to paralyze the target."));
+    assert_eq!(
+        fetch_move_info("Volt Tackle", 2)
+            .await
+            .unwrap_err()
+            .to_string(),
+        "Volt Tackle is not available in Gen 2"
+    );
+
+    let metronome = fetch_item_info("Metronome", 9).await.unwrap();
+    assert_eq!((metronome.r#gen, metronome.fling_power), (4, Some(30)));
+    assert!(
+        metronome
+            .to_readable_form()
+            .contains("Effect: Held: Increases the power")
+    );
+    assert_eq!(
+        fetch_item_info("Adamant Orb", 4).await.unwrap().name,
+        "Adamant Orb"
+    );
+    assert!(fetch_item_info("Adamant Orb", 3).await.is_err());
+    let run_away = fetch_ability_info("Run Away", 9).await.unwrap();
+    assert_eq!((run_away.name.as_str(), run_away.r#gen), ("Run Away", 3));
+    assert!(fetch_ability_info("Run Away", 2).await.is_err());
+}
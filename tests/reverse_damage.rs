@@ -0,0 +1,123 @@
+use pokebrains::damage::{Combatant, Field};
+use pokebrains::inference::{Hit, NatureEffect, Position, reverse::reverse};
+use pokebrains::stats::Stat;
+use pokebrains::{BattleEvents, Pokemon, Team};
+
+fn pokemon(json: &str) -> Pokemon {
+    Team::from_json(&format!(r#"{{"pokemon": [{}]}}"#, json))
+        .unwrap()
+        .pokemon
+        .remove(0)
+}
+
+fn set(species: &str, evs: &str, nature: &str) -> Pokemon {
+    pokemon(&format!(
+        r#"{{"name": "{}", "evs": {}, "nature": "{}"}}"#,
+        species, evs, nature
+    ))
+}
+
+#[test]
+fn exact_damage_pins_down_the_attacker() {
+    // Our Tyranitar has 404 HP; no Adamant Dragonite does 304 with Outrage unless boosted
+    let hit = Hit {
+        move_name: "Outrage".to_string(),
+        attacker: Combatant::from(pokemon(r#"{"name": "Dragonite", "ability": "Multiscale"}"#)),
+        defender: Combatant::from(set("Tyranitar", r#"{"hp": 252}"#, "Careful")),
+        field: Field::default(),
+        crit: false,
+        fraction: 304.0 / 404.0,
+        knocked_out: false,
+        opponent: Position::Attacker,
+    };
+    let calc = reverse(9, &hit).unwrap();
+    assert_eq!(calc.stat, Stat::Atk);
+    assert!(!calc.allows_item(None));
+    assert!(calc.allows_item(Some("Choice Band")));
+    // Items that don't change the damage count as none
+    assert!(!calc.allows_item(Some("Leftovers")));
+
+    let mut band = set("Dragonite", r#"{"atk": 252}"#, "Adamant");
+    band.item = Some("Choice Band".to_string());
+    assert!(calc.allows(&band));
+    band.nature = Some("Jolly".to_string());
+    band.evs.atk = 0;
+    assert!(!calc.allows(&band));
+
+    let strongest = calc.strongest().unwrap();
+    assert_eq!(strongest.evs, 252);
+    assert_eq!(strongest.nature, NatureEffect::Boosting);
+}
+
+#[test]
+fn percent_damage_bounds_the_defender() {
+    let mut attacker = set("Dragonite", r#"{"atk": 252}"#, "Adamant");
+    attacker.item = Some("Choice Band".to_string());
+    // 252 HP / 0 Def Tyranitar takes 75.2-88.6%
+    let hit = Hit {
+        move_name: "Outrage".to_string(),
+        attacker: Combatant::from(attacker),
+        defender: Combatant::from(pokemon(
+            r#"{"name": "Tyranitar", "ability": "Sand Stream"}"#,
+        )),
+        field: Field::default(),
+        crit: false,
+        fraction: 0.80,
+        knocked_out: false,
+        opponent: Position::Defender,
+    };
+    let calc = reverse(9, &hit).unwrap();
+    assert_eq!(calc.stat, Stat::Def);
+    assert!(calc.allows(&set("Tyranitar", r#"{"hp": 252}"#, "Careful")));
+    assert!(!calc.allows(&set("Tyranitar", r#"{"hp": 252, "def": 252}"#, "Impish")));
+    assert!(
+        calc.to_string()
+            .starts_with("Def (Outrage): any other item ")
+    );
+}
+
+static BATTLE: &str = "\
+|player|p1|me|1|
+|player|p2|foe|1|
+|gen|9
+|tier|[Gen 9] OU
+|poke|p1|Tyranitar, M|
+|poke|p2|Dragonite, M|
+|start
+|switch|p1a: Tyranitar|Tyranitar, M|404/404
+|switch|p2a: Dragonite|Dragonite, M|100/100
+|turn|1
+|move|p2a: Dragonite|Outrage|p1a: Tyranitar
+|-damage|p1a: Tyranitar|100/404
+|turn|2";
+
+#[test]
+fn hits_in_battle_narrow_the_set() {
+    let mut events = BattleEvents::new("me".to_string());
+    events.opponents.set_own_team(
+        &Team::from_json(
+            r#"{"pokemon": [{"name": "Tyranitar", "evs": {"hp": 252}, "nature": "Careful"}]}"#,
+        )
+        .unwrap(),
+    );
+    for line in BATTLE.lines() {
+        events.add_event(line);
+    }
+
+    let dragonite = events.opponents.belief("Dragonite").unwrap();
+    let investments = dragonite.investments();
+    assert_eq!(investments.len(), 1);
+    assert_eq!(investments[0].moves, ["Outrage"]);
+    assert!(!investments[0].allows_item(None));
+    assert!(
+        dragonite
+            .to_string()
+            .contains(" | Atk (Outrage): Choice Band ")
+    );
+
+    // No spread data for Dragonite here, so the hit fills in its attacking stat
+    let set = dragonite.most_likely_set();
+    assert_eq!(set.evs.atk, 252);
+    assert_eq!(set.nature.as_deref(), Some("Adamant"));
+    assert_eq!(set.item.as_deref(), Some("Choice Band"));
+}
@@ -0,0 +1,151 @@
+use pokebrains::inference::{Observation, SetBelief};
+use pokebrains::sets::SetsDex;
+use pokebrains::usage::UsageStats;
+use pokebrains::{BattleEvents, Team};
+
+static CHAOS: &str = r#"{
+  "info": {"metagame": "gen9ou", "cutoff": 1695, "number of battles": 1000},
+  "data": {
+    "Dragonite": {
+      "Raw count": 300,
+      "usage": 0.2,
+      "Abilities": {"multiscale": 200.0},
+      "Items": {"choiceband": 100.0, "heavydutyboots": 100.0},
+      "Spreads": {"Adamant:0/252/0/0/4/252": 100.0, "Impish:248/0/252/0/8/0": 100.0},
+      "Moves": {"extremespeed": 150.0, "earthquake": 120.0, "dragondance": 100.0,
+                "roost": 80.0, "outrage": 100.0, "firepunch": 50.0},
+      "Teammates": {},
+      "Checks and Counters": {}
+    }
+  }
+}"#;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn dragonite() -> SetBelief {
+    let stats = UsageStats::from_json(CHAOS).unwrap();
+    SetBelief::from_usage(stats.get("Dragonite").unwrap())
+}
+
+#[test]
+fn revealed_moves_shift_the_set() {
+    let sets = SetsDex::bundled();
+    let mut ferrothorn = SetBelief::from_sets("Ferrothorn", sets.sets("gen5ou", "Ferrothorn"));
+    assert!(close(ferrothorn.item_probability("Leftovers"), 1.0));
+    assert!(close(ferrothorn.move_probability("Spikes"), 1.0 / 3.0));
+
+    ferrothorn.observe(Observation::Move("Spikes".to_string()));
+    assert!(close(ferrothorn.move_probability("Spikes"), 1.0));
+    assert!(ferrothorn.move_probability("Protect") > 0.9);
+    assert!(ferrothorn.move_probability("Stealth Rock") < 0.1);
+    assert!(ferrothorn.probability("Relaxed Nature") == 0.0);
+
+    let set = ferrothorn.most_likely_set();
+    assert_eq!(set.item.as_deref(), Some("Leftovers"));
+    assert_eq!(set.moves[0], "Spikes");
+    assert_eq!(set.moves.len(), 4);
+}
+
+#[test]
+fn items_and_choice_lock() {
+    let mut belief = dragonite();
+    assert!(close(belief.item_probability("Choice Band"), 0.5));
+    assert!(close(belief.probability("Multiscale"), 1.0));
+
+    belief.observe(Observation::Move("Extreme Speed".to_string()));
+    belief.observe(Observation::NotChoiceLocked);
+    assert_eq!(belief.item_probability("Choice Band"), 0.0);
+    assert!(close(belief.probability("Heavy-Duty Boots"), 1.0));
+
+    // An item the data never saw still gets recorded
+    belief.observe(Observation::Item("Lum Berry".to_string()));
+    assert_eq!(belief.revealed_item(), Some("Lum Berry"));
+    assert!(close(belief.item_probability("Lum Berry"), 1.0));
+}
+
+#[test]
+fn damage_narrows_the_spread() {
+    let mut belief = dragonite();
+    belief.observe(Observation::Item("Heavy-Duty Boots".to_string()));
+
+    let tyranitar = Team::from_json(
+        r#"{"pokemon": [{"name": "Tyranitar", "evs": {"hp": 252}, "nature": "Careful"}]}"#,
+    )
+    .unwrap()
+    .pokemon
+    .remove(0);
+    // 252+ Atk Outrage does 50.5-59.4%, the Impish spread only 37.9-44.9%
+    belief.observe(Observation::DamageDealt {
+        move_name: "Outrage".to_string(),
+        defender: tyranitar,
+        fraction: 0.52,
+        knocked_out: false,
+    });
+    let (nature, evs, p) = belief.spreads().remove(0);
+    assert_eq!(nature, "Adamant");
+    assert_eq!(evs.atk, 252);
+    assert!(p > 0.95);
+    assert_eq!(belief.most_likely_set().nature.as_deref(), Some("Adamant"));
+}
+
+static BATTLE: &str = "\
+|player|p1|me|1|
+|player|p2|foe|1|
+|gen|5
+|tier|[Gen 5] OU
+|poke|p1|Tyranitar, M|
+|poke|p2|Politoed, M|
+|poke|p2|Ferrothorn, F|
+|teampreview
+|start
+|switch|p1a: Tyranitar|Tyranitar, M|100/100
+|switch|p2a: Toed|Politoed, M|100/100
+|-weather|RainDance|[from] ability: Drizzle|[of] p2a: Toed
+|turn|1
+|move|p1a: Tyranitar|Crunch|p2a: Toed
+|-damage|p2a: Toed|70/100
+|move|p2a: Toed|Scald|p1a: Tyranitar
+|-supereffective|p1a: Tyranitar
+|-damage|p1a: Tyranitar|40/100
+|-heal|p2a: Toed|76/100|[from] item: Leftovers
+|upkeep
+|turn|2
+|move|p2a: Toed|Encore|p1a: Tyranitar
+|upkeep
+|turn|3";
+
+#[test]
+fn beliefs_follow_the_battle() {
+    let mut events = BattleEvents::new("me".to_string());
+    for line in BATTLE.lines() {
+        events.add_event(line);
+    }
+    let opponents = &events.opponents;
+    assert_eq!(opponents.format(), "gen5ou");
+    assert_eq!(opponents.beliefs().count(), 2);
+
+    let politoed = opponents.belief("Politoed").unwrap();
+    assert_eq!(politoed.revealed_ability(), Some("Drizzle"));
+    assert_eq!(politoed.revealed_item(), Some("Leftovers"));
+    assert_eq!(politoed.revealed_moves(), ["Scald", "Encore"]);
+    assert!(close(
+        opponents.probability("Politoed", "Protect").unwrap(),
+        1.0
+    ));
+    assert!(close(
+        opponents.probability("Politoed", "Toxic").unwrap(),
+        0.5
+    ));
+
+    let set = opponents.most_likely_set("Politoed").unwrap();
+    assert_eq!(set.nature.as_deref(), Some("Calm"));
+    assert_eq!(set.moves, vec!["Scald", "Encore", "Protect", "Toxic"]);
+
+    let summary = opponents.to_string();
+    assert!(
+        summary.contains("Politoed | Item: Leftovers (revealed) | Ability: Drizzle (revealed)")
+    );
+    assert!(summary.contains("Ferrothorn | Item: Leftovers 100%"));
+}
@@ -0,0 +1,83 @@
+use pokebrains::sets::SetsDex;
+
+#[test]
+fn candidate_sets_for_species() {
+    let sets = SetsDex::bundled();
+    assert_eq!(sets.formats(), vec!["gen5ou", "gen9ou"]);
+
+    let ferrothorn = sets.sets("gen5ou", "ferrothorn");
+    let names: Vec<&str> = ferrothorn.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["Utility", "Spikes"]);
+
+    let utility = &ferrothorn[0];
+    assert_eq!(utility.moves[3], vec!["Gyro Ball", "Thunder Wave"]);
+    assert!(utility.possible_moves().any(|m| m == "Thunder Wave"));
+
+    let tusk = &sets.sets("gen9ou", "Great Tusk")[0];
+    assert_eq!(tusk.nature, vec!["Jolly", "Impish"]);
+    assert_eq!(tusk.evs.len(), 2);
+    assert_eq!(tusk.tera_types, vec!["Water", "Steel"]);
+
+    assert!(sets.sets("gen9ou", "Ferrothorn").is_empty());
+    assert!(sets.sets("gen4ou", "Ferrothorn").is_empty());
+}
+
+#[test]
+fn sets_convert_to_pokemon() {
+    let sets = SetsDex::bundled();
+
+    let pokemon = sets.candidates("gen5ou", "Ferrothorn");
+    let utility = &pokemon[0];
+    assert_eq!(utility.species.as_deref(), Some("Ferrothorn"));
+    assert_eq!(utility.item.as_deref(), Some("Leftovers"));
+    assert_eq!(utility.ability.as_deref(), Some("Iron Barbs"));
+    assert_eq!(utility.nature.as_deref(), Some("Sassy"));
+    assert_eq!(
+        utility.moves,
+        vec!["Stealth Rock", "Leech Seed", "Power Whip", "Gyro Ball"]
+    );
+    assert_eq!(utility.evs.hp, 252);
+    assert_eq!(utility.evs.spe, 0);
+    let ivs = utility.ivs.as_ref().unwrap();
+    assert_eq!((ivs.spe, ivs.atk), (0, 31));
+
+    let specs = &sets.candidates("gen5ou", "Politoed")[1];
+    assert_eq!(specs.moves[3], "Hidden Power [Grass]");
+}
+
+#[test]
+fn sets_from_a_dump() {
+    let mut sets = SetsDex::default();
+    sets.add_json(
+        "gen9uu",
+        r#"{"Charizard": {"Sun": {"moves": ["Fire Blast", ["Solar Beam", "Focus Blast"]],
+            "item": "Heavy-Duty Boots", "ability": "Solar Power", "teratypes": "Fire"}}}"#,
+    )
+    .unwrap();
+
+    // Formes fall back to the base species
+    let sets = sets.sets("gen9uu", "Charizard-Mega-Y");
+    assert_eq!(sets.len(), 1);
+    assert_eq!(sets[0].name, "Sun");
+    assert_eq!(sets[0].species, "Charizard");
+    assert_eq!(sets[0].tera_types, vec!["Fire"]);
+    assert!(sets[0].evs.is_empty());
+}
+
+#[test]
+fn a_bad_file_loads_nothing() {
+    let dir = std::env::temp_dir().join(format!("pokebrains-sets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("gen9uu.json"),
+        r#"{"Charizard": {"Sun": {"moves": []}}}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("gen9ru.json"), "{").unwrap();
+
+    let mut sets = SetsDex::default();
+    let error = format!("{:#}", sets.load_dir(&dir).unwrap_err());
+    assert!(error.contains("gen9ru.json"), "{}", error);
+    assert!(sets.formats().is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
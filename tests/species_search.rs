@@ -0,0 +1,102 @@
+use pokebrains::search::{SpeciesQuery, StatKey, report, search};
+use pokebrains::stats::Stat;
+use pokebrains::types::Type;
+
+fn names(query: &str) -> Vec<String> {
+    let query = SpeciesQuery::parse(query).unwrap();
+    search(&query)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.name)
+        .collect()
+}
+
+#[test]
+fn parses_loose_queries() {
+    let query =
+        SpeciesQuery::parse("Steel types in gen6 OU with base Spe ≥ 90 that learn U-turn").unwrap();
+    assert_eq!(
+        query,
+        SpeciesQuery {
+            r#gen: Some(6),
+            types: vec![Type::Steel],
+            moves: vec!["U-turn".to_string()],
+            min_stats: vec![(StatKey::Stat(Stat::Spe), 90)],
+            tiers: vec!["OU".to_string()],
+            ..Default::default()
+        }
+    );
+
+    let query = SpeciesQuery::parse("gen5ou bst<500 Close Combat sort:atk limit:3").unwrap();
+    assert_eq!(query.format.as_deref(), Some("gen5ou"));
+    assert_eq!(query.max_stats, [(StatKey::Total, 499)]);
+    assert_eq!(query.moves, ["Close Combat"]);
+    assert_eq!(query.sort, Some(StatKey::Stat(Stat::Atk)));
+    assert_eq!(query.limit, Some(3));
+
+    // "Water Absorb" is an ability, not the Water type
+    let query = SpeciesQuery::parse("water absorb").unwrap();
+    assert!(query.types.is_empty());
+    assert_eq!(query.abilities, ["Water Absorb"]);
+
+    assert!(SpeciesQuery::parse("steel fastest").is_err());
+}
+
+#[test]
+fn filters_and_sorts() {
+    assert_eq!(
+        names("Steel types in gen6 OU with base Spe ≥ 90 that learn U-turn"),
+        ["Jirachi"]
+    );
+    // Gengar lost Levitate in Gen 7
+    assert_eq!(names("gen6 ability:levitate ghost"), ["Gengar", "Rotom"]);
+    assert_eq!(names("gen7 levitate ghost"), ["Rotom"]);
+
+    let fastest = names("dragon sort:spe limit:2");
+    assert_eq!(fastest.len(), 2);
+    let query = SpeciesQuery::parse("dragon sort:spe").unwrap();
+    let speeds: Vec<u16> = search(&query)
+        .unwrap()
+        .iter()
+        .map(|m| m.base_stats.spe)
+        .collect();
+    assert!(speeds.windows(2).all(|w| w[0] >= w[1]));
+
+    // Ubers and battle-only formes are left out of OU; Gholdengo isn't in Gen 8
+    let ou = names("gen6ou");
+    assert!(ou.contains(&"Ferrothorn".to_string()));
+    assert!(!ou.contains(&"Aegislash".to_string()));
+    assert!(!names("gen6").contains(&"Aegislash-Blade".to_string()));
+    assert!(!names("gen8").contains(&"Gholdengo".to_string()));
+    // Clefable was Normal type in Gen 5
+    assert!(names("gen5 normal").contains(&"Clefable".to_string()));
+    assert!(names("gen5 fairy").is_empty());
+}
+
+#[test]
+fn reports_matches() {
+    let query = SpeciesQuery::parse("gen6 ground steel").unwrap();
+    let excadrill = search(&query).unwrap();
+    let text = report(&query, &excadrill);
+    assert!(text.starts_with(
+        "Excadrill (Ground/Steel) OU - 110/135/60/50/65/88 (508) - Sand Rush, Sand Force, Mold Breaker\n"
+    ));
+    // The bundled dex is a subset, and says so
+    assert!(text.ends_with(" Pokémon in the offline data)\n"));
+    let query = SpeciesQuery::parse("gen1 fairy").unwrap();
+    assert!(report(&query, &search(&query).unwrap()).starts_with("No Pokémon match the search\n"));
+    let query = SpeciesQuery::parse("gen9 close combat").unwrap();
+    assert!(report(&query, &search(&query).unwrap()).ends_with(
+        ", whose learnsets are partial, so Pokémon that learn the moves may be missing)\n"
+    ));
+
+    assert_eq!(
+        search(&SpeciesQuery::parse("move:starmask").unwrap())
+            .unwrap_err()
+            .to_string(),
+        "Can't search by starmask: it isn't a move in the offline data"
+    );
+    // Volt Tackle isn't bundled, which the error says rather than blaming the query
+    let error = SpeciesQuery::parse("volt tackle").unwrap_err().to_string();
+    assert!(error.contains("offline data"), "{}", error);
+}
@@ -0,0 +1,233 @@
+use pokebrains::inference::{Observation, SetBelief, SpeedBounds};
+use pokebrains::usage::UsageStats;
+use pokebrains::{BattleEvents, Team};
+
+static CHAOS: &str = r#"{
+  "info": {"metagame": "gen9ou", "cutoff": 1695, "number of battles": 1000},
+  "data": {
+    "Dragonite": {
+      "Raw count": 300,
+      "usage": 0.2,
+      "Abilities": {"multiscale": 200.0},
+      "Items": {"choiceband": 100.0, "heavydutyboots": 100.0},
+      "Spreads": {"Adamant:0/252/0/0/4/252": 100.0, "Impish:248/0/252/0/8/0": 100.0},
+      "Moves": {"extremespeed": 150.0, "earthquake": 120.0, "dragondance": 100.0},
+      "Teammates": {},
+      "Checks and Counters": {}
+    }
+  }
+}"#;
+
+fn own_team() -> Team {
+    Team::from_json(
+        r#"{"pokemon": [
+            {"name": "Kingambit", "evs": {"hp": 252, "atk": 252}, "nature": "Adamant"},
+            {"name": "Great Tusk", "evs": {"atk": 252, "spe": 252}, "nature": "Jolly"},
+            {"name": "Tyranitar", "evs": {"hp": 252}, "nature": "Careful"}
+        ]}"#,
+    )
+    .unwrap()
+}
+
+fn battle(log: &str) -> BattleEvents {
+    let mut events = BattleEvents::new("me".to_string());
+    events.opponents.set_own_team(&own_team());
+    for line in log.lines() {
+        events.add_event(line);
+    }
+    events
+}
+
+#[test]
+fn speed_observations_pick_the_spread() {
+    let stats = UsageStats::from_json(CHAOS).unwrap();
+    let mut dragonite = SetBelief::from_usage(stats.get("Dragonite").unwrap());
+    // Adamant 252 Spe is 259, the Impish spread 196
+    assert_eq!(dragonite.speed_range(), Some((196.0, 259.0)));
+
+    dragonite.observe(Observation::SpeedAtLeast(230.0));
+    let (nature, _, p) = dragonite.spreads().remove(0);
+    assert_eq!(nature, "Adamant");
+    assert!(p > 0.95);
+    assert_eq!(dragonite.speed_range(), Some((259.0, 259.0)));
+    assert!(dragonite.to_string().contains("| Speed: >= 230"));
+
+    let tyranitar = own_team().pokemon.remove(2);
+    let comparison = dragonite.compare_speed(&tyranitar).unwrap();
+    assert_eq!(comparison.own_speed, 158.0);
+    assert_eq!(comparison.opponent_faster(), Some(true));
+    assert_eq!(comparison.probability_faster, Some(1.0));
+}
+
+static MOVE_ORDER: &str = "\
+|player|p1|me|1|
+|player|p2|foe|1|
+|gen|9
+|tier|[Gen 9] OU
+|poke|p1|Kingambit, M|
+|poke|p1|Great Tusk|
+|poke|p2|Tyranitar, M|
+|start
+|switch|p1a: Kingambit|Kingambit, M|100/100
+|switch|p2a: Tyranitar|Tyranitar, M|100/100
+|turn|1
+|move|p1a: Kingambit|Sucker Punch|p2a: Tyranitar
+|-damage|p2a: Tyranitar|80/100
+|move|p2a: Tyranitar|Crunch|p1a: Kingambit
+|-resisted|p1a: Kingambit
+|-damage|p1a: Kingambit|90/100
+|turn|2
+|move|p2a: Tyranitar|Crunch|p1a: Kingambit
+|-resisted|p1a: Kingambit
+|-damage|p1a: Kingambit|80/100
+|move|p1a: Kingambit|Rock Polish|p1a: Kingambit
+|-boost|p1a: Kingambit|spe|2
+|turn|3
+|move|p2a: Tyranitar|Crunch|p1a: Kingambit
+|-resisted|p1a: Kingambit
+|-damage|p1a: Kingambit|70/100
+|move|p1a: Kingambit|Iron Head|p2a: Tyranitar
+|-damage|p2a: Tyranitar|50/100
+|turn|4
+|switch|p1a: Great Tusk|Great Tusk|100/100
+|move|p2a: Tyranitar|Crunch|p1a: Great Tusk
+|-damage|p1a: Great Tusk|80/100
+|turn|5
+|move|p1a: Great Tusk|Headlong Rush|p2a: Tyranitar
+|-supereffective|p2a: Tyranitar
+|-damage|p2a: Tyranitar|10/100
+|move|p2a: Tyranitar|Crunch|p1a: Great Tusk
+|-damage|p1a: Great Tusk|60/100
+|turn|6";
+
+#[test]
+fn move_order_bounds_the_opponent() {
+    let events = battle(MOVE_ORDER);
+    let opponents = &events.opponents;
+    let tyranitar = opponents.belief("Tyranitar").unwrap();
+    // Sucker Punch has priority and turn 4 had a switch, so only turns 2, 3 and 5 count:
+    // it outsped Kingambit at 136 and then at +2 (272), and was outsped by Great Tusk at 300
+    assert_eq!(
+        tyranitar.speed_bounds(),
+        SpeedBounds {
+            min: Some(272.0),
+            max: Some(300.0)
+        }
+    );
+    assert_eq!(opponents.is_faster("Tyranitar", "Kingambit"), Some(true));
+    assert_eq!(opponents.is_faster("Tyranitar", "Great Tusk"), None);
+
+    let comparison = opponents.active_speed().unwrap();
+    assert_eq!(comparison.opponent_range, (272.0, 300.0));
+    assert_eq!(
+        comparison.to_string(),
+        "Tyranitar (272-300 Spe) may be faster than your Great Tusk (300 Spe)"
+    );
+}
+
+static FIELD: &str = "\
+|player|p1|me|1|
+|player|p2|foe|1|
+|gen|9
+|tier|[Gen 9] OU
+|poke|p1|Kingambit, M|
+|poke|p2|Tyranitar, M|
+|start
+|switch|p1a: Kingambit|Kingambit, M|100/100
+|switch|p2a: Tyranitar|Tyranitar, M|100/100
+|turn|1
+|move|p2a: Tyranitar|Trick Room|p2a: Tyranitar
+|-fieldstart|move: Trick Room|[of] p2a: Tyranitar
+|move|p1a: Kingambit|Iron Head|p2a: Tyranitar
+|-damage|p2a: Tyranitar|70/100
+|turn|2
+|move|p2a: Tyranitar|Crunch|p1a: Kingambit
+|-resisted|p1a: Kingambit
+|-damage|p1a: Kingambit|90/100
+|move|p1a: Kingambit|Iron Head|p2a: Tyranitar
+|-damage|p2a: Tyranitar|40/100
+|-fieldend|move: Trick Room
+|turn|3
+|-activate|p2a: Tyranitar|item: Quick Claw
+|move|p2a: Tyranitar|Crunch|p1a: Kingambit
+|-resisted|p1a: Kingambit
+|-damage|p1a: Kingambit|80/100
+|move|p1a: Kingambit|Iron Head|p2a: Tyranitar
+|-damage|p2a: Tyranitar|10/100
+|-status|p1a: Kingambit|par
+|turn|4
+|move|p2a: Tyranitar|Crunch|p1a: Kingambit
+|-resisted|p1a: Kingambit
+|-damage|p1a: Kingambit|70/100
+|move|p1a: Kingambit|Iron Head|p2a: Tyranitar
+|-damage|p2a: Tyranitar|0 fnt
+|turn|5";
+
+#[test]
+fn trick_room_quick_claw_and_paralysis() {
+    let events = battle(FIELD);
+    let tyranitar = events.opponents.belief("Tyranitar").unwrap();
+    // Trick Room itself has -7 priority. Moving first under it on turn 2 puts it at or below
+    // 136, turn 3 was Quick Claw and turn 4 only shows it beat a paralyzed Kingambit at 68.
+    assert_eq!(
+        tyranitar.speed_bounds(),
+        SpeedBounds {
+            min: Some(68.0),
+            max: Some(136.0)
+        }
+    );
+    assert!(tyranitar.to_string().contains("| Speed: 68-136"));
+    let comparison = events
+        .opponents
+        .compare_speed("Tyranitar", "Kingambit")
+        .unwrap();
+    assert_eq!(comparison.opponent_faster(), None);
+    assert!(!comparison.trick_room);
+}
+
+static RAIN: &str = "\
+|player|p1|me|1|
+|player|p2|foe|1|
+|gen|9
+|tier|[Gen 9] OU
+|poke|p1|Kingdra, M|
+|poke|p2|Tyranitar, M|
+|start
+|switch|p1a: Kingdra|Kingdra, M|100/100
+|switch|p2a: Tyranitar|Tyranitar, M|100/100
+|-weather|RainDance
+|turn|1
+|move|p1a: Kingdra|Surf|p2a: Tyranitar
+|-supereffective|p2a: Tyranitar
+|-damage|p2a: Tyranitar|20/100
+|move|p2a: Tyranitar|Crunch|p1a: Kingdra
+|-damage|p1a: Kingdra|40/100
+|-weather|RainDance|[upkeep]
+|turn|2";
+
+#[test]
+fn own_speed_abilities_count() {
+    let mut events = BattleEvents::new("me".to_string());
+    events.opponents.set_own_team(
+        &Team::from_json(
+            r#"{"pokemon": [
+                {"name": "Kingdra", "ability": "Swift Swim", "evs": {"spa": 252, "spe": 252},
+                 "nature": "Modest"}
+            ]}"#,
+        )
+        .unwrap(),
+    );
+    for line in RAIN.lines() {
+        events.add_event(line);
+    }
+    // Swift Swim doubles Kingdra's 269 Speed in the rain, so moving first only says
+    // Tyranitar is at most 538
+    let tyranitar = events.opponents.belief("Tyranitar").unwrap();
+    assert_eq!(
+        tyranitar.speed_bounds(),
+        SpeedBounds {
+            min: None,
+            max: Some(538.0)
+        }
+    );
+}
@@ -0,0 +1,93 @@
+use pokebrains::Team;
+use pokebrains::stats::{
+    BaseStats, Nature, Stat, apply_stage, calc_stat, calc_stat_gb, hp_dv, stage_multiplier,
+};
+
+static GARCHOMP: &str = "#\
+Garchomp @ Choice Scarf
+Ability: Rough Skin
+EVs: 252 Atk / 4 Def / 252 Spe
+Jolly Nature
+- Outrage
+- Earthquake
+- Stone Edge
+- Fire Fang
+";
+
+#[tokio::test]
+async fn gen3_plus_stats_from_team() {
+    let team = Team::deserialize(GARCHOMP).await;
+    let garchomp = &team.pokemon[0];
+    let base = BaseStats::new(108, 130, 95, 80, 85, 102);
+
+    let stats = garchomp.stats(9, &base);
+
+    assert_eq!(stats.hp, 357);
+    assert_eq!(stats.atk, 359);
+    assert_eq!(stats.def, 227);
+    assert_eq!(stats.spa, 176); // Jolly lowers SpA
+    assert_eq!(stats.spd, 206);
+    assert_eq!(stats.spe, 333); // Jolly boosts Spe
+}
+
+#[test]
+fn shedinja_hp_is_always_one() {
+    assert_eq!(calc_stat(Stat::HP, 1, 31, 252, 100, None), 1);
+    assert_eq!(calc_stat(Stat::HP, 1, 31, 0, 50, None), 1);
+}
+
+#[test]
+fn level_50_stats() {
+    // Adamant 252 Atk Garchomp at level 50
+    let atk = calc_stat(Stat::Atk, 130, 31, 252, 50, Some(Nature::Adamant));
+    assert_eq!(atk, 200);
+}
+
+#[test]
+fn gen1_stats_use_dvs_and_stat_exp() {
+    // Max DV / max stat exp Mewtwo
+    assert_eq!(calc_stat_gb(Stat::HP, 106, 15, 65535, 100), 416);
+    assert_eq!(calc_stat_gb(Stat::Spe, 130, 15, 65535, 100), 359);
+    assert_eq!(hp_dv(15, 15, 15, 15), 15);
+    assert_eq!(hp_dv(14, 15, 14, 15), 5);
+}
+
+#[test]
+fn nature_parsing() {
+    assert_eq!("jolly".parse::<Nature>().unwrap(), Nature::Jolly);
+    assert_eq!("Timid Nature".parse::<Nature>().unwrap(), Nature::Timid);
+    assert_eq!(Nature::Modest.modifiers(), Some((Stat::SpA, Stat::Atk)));
+    assert_eq!(Nature::Serious.modifiers(), None);
+    assert!("Starmask".parse::<Nature>().is_err());
+}
+
+#[test]
+fn stat_stages() {
+    assert_eq!(stage_multiplier(9, 2), (4, 2));
+    assert_eq!(stage_multiplier(9, -1), (2, 3));
+    assert_eq!(apply_stage(9, 300, 1), 450);
+    assert_eq!(apply_stage(9, 300, -2), 150);
+    assert_eq!(apply_stage(9, 300, 12), 1200); // clamped to +6
+    assert_eq!(stage_multiplier(1, -1), (66, 100));
+}
+
+#[tokio::test]
+async fn ivs_and_level_are_parsed() {
+    let team = Team::deserialize_from_file("./teams/GEN_V.txt")
+        .await
+        .unwrap();
+    assert_eq!(team.pokemon.len(), 6);
+
+    let politoed = &team.pokemon[0];
+    let ivs = politoed.ivs.as_ref().unwrap();
+    assert_eq!(ivs.atk, 0);
+    assert_eq!(ivs.hp, 31);
+
+    let keldeo = &team.pokemon[3];
+    let ivs = keldeo.ivs.as_ref().unwrap();
+    assert_eq!((ivs.atk, ivs.def), (2, 30));
+
+    let serialized = team.serialize();
+    assert!(serialized.contains("IVs: 0 Atk"));
+    assert!(serialized.contains("IVs: 2 Atk / 30 Def"));
+}
@@ -0,0 +1,132 @@
+use pokebrains::Team;
+use pokebrains::analysis::{analyze_team, defensive_multiplier};
+use pokebrains::types::{Type, effectiveness, type_effectiveness};
+
+#[test]
+fn type_chart() {
+    assert_eq!(effectiveness(Type::Water, Type::Fire), 2.0);
+    assert_eq!(effectiveness(Type::Fire, Type::Water), 0.5);
+    assert_eq!(effectiveness(Type::Normal, Type::Ghost), 0.0);
+    assert_eq!(effectiveness(Type::Dragon, Type::Fairy), 0.0);
+    assert_eq!(
+        type_effectiveness(Type::Fire, &[Type::Grass, Type::Steel]),
+        4.0
+    );
+    assert_eq!(
+        type_effectiveness(Type::Ground, &[Type::Water, Type::Flying]),
+        0.0
+    );
+    assert_eq!("fairy".parse::<Type>().unwrap(), Type::Fairy);
+}
+
+#[test]
+fn abilities_change_matchups() {
+    let dragon_psychic = [Type::Dragon, Type::Psychic];
+    assert_eq!(
+        defensive_multiplier(9, &dragon_psychic, None, Type::Ground),
+        1.0
+    );
+    assert_eq!(
+        defensive_multiplier(9, &dragon_psychic, Some("Levitate"), Type::Ground),
+        0.0
+    );
+    assert_eq!(
+        defensive_multiplier(9, &[Type::Normal], Some("Thick Fat"), Type::Fire),
+        0.5
+    );
+    assert_eq!(
+        defensive_multiplier(9, &[Type::Normal], Some("Thick Fat"), Type::Ice),
+        0.5
+    );
+    assert_eq!(
+        defensive_multiplier(
+            9,
+            &[Type::Bug, Type::Ghost],
+            Some("Wonder Guard"),
+            Type::Water
+        ),
+        0.0
+    );
+}
+
+#[tokio::test]
+async fn rain_team_analysis() {
+    let team = Team::deserialize_from_file("./teams/GEN_V.txt")
+        .await
+        .unwrap();
+    let analysis = analyze_team(&team, 5);
+    assert_eq!(analysis.members.len(), 6);
+    assert!(analysis.unknown.is_empty());
+
+    let member = |name: &str| analysis.members.iter().find(|m| m.name == name).unwrap();
+    assert_eq!(member("Ferrothorn").multiplier(Type::Fire), 4.0);
+    assert_eq!(member("Latios").multiplier(Type::Ground), 0.0);
+    assert_eq!(member("Thundurus-Therian").multiplier(Type::Electric), 0.0);
+    assert_eq!(
+        member("Politoed").weaknesses(),
+        vec![(Type::Electric, 2.0), (Type::Grass, 2.0)]
+    );
+    // Hidden Power [Ice] counts as an Ice attack
+    assert!(
+        member("Keldeo-Resolute")
+            .attacking_types
+            .contains(&Type::Ice)
+    );
+
+    // Politoed, Tentacruel and Keldeo are all weak to Electric
+    let electric = analysis
+        .shared_weaknesses
+        .iter()
+        .find(|w| w.r#type == Type::Electric)
+        .unwrap();
+    assert_eq!(electric.weak, 3);
+    assert_eq!(electric.resist, 3);
+
+    // Only Ground hits Electric super effectively, and nobody has a Ground move
+    assert!(analysis.uncovered.contains(&Type::Electric));
+    assert!(!analysis.uncovered.contains(&Type::Dragon));
+    // Tentacruel resists both Ice and Fighting, nobody resists both Electric and Ice
+    let combos = &analysis.unresisted_combos;
+    assert!(!combos.contains(&(Type::Ice, Type::Fighting)));
+    assert!(combos.contains(&(Type::Electric, Type::Ice)));
+
+    let text = analysis.to_string();
+    assert!(text.starts_with("Defensive matchups (Gen 5):"));
+    assert!(text.contains("Shared weaknesses:"));
+    assert!(text.contains("Ferrothorn (Grass/Steel, Iron Barbs)"));
+}
+
+#[tokio::test]
+async fn analysis_uses_the_formats_generation() {
+    let team = Team::deserialize(
+        "#\
+Clefable @ Leftovers
+Ability: Magic Guard
+- Moonblast
+
+Ferrothorn @ Leftovers
+Ability: Iron Barbs
+- Power Whip
+",
+    )
+    .await;
+
+    // Clefable was Normal and Steel resisted Ghost and Dark before Gen 6
+    let gen5 = analyze_team(&team, 5);
+    assert_eq!(gen5.members[0].types, vec![Type::Normal]);
+    assert_eq!(gen5.members[0].multiplier(Type::Ghost), 0.0);
+    assert_eq!(gen5.members[1].multiplier(Type::Ghost), 0.5);
+    assert_eq!(gen5.members[1].multiplier(Type::Dark), 0.5);
+    assert!(
+        gen5.members[0]
+            .multipliers
+            .iter()
+            .all(|(t, _)| *t != Type::Fairy)
+    );
+    assert!(!gen5.uncovered.contains(&Type::Fairy));
+
+    let gen9 = analyze_team(&team, 9);
+    assert_eq!(gen9.members[0].types, vec![Type::Fairy]);
+    assert_eq!(gen9.members[1].multiplier(Type::Ghost), 1.0);
+    assert_eq!(gen9.members[1].multiplier(Type::Dark), 1.0);
+}
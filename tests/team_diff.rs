@@ -0,0 +1,137 @@
+use pokebrains::Team;
+use pokebrains::analysis::{Change, StatChange, diff_teams};
+
+static OLD: &str = "#\
+Politoed @ Leftovers
+Ability: Drizzle
+EVs: 248 HP / 112 Def / 140 SpD / 8 Spe
+Calm Nature
+- Scald
+- Toxic
+- Encore
+- Protect
+
+Charizard @ Charizardite X
+Ability: Blaze
+EVs: 252 Atk / 4 Def / 252 Spe
+Jolly Nature
+- Flare Blitz
+- Dragon Claw
+- Earthquake
+- Dragon Dance
+
+Latios @ Choice Specs
+Ability: Levitate
+- Draco Meteor
+- Surf
+- Psyshock
+- Trick
+";
+
+static NEW: &str = "#\
+Charizard-Mega-X @ Charizardite X
+Ability: Tough Claws
+EVs: 252 Atk / 4 Def / 252 Spe
+Jolly Nature
+- Dragon Dance
+- Flare Blitz
+- Dragon Claw
+- Earthquake
+
+Garchomp @ Rocky Helmet
+Ability: Rough Skin
+- Stealth Rock
+- Earthquake
+- Dragon Tail
+- Fire Blast
+
+Politoed @ Damp Rock
+Ability: Drizzle
+EVs: 252 HP / 108 Def / 140 SpD / 8 Spe
+Bold Nature
+IVs: 0 Atk
+- Scald
+- Perish Song
+- Encore
+- Protect
+";
+
+#[tokio::test]
+async fn members_are_matched_by_species() {
+    let old = Team::deserialize(OLD).await;
+    let new = Team::deserialize(NEW).await;
+    let diff = diff_teams(&old, &new);
+
+    assert_eq!(diff.added, vec!["Garchomp"]);
+    assert_eq!(diff.removed, vec!["Latios"]);
+    assert_eq!(diff.changed.len(), 2);
+
+    let politoed = &diff.changed[0];
+    assert_eq!(politoed.species, "Politoed");
+    assert_eq!(
+        politoed.changes,
+        vec![
+            Change::Item {
+                from: Some("Leftovers".to_string()),
+                to: Some("Damp Rock".to_string()),
+            },
+            Change::Nature {
+                from: Some("Calm".to_string()),
+                to: Some("Bold".to_string()),
+            },
+            Change::Evs {
+                stats: vec![
+                    StatChange {
+                        stat: "HP",
+                        from: 248,
+                        to: 252
+                    },
+                    StatChange {
+                        stat: "Def",
+                        from: 112,
+                        to: 108
+                    },
+                ]
+            },
+            Change::Ivs {
+                stats: vec![StatChange {
+                    stat: "Atk",
+                    from: 31,
+                    to: 0
+                }]
+            },
+            Change::Moves {
+                removed: vec!["Toxic".to_string()],
+                added: vec!["Perish Song".to_string()],
+            },
+        ]
+    );
+
+    // Same Pokédex number, so the Mega is a forme change; reordered moves aren't a change
+    let charizard = &diff.changed[1];
+    assert_eq!(charizard.species, "Charizard-Mega-X");
+    assert_eq!(charizard.changes.len(), 2);
+    assert!(matches!(charizard.changes[0], Change::Species { .. }));
+}
+
+#[tokio::test]
+async fn text_and_json_output() {
+    let old = Team::deserialize(OLD).await;
+    let new = Team::deserialize(NEW).await;
+    let diff = diff_teams(&old, &new);
+
+    let text = diff.to_string();
+    assert!(text.starts_with("+ Garchomp\n- Latios\n~ Politoed\n"));
+    assert!(text.contains("    Item: Leftovers -> Damp Rock\n"));
+    assert!(text.contains("    EVs: HP 248 -> 252 (+4), Def 112 -> 108 (-4)\n"));
+    assert!(text.contains("    Moves: Toxic -> Perish Song\n"));
+    assert!(text.contains("    Ability: Blaze -> Tough Claws"));
+
+    let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+    assert_eq!(json["added"][0], "Garchomp");
+    assert_eq!(json["changed"][0]["changes"][0]["field"], "item");
+    assert_eq!(json["changed"][0]["changes"][2]["stats"][0]["to"], 252);
+
+    assert!(diff_teams(&old, &old).is_empty());
+    assert_eq!(diff_teams(&old, &old).to_string(), "No changes");
+}
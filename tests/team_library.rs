@@ -0,0 +1,144 @@
+use pokebrains::Team;
+use pokebrains::library::{Outcome, TeamLibrary, TeamQuery};
+use std::path::PathBuf;
+
+static RAIN: &str = "#\
+Politoed @ Leftovers
+Ability: Drizzle
+EVs: 248 HP / 112 Def / 140 SpD / 8 Spe
+Calm Nature
+- Scald
+- Toxic
+- Encore
+- Protect
+
+Ferrothorn @ Leftovers
+Ability: Iron Barbs
+EVs: 252 HP / 88 Def / 168 SpD
+Sassy Nature
+- Stealth Rock
+- Leech Seed
+- Power Whip
+- Gyro Ball
+";
+
+static SUN: &str = "#\
+Ninetales @ Heat Rock
+Ability: Drought
+EVs: 248 HP / 8 SpA / 252 Spe
+Timid Nature
+- Fire Blast
+- Will-O-Wisp
+- Solar Beam
+- Hypnosis
+";
+
+/// A fresh library directory per test
+fn temp_library(name: &str) -> TeamLibrary {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("pokebrains-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    TeamLibrary::open(dir).unwrap()
+}
+
+#[tokio::test]
+async fn save_tag_record_and_list() {
+    let library = temp_library("save");
+    let team = Team::deserialize(RAIN).await;
+
+    let entry = library
+        .save("Gen 5 Rain", &team, Some("gen5ou"), &["rain".to_string()])
+        .unwrap();
+    assert_eq!(entry.id, "gen5rain");
+    assert_eq!(entry.format.as_deref(), Some("gen5ou"));
+    let copy = library.save("Gen 5 Rain", &team, None, &[]).unwrap();
+    assert_eq!(copy.id, "gen5rain-2");
+
+    // Hand-made files are picked up with default metadata
+    std::fs::write(library.dir().join("loose.txt"), SUN).unwrap();
+
+    library
+        .add_tags("gen5rain", &["offense".to_string(), "Rain".to_string()])
+        .unwrap();
+    library.record_result("gen5rain", Outcome::Win).unwrap();
+    library.record_result("gen5rain", Outcome::Win).unwrap();
+    let entry = library.record_result("gen5rain", Outcome::Loss).unwrap();
+    assert_eq!(entry.tags, vec!["rain", "offense"]);
+    assert_eq!(entry.record.to_string(), "2-1-0");
+
+    let ids: Vec<String> = library.list().unwrap().into_iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec!["gen5rain", "gen5rain-2", "loose"]);
+
+    let loaded = library.load("gen5rain").await.unwrap();
+    assert_eq!(loaded.pokemon.len(), 2);
+    assert_eq!(loaded.pokemon[1].name, "Ferrothorn");
+
+    library.remove("gen5rain-2").unwrap();
+    assert!(library.entry("gen5rain-2").is_err());
+}
+
+#[tokio::test]
+async fn search_by_query() {
+    let query = TeamQuery::parse("teams with Ferrothorn in gen5ou #rain");
+    assert_eq!(query.species, vec!["Ferrothorn"]);
+    assert_eq!(query.format.as_deref(), Some("gen5ou"));
+    assert_eq!(query.tags, vec!["rain"]);
+    assert!(query.words.is_empty());
+
+    let library = temp_library("search");
+    let rain = Team::deserialize(RAIN).await;
+    let sun = Team::deserialize(SUN).await;
+    library
+        .save("Rain", &rain, Some("gen5ou"), &["rain".to_string()])
+        .unwrap();
+    library
+        .save("Sun", &sun, Some("gen5ou"), &["sun".to_string()])
+        .unwrap();
+
+    let found = library.search(&query).await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, "rain");
+
+    let found = library
+        .search(&TeamQuery::parse("Ninetales"))
+        .await
+        .unwrap();
+    assert_eq!(found[0].id, "sun");
+    assert_eq!(
+        library
+            .search(&TeamQuery::parse("gen4ou"))
+            .await
+            .unwrap()
+            .len(),
+        0
+    );
+    assert_eq!(
+        library.search(&TeamQuery::default()).await.unwrap().len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn import_and_export() {
+    let library = temp_library("export");
+    let team = Team::deserialize(RAIN).await;
+    library.save("Rain", &team, None, &[]).unwrap();
+
+    let json = library.dir().join("rain-export.json");
+    library.export("rain", &json).await.unwrap();
+    let imported = library
+        .import(&json, Some("Imported"), Some("gen5ou"), &[])
+        .await
+        .unwrap();
+    assert_eq!(imported.id, "imported");
+    assert_eq!(
+        library.load("imported").await.unwrap().serialize(),
+        team.serialize()
+    );
+
+    let markdown = library.dir().join("rain.md");
+    library.export("rain", &markdown).await.unwrap();
+    let text = std::fs::read_to_string(markdown).unwrap();
+    assert!(text.starts_with("# Rain\n"));
+    assert!(text.contains("## Ferrothorn @ Leftovers"));
+}
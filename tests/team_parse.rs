@@ -1,4 +1,4 @@
-use pokebrains::Team;
+use pokebrains::{EVs, Team};
 static TEAM: &str = "#\
 Dragonite @ Choice Scarf
 Ability: Inner Focus
@@ -88,3 +88,54 @@ async fn serialize_packed() {
     let serialized = team.serialize_packed();
     println!("{}", serialized);
 }
+
+#[tokio::test]
+async fn json_and_toml_round_trip() {
+    let team = Team::deserialize_from_file("./teams/GEN_V.txt")
+        .await
+        .unwrap();
+
+    let json = team.to_json().unwrap();
+    assert!(json.contains("\"ability\": \"Drizzle\""));
+    // Unset fields are left out
+    assert!(!json.contains("\"level\""));
+    let from_json = Team::from_json(&json).unwrap();
+    assert_eq!(from_json.serialize(), team.serialize());
+
+    let toml = team.to_toml().unwrap();
+    assert!(toml.contains("[[pokemon]]"));
+    let from_toml = Team::from_toml(&toml).unwrap();
+    assert_eq!(from_toml.serialize(), team.serialize());
+    assert_eq!(from_toml.pokemon[0].ivs.as_ref().unwrap().atk, 0);
+
+    // Hand-written sets only need a name
+    let minimal =
+        Team::from_json(r#"{"pokemon": [{"name": "Gengar", "moves": ["Shadow Ball"]}]}"#).unwrap();
+    assert_eq!(minimal.pokemon[0].evs, EVs::default());
+    assert_eq!(minimal.pokemon[0].moves, vec!["Shadow Ball"]);
+}
+
+#[tokio::test]
+async fn markdown_and_html() {
+    let team = Team::deserialize_from_file("./teams/GEN_V.txt")
+        .await
+        .unwrap();
+
+    let markdown = team.to_markdown("Gen 5 Rain");
+    assert!(markdown.starts_with("# Gen 5 Rain\n"));
+    assert!(markdown.contains("## Politoed (M) @ Leftovers\n*Water*\n"));
+    assert!(markdown.contains("- **EVs:** 248 HP / 112 Def / 140 SpD / 8 Spe\n"));
+    assert!(markdown.contains("- **IVs:** 0 Atk\n"));
+    assert!(markdown.contains("1. Rapid Spin\n"));
+
+    let html = team.to_html("Gen 5 <Rain>");
+    assert!(html.contains("<title>Gen 5 &lt;Rain&gt;</title>"));
+    assert!(html.contains(".type-water { color: #6890F0;"));
+    // Ferrothorn's header blends Grass and Steel
+    assert!(
+        html.contains("background: linear-gradient(90deg, #78C850, #B8B8D0)\">Ferrothorn</h2>")
+    );
+    assert!(html.contains("<span class=\"type-ice\">-</span> Hidden Power [Ice]"));
+    assert!(html.contains("<span class=\"stat-spa\">252 SpA</span>"));
+    assert_eq!(html.matches("<article>").count(), 6);
+}
@@ -0,0 +1,118 @@
+use pokebrains::Team;
+use pokebrains::analysis::{Archetype, Role, Weather, classify_team};
+
+#[tokio::test]
+async fn rain_team_roles() {
+    let team = Team::deserialize_from_file("./teams/GEN_V.txt")
+        .await
+        .unwrap();
+    let profile = classify_team(&team);
+    assert_eq!(profile.archetype, Archetype::Weather(Weather::Rain));
+
+    let roles = |name: &str| {
+        profile
+            .members
+            .iter()
+            .find(|m| m.name == name)
+            .unwrap()
+            .roles
+            .clone()
+    };
+    assert_eq!(roles("Politoed"), vec![Role::WeatherSetter, Role::Wall]);
+    assert_eq!(roles("Tentacruel"), vec![Role::HazardRemover, Role::Wall]);
+    assert_eq!(roles("Ferrothorn"), vec![Role::HazardSetter, Role::Wall]);
+    assert_eq!(roles("Keldeo-Resolute"), vec![Role::RevengeKiller]);
+    assert_eq!(roles("Latios"), vec![Role::Wallbreaker]);
+    assert_eq!(profile.missing, vec![Role::Pivot]);
+
+    let text = profile.to_string();
+    assert!(text.starts_with("Archetype: Rain"));
+    assert!(text.contains("Tentacruel: Spinner/Defogger, Wall"));
+}
+
+#[tokio::test]
+async fn offense_and_stall() {
+    let offense = Team::deserialize(
+        "#\
+Garchomp @ Life Orb
+Ability: Rough Skin
+EVs: 252 Atk / 4 Def / 252 Spe
+- Swords Dance
+- Earthquake
+- Stone Edge
+- Stealth Rock
+
+Azumarill @ Choice Band
+Ability: Huge Power
+EVs: 252 HP / 252 Atk / 4 Spe
+- Play Rough
+- Aqua Tail
+- Ice Punch
+- Knock Off
+
+Gengar @ Choice Specs
+Ability: Cursed Body
+EVs: 252 SpA / 4 SpD / 252 Spe
+- Shadow Ball
+- Sludge Bomb
+- Focus Blast
+- Trick
+
+Scizor @ Choice Band
+Ability: Technician
+EVs: 252 HP / 252 Atk / 4 SpD
+- Bullet Punch
+- U-turn
+- Knock Off
+- Superpower
+",
+    )
+    .await;
+    let profile = classify_team(&offense);
+    assert_eq!(profile.archetype, Archetype::HyperOffense);
+    assert!(profile.members[0].has(Role::SetupSweeper));
+    assert!(profile.members[0].has(Role::HazardSetter));
+    assert!(profile.members[1].has(Role::Wallbreaker));
+    assert!(profile.members[3].has(Role::Pivot));
+
+    let stall = Team::deserialize(
+        "#\
+Blissey @ Leftovers
+Ability: Natural Cure
+EVs: 252 HP / 252 Def / 4 SpD
+- Soft-Boiled
+- Seismic Toss
+- Heal Bell
+- Toxic
+
+Skarmory @ Leftovers
+Ability: Sturdy
+EVs: 252 HP / 252 Def / 4 SpD
+- Spikes
+- Roost
+- Whirlwind
+- Defog
+
+Gliscor @ Toxic Orb
+Ability: Poison Heal
+EVs: 244 HP / 248 Def / 16 Spe
+- Earthquake
+- Protect
+- Toxic
+- Roost
+
+Toxapex @ Black Sludge
+Ability: Regenerator
+EVs: 252 HP / 252 Def / 4 SpD
+- Scald
+- Recover
+- Haze
+- Toxic Spikes
+",
+    )
+    .await;
+    let profile = classify_team(&stall);
+    assert_eq!(profile.archetype, Archetype::Stall);
+    assert!(profile.members[0].has(Role::Cleric));
+    assert!(profile.members[1].has(Role::HazardRemover));
+}
@@ -0,0 +1,111 @@
+use pokebrains::Team;
+use pokebrains::format::{Clause, Format};
+use pokebrains::validator::validate_structure;
+
+static BROKEN_TEAM: &str = "#\
+Garchomp @ Choice Scarf
+Ability: Rough Skin
+EVs: 252 HP / 252 Atk / 252 Spe
+Starmask Nature
+- Outrage
+- Earthquake
+- Earthquake
+- Stone Edge
+- Fire Fang
+
+Garchomp @ Choice Scarf
+Ability: Rough Skin
+Level: 120
+EVs: 300 Atk / 4 Def / 200 Spe
+IVs: 40 Spe
+Jolly Nature
+- Outrage
+";
+
+#[tokio::test]
+async fn reports_every_problem_at_once() {
+    let team = Team::deserialize(BROKEN_TEAM).await;
+    let problems = validate_structure(&team, &Format::from_id("gen5ou"));
+    let messages: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+
+    for m in &messages {
+        println!("{}", m);
+    }
+
+    let expect = |needle: &str| {
+        assert!(
+            messages.iter().any(|m| m.contains(needle)),
+            "missing problem containing '{}'",
+            needle
+        )
+    };
+    expect("756 total EVs");
+    expect("Starmask is not a valid nature");
+    expect("has Earthquake 2 times");
+    expect("has 5 moves");
+    expect("300 EVs in Atk");
+    expect("40 IVs in Spe");
+    expect("level 120");
+    expect("Species Clause");
+    // Item clause is not part of gen5ou
+    assert!(!messages.iter().any(|m| m.contains("Item Clause")));
+}
+
+#[tokio::test]
+async fn valid_team_has_no_problems() {
+    let team = Team::deserialize_from_file("./teams/GEN_V.txt")
+        .await
+        .unwrap();
+    let problems = validate_structure(&team, &Format::from_id("gen5ou"));
+    assert!(problems.is_empty(), "{:?}", problems);
+}
+
+#[tokio::test]
+async fn vgc_enforces_item_clause_and_level_cap() {
+    let team = Team::deserialize_from_file("./teams/GEN_V.txt")
+        .await
+        .unwrap();
+    let format = Format::from_id("gen9vgc2024regg");
+    assert_eq!(format.r#gen, 9);
+    assert_eq!(format.max_level, 50);
+    assert!(format.has_clause(Clause::Item));
+
+    let problems = validate_structure(&team, &format);
+    // Two Leftovers and six Pokémon at the default level 100
+    assert!(problems.iter().any(|p| p.message.contains("Leftovers")));
+    assert_eq!(
+        problems
+            .iter()
+            .filter(|p| p.message.contains("level 100"))
+            .count(),
+        6
+    );
+}
+
+#[test]
+fn format_ids_are_parsed() {
+    let format = Format::from_id("[Gen 8] LC");
+    assert_eq!(format.r#gen, 8);
+    assert_eq!(format.tier, "lc");
+    assert_eq!(format.max_level, 5);
+
+    let format = Format::from_id("gen2ou");
+    assert_eq!(format.r#gen, 2);
+    assert_eq!(format.max_team_size, 6);
+}
+
+#[tokio::test]
+async fn gen2_rejects_natures_and_abilities() {
+    let team = Team::deserialize(
+        "#\
+Snorlax @ Leftovers
+Ability: Thick Fat
+Careful Nature
+- Curse
+- Rest
+",
+    )
+    .await;
+    let problems = validate_structure(&team, &Format::from_id("gen2ou"));
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+}
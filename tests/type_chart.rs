@@ -0,0 +1,126 @@
+use pokebrains::Team;
+use pokebrains::damage::{Combatant, Field, calculate};
+use pokebrains::types::{Matchup, Type, effectiveness_in, type_effectiveness_in};
+
+use Type::*;
+
+#[test]
+fn charts_change_between_generations() {
+    assert_eq!(effectiveness_in(1, Bug, Poison), 2.0);
+    assert_eq!(effectiveness_in(1, Poison, Bug), 2.0);
+    assert_eq!(effectiveness_in(1, Ghost, Psychic), 0.0);
+    assert_eq!(effectiveness_in(1, Ice, Fire), 1.0);
+    assert_eq!(effectiveness_in(2, Ghost, Psychic), 2.0);
+    assert_eq!(effectiveness_in(2, Ice, Fire), 0.5);
+
+    assert_eq!(effectiveness_in(4, Ghost, Steel), 0.5);
+    assert_eq!(effectiveness_in(5, Dark, Steel), 0.5);
+    assert_eq!(effectiveness_in(6, Dark, Steel), 1.0);
+    // Fairy is neutral until it exists
+    assert_eq!(effectiveness_in(5, Dragon, Fairy), 1.0);
+    assert_eq!(effectiveness_in(6, Dragon, Fairy), 0.0);
+
+    assert_eq!(type_effectiveness_in(5, Ghost, &[Steel, Flying]), 0.5);
+    assert_eq!(type_effectiveness_in(9, Ghost, &[Steel, Flying]), 1.0);
+}
+
+#[test]
+fn abilities_items_and_moves() {
+    let levitate = Matchup {
+        defender_ability: Some("Levitate".to_string()),
+        ..Matchup::new(9)
+    };
+    assert_eq!(levitate.multiplier(Ground, &[Ghost, Poison]), 0.0);
+    assert_eq!(
+        levitate.immunity(Ground, &[Ghost, Poison]),
+        Some("Levitate")
+    );
+    let mold_breaker = Matchup {
+        attacker_ability: Some("Mold Breaker".to_string()),
+        ..levitate.clone()
+    };
+    assert_eq!(mold_breaker.multiplier(Ground, &[Ghost, Poison]), 2.0);
+
+    // Storm Drain only grants an immunity from Gen 5
+    let storm_drain = |r#gen| Matchup {
+        defender_ability: Some("Storm Drain".to_string()),
+        ..Matchup::new(r#gen)
+    };
+    assert_eq!(storm_drain(4).multiplier(Water, &[Poison, Dark]), 1.0);
+    assert_eq!(storm_drain(5).multiplier(Water, &[Poison, Dark]), 0.0);
+
+    let balloon = Matchup {
+        defender_item: Some("Air Balloon".to_string()),
+        ..Matchup::new(9)
+    };
+    assert_eq!(balloon.immunity(Ground, &[Steel]), Some("Air Balloon"));
+    let arrows = Matchup {
+        move_name: Some("Thousand Arrows".to_string()),
+        ..balloon
+    };
+    assert_eq!(arrows.multiplier(Ground, &[Steel, Flying]), 2.0);
+
+    let freeze_dry = Matchup {
+        move_name: Some("Freeze-Dry".to_string()),
+        ..Matchup::new(9)
+    };
+    assert_eq!(freeze_dry.multiplier(Ice, &[Water, Flying]), 4.0);
+    assert_eq!(freeze_dry.multiplier(Ice, &[Water, Ground]), 4.0);
+
+    let flying_press = Matchup {
+        move_name: Some("Flying Press".to_string()),
+        ..Matchup::new(9)
+    };
+    assert_eq!(flying_press.multiplier(Fighting, &[Bug, Fighting]), 2.0);
+
+    let scrappy = Matchup {
+        attacker_ability: Some("Scrappy".to_string()),
+        ..Matchup::new(9)
+    };
+    assert_eq!(scrappy.multiplier(Normal, &[Ghost, Poison]), 1.0);
+    assert_eq!(scrappy.multiplier(Normal, &[Ghost, Steel]), 0.5);
+
+    let wonder_guard = Matchup {
+        defender_ability: Some("Wonder Guard".to_string()),
+        ..Matchup::new(9)
+    };
+    assert_eq!(wonder_guard.multiplier(Normal, &[Bug, Ghost]), 0.0);
+    assert_eq!(wonder_guard.multiplier(Fire, &[Bug, Ghost]), 2.0);
+
+    let tera = Matchup {
+        tera_type: Some(Fairy),
+        ..Matchup::new(9)
+    };
+    assert_eq!(tera.multiplier(Dragon, &[Dragon, Flying]), 0.0);
+    let chart = tera.chart(&[Dragon, Flying]);
+    assert!(chart.contains(&(Steel, 2.0)) && chart.contains(&(Ice, 1.0)));
+    assert_eq!(Matchup::new(5).chart(&[Normal]).len(), 17);
+}
+
+#[test]
+fn the_damage_calculator_uses_the_chart() {
+    let pokemon = |json: &str| {
+        Combatant::from(
+            Team::from_json(&format!(r#"{{"pokemon": [{}]}}"#, json))
+                .unwrap()
+                .pokemon
+                .remove(0),
+        )
+    };
+    // Any Normal type will do; the calculator doesn't check the ability is legal
+    let snorlax = pokemon(r#"{"name": "Snorlax", "ability": "Scrappy"}"#);
+    let gengar = pokemon(r#"{"name": "Gengar", "ability": "Cursed Body"}"#);
+    let field = Field::default();
+    assert!(
+        calculate(9, &snorlax, &gengar, "Body Slam", &field)
+            .unwrap()
+            .max()
+            > 0
+    );
+
+    // Steel resisted Ghost through Gen 5
+    let skarmory = pokemon(r#"{"name": "Skarmory", "ability": "Sturdy"}"#);
+    let gen5 = calculate(5, &gengar, &skarmory, "Shadow Ball", &field).unwrap();
+    let gen6 = calculate(6, &gengar, &skarmory, "Shadow Ball", &field).unwrap();
+    assert!(gen5.max() * 2 <= gen6.max() + 1);
+}
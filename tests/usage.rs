@@ -0,0 +1,96 @@
+use pokebrains::usage::{UsageDex, UsageStats};
+
+static CHAOS: &str = r#"{
+  "info": {"metagame": "gen9ou", "cutoff": 1695, "cutoff deviation": 0, "team type": null,
+           "number of battles": 1000},
+  "data": {
+    "Great Tusk": {
+      "Raw count": 400,
+      "usage": 0.35,
+      "Viability Ceiling": [80, 2000, 1900, 1850],
+      "Abilities": {"protosynthesis": 300.0},
+      "Items": {"boosterenergy": 150.0, "leftovers": 120.0, "nothing": 30.0},
+      "Spreads": {"Jolly:0/252/4/0/0/252": 180.0, "Impish:252/0/216/0/0/40": 90.0, "Other": 30.0},
+      "Moves": {"rapidspin": 280.0, "headlongrush": 270.0, "icespinner": 200.0, "knockoff": 150.0,
+                "stealthrock": 140.0, "bulkup": 60.0, "": 0.0},
+      "Tera Types": {"steel": 120.0, "water": 90.0, "ground": 90.0},
+      "Happiness": {"255": 300.0},
+      "Teammates": {"Kingambit": 90.0, "Gholdengo": 120.0},
+      "Checks and Counters": {
+        "Corviknight": [120.0, 0.62, 0.04],
+        "Dragapult": [150.0, 0.58, 0.01]
+      }
+    },
+    "Kingambit": {
+      "Raw count": 500,
+      "usage": 0.41,
+      "Abilities": {"supremeoverlord": 380.0, "defiant": 20.0},
+      "Items": {"leftovers": 200.0, "blackglasses": 200.0},
+      "Spreads": {"Adamant:252/252/0/0/4/0": 400.0},
+      "Moves": {"kowtowcleave": 390.0, "suckerpunch": 380.0, "swordsdance": 350.0},
+      "Teammates": {"Great Tusk": 100.0},
+      "Checks and Counters": {}
+    }
+  }
+}"#;
+
+#[test]
+fn parse_chaos_file() {
+    let stats = UsageStats::from_json(CHAOS).unwrap();
+    assert_eq!(stats.format, "gen9ou");
+    assert_eq!(stats.cutoff, 1695);
+    assert_eq!(stats.battles, 1000);
+
+    let ranked: Vec<&str> = stats.ranked().iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(ranked, vec!["Kingambit", "Great Tusk"]);
+
+    let tusk = stats.get("greattusk").unwrap();
+    assert_eq!(tusk.raw_count, 400);
+    assert_eq!(tusk.abilities, vec![("Protosynthesis".to_string(), 1.0)]);
+    // "nothing" is left out, names come from the dex
+    assert_eq!(tusk.items[0], ("Booster Energy".to_string(), 0.5));
+    assert_eq!(tusk.items.len(), 2);
+    assert_eq!(tusk.moves[0].0, "Rapid Spin");
+    assert!((tusk.move_share("Knock Off") - 0.5).abs() < 1e-9);
+    assert_eq!(tusk.move_share("Earthquake"), 0.0);
+    assert_eq!(tusk.tera_types[0], ("Steel".to_string(), 0.4));
+    assert_eq!(tusk.teammates[0].0, "Gholdengo");
+    assert!((tusk.teammate_share("Kingambit") - 0.3).abs() < 1e-9);
+
+    assert_eq!(tusk.spreads.len(), 2);
+    assert_eq!(tusk.spreads[0].nature, "Jolly");
+    assert_eq!(tusk.spreads[0].evs.spe, 252);
+    assert!((tusk.spreads[0].share - 0.6).abs() < 1e-9);
+
+    // Dragapult's 0.58 - 4 * 0.01 beats Corviknight's 0.62 - 4 * 0.04
+    let counters: Vec<&str> = tusk.counters.iter().map(|c| c.species.as_str()).collect();
+    assert_eq!(counters, vec!["Dragapult", "Corviknight"]);
+}
+
+#[test]
+fn lookups_by_format() {
+    let mut usage = UsageDex::default();
+    usage.insert(UsageStats::from_json(CHAOS).unwrap());
+    // A lower cutoff of the same month doesn't replace the higher one
+    usage.insert(UsageStats::from_json(&CHAOS.replace("1695", "0")).unwrap());
+
+    assert_eq!(usage.format("gen9ou").unwrap().cutoff, 1695);
+    let kingambit = usage.species("gen9ou", "Kingambit").unwrap();
+    assert!((kingambit.ability_share("Supreme Overlord") - 0.95).abs() < 1e-9);
+    assert!(usage.species("gen9ou", "Pikachu").is_err());
+    assert!(usage.species("gen8ou", "Kingambit").is_err());
+}
+
+#[test]
+fn a_bad_file_is_skipped() {
+    let dir = std::env::temp_dir().join(format!("pokebrains-usage-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("gen9ou-1695.json"), CHAOS).unwrap();
+    std::fs::write(dir.join("gen9uu-1695.json"), "{\"info\":").unwrap();
+
+    let mut usage = UsageDex::default();
+    usage.load_dir(&dir).unwrap();
+    assert!(usage.format("gen9ou").is_some());
+    assert!(usage.format("gen9uu").is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}